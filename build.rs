@@ -0,0 +1,49 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Embed info.toml and the exercises into the binary, so that `rustlings init`
+// can set them up even when rustlings was installed without cloning the repository.
+fn main() {
+    println!("cargo:rerun-if-changed=info.toml");
+    println!("cargo:rerun-if-changed=exercises");
+    println!("cargo:rerun-if-changed=hidden_tests");
+
+    let mut files = vec![PathBuf::from("info.toml")];
+    collect_files(Path::new("exercises"), &mut files);
+    // Hidden tests are set up along with the exercises they belong to
+    if Path::new("hidden_tests").is_dir() {
+        collect_files(Path::new("hidden_tests"), &mut files);
+    }
+    files.sort();
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut embedded = String::from("pub static EMBEDDED_FILES: &[(&str, &[u8])] = &[\n");
+    for file in files {
+        let name = file.to_string_lossy().replace('\\', "/");
+        let source = manifest_dir.join(&file);
+        embedded.push_str(&format!("    ({name:?}, include_bytes!({source:?})),\n"));
+    }
+    embedded.push_str("];\n");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("embedded.rs"), embedded).unwrap();
+}
+
+// Collect the sources and READMEs of the exercises, skipping anything
+// generated while working on them, like the clippy Cargo.toml and target folder
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().unwrap() != "target" {
+                collect_files(&path, files);
+            }
+        } else if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("rs") | Some("md")
+        ) {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+// The property testing harness exercises with `mode = "property"` are
+// linked against, as the `rustlings_property` crate. It's a small take on
+// quickcheck: properties are checked against many generated inputs, and a
+// failing input is shrunk to a simpler one before it's reported.
+//
+//     use rustlings_property::check;
+//
+//     #[test]
+//     fn reversing_twice_gives_the_original() {
+//         check(|v: Vec<i32>| reverse(&reverse(&v)) == v);
+//     }
+//
+// The number of cases is set with `RUSTLINGS_PROPERTY_CASES`, and the seed
+// of a failing run can be passed in `RUSTLINGS_PROPERTY_SEED` to repeat it.
+
+use std::cell::Cell;
+use std::env;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// How many inputs a property is checked against by default
+pub const DEFAULT_CASES: usize = 256;
+// How large generated collections and numbers get at most
+pub const MAX_SIZE: usize = 100;
+
+// A source of random values, growing in size as more cases are checked
+pub struct Gen {
+    state: u64,
+    size: usize,
+}
+
+impl Gen {
+    pub fn new(seed: u64) -> Gen {
+        Gen {
+            // The state of xorshift must never be zero
+            state: seed | 1,
+            size: MAX_SIZE,
+        }
+    }
+
+    // How large values should be, from 0 up to `MAX_SIZE`
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // A number in the given range, which must not be empty
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(range.start < range.end, "the range {range:?} is empty");
+        let width = range.end.wrapping_sub(range.start) as u64;
+        range.start.wrapping_add((self.next_u64() % width) as i64)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    // An arbitrary value of the given type
+    pub fn gen<T: Arbitrary>(&mut self) -> T {
+        T::arbitrary(self)
+    }
+
+    // Up to `size` values made by the given generator
+    pub fn vec<T>(&mut self, mut generate: impl FnMut(&mut Gen) -> T) -> Vec<T> {
+        let len = self.range(0..self.size as i64 + 1) as usize;
+        (0..len).map(|_| generate(self)).collect()
+    }
+
+    // One of the given items, which must not be empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len() as i64) as usize]
+    }
+}
+
+// Types that values can be generated for, and shrunk to simpler values
+pub trait Arbitrary: Clone + Debug + 'static {
+    fn arbitrary(g: &mut Gen) -> Self;
+
+    // Simpler values to try when this one makes a property fail
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+impl Arbitrary for bool {
+    fn arbitrary(g: &mut Gen) -> bool {
+        g.bool()
+    }
+
+    fn shrink(&self) -> Vec<bool> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+macro_rules! arbitrary_integer {
+    ($($ty:ty),*) => {$(
+        impl Arbitrary for $ty {
+            fn arbitrary(g: &mut Gen) -> $ty {
+                // Mostly small numbers, and now and then one from the whole range
+                if g.range(0..10) == 0 {
+                    g.next_u64() as $ty
+                } else {
+                    let size = g.size() as i64;
+                    let low = if <$ty>::MIN == 0 { 0 } else { -size };
+                    g.range(low..size + 1) as $ty
+                }
+            }
+
+            // Towards zero
+            #[allow(unused_comparisons)]
+            fn shrink(&self) -> Vec<$ty> {
+                let mut simpler = Vec::new();
+                if *self != 0 {
+                    simpler.push(0);
+                }
+                if *self < 0 && *self != <$ty>::MIN {
+                    simpler.push(0 - *self);
+                }
+                let mut delta = *self / 2;
+                while delta != 0 {
+                    let candidate = *self - delta;
+                    if !simpler.contains(&candidate) {
+                        simpler.push(candidate);
+                    }
+                    delta /= 2;
+                }
+                simpler
+            }
+        }
+    )*};
+}
+
+arbitrary_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Arbitrary for char {
+    fn arbitrary(g: &mut Gen) -> char {
+        // Mostly printable ASCII, and now and then any character
+        if g.range(0..10) == 0 {
+            char::from_u32(g.range(0..0x11_0000) as u32).unwrap_or('?')
+        } else {
+            g.range(0x20..0x7f) as u8 as char
+        }
+    }
+
+    fn shrink(&self) -> Vec<char> {
+        ['a', 'b', ' '].into_iter().filter(|c| c < self).collect()
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(g: &mut Gen) -> String {
+        g.vec(char::arbitrary).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Vec<String> {
+        let chars: Vec<char> = self.chars().collect();
+        chars
+            .shrink()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(g: &mut Gen) -> Vec<T> {
+        g.vec(T::arbitrary)
+    }
+
+    // Without half of the elements, without single elements,
+    // and with single elements shrunk
+    fn shrink(&self) -> Vec<Vec<T>> {
+        let mut simpler = Vec::new();
+        if !self.is_empty() {
+            simpler.push(Vec::new());
+        }
+        if self.len() > 1 {
+            let half = self.len() / 2;
+            simpler.push(self[..half].to_vec());
+            simpler.push(self[half..].to_vec());
+        }
+        for i in 0..self.len() {
+            let mut without = self.clone();
+            without.remove(i);
+            simpler.push(without);
+        }
+        for (i, element) in self.iter().enumerate() {
+            for shrunk in element.shrink() {
+                let mut with = self.clone();
+                with[i] = shrunk;
+                simpler.push(with);
+            }
+        }
+        simpler
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(g: &mut Gen) -> Option<T> {
+        if g.range(0..4) == 0 {
+            None
+        } else {
+            Some(T::arbitrary(g))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Option<T>> {
+        match self {
+            None => Vec::new(),
+            Some(value) => std::iter::once(None)
+                .chain(value.shrink().into_iter().map(Some))
+                .collect(),
+        }
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary> Arbitrary for (A, B) {
+    fn arbitrary(g: &mut Gen) -> (A, B) {
+        (A::arbitrary(g), B::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<(A, B)> {
+        let (a, b) = self;
+        let mut simpler: Vec<(A, B)> = a.shrink().into_iter().map(|a| (a, b.clone())).collect();
+        simpler.extend(b.shrink().into_iter().map(|b| (a.clone(), b)));
+        simpler
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary, C: Arbitrary> Arbitrary for (A, B, C) {
+    fn arbitrary(g: &mut Gen) -> (A, B, C) {
+        (A::arbitrary(g), B::arbitrary(g), C::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<(A, B, C)> {
+        let (a, b, c) = self;
+        let mut simpler: Vec<(A, B, C)> = a
+            .shrink()
+            .into_iter()
+            .map(|a| (a, b.clone(), c.clone()))
+            .collect();
+        simpler.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone())));
+        simpler.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c)));
+        simpler
+    }
+}
+
+// What a property returns: whether it holds, or nothing if it asserts instead
+pub trait Outcome {
+    fn holds(self) -> bool;
+}
+
+impl Outcome for bool {
+    fn holds(self) -> bool {
+        self
+    }
+}
+
+impl Outcome for () {
+    fn holds(self) -> bool {
+        true
+    }
+}
+
+// Check that the property holds for arbitrary inputs, panicking with the
+// simplest failing input that was found otherwise
+pub fn check<T: Arbitrary, O: Outcome>(property: impl Fn(T) -> O) {
+    let failure = run(T::arbitrary, &property);
+    if let Some((seed, case, input)) = failure {
+        let shrunk = shrink(input.clone(), &property);
+        fail(seed, case, &input, Some(&shrunk));
+    }
+}
+
+// Check that the property holds for the inputs of the given generator.
+// Those inputs can't be shrunk, so the input is reported as it failed.
+pub fn check_with<T: Clone + Debug, O: Outcome>(
+    generate: impl Fn(&mut Gen) -> T,
+    property: impl Fn(T) -> O,
+) {
+    if let Some((seed, case, input)) = run(generate, &property) {
+        fail(seed, case, &input, None);
+    }
+}
+
+// The seed, case number and input of the first case the property fails for
+fn run<T: Clone + Debug, O: Outcome>(
+    mut generate: impl FnMut(&mut Gen) -> T,
+    property: &impl Fn(T) -> O,
+) -> Option<(u64, usize, T)> {
+    let seed = env::var("RUSTLINGS_PROPERTY_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        });
+    let cases = env::var("RUSTLINGS_PROPERTY_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(DEFAULT_CASES)
+        .max(1);
+
+    let mut g = Gen::new(seed);
+    for case in 0..cases {
+        // Simple inputs come first, so that they fail first
+        g.size = case * MAX_SIZE / cases;
+        let input = generate(&mut g);
+        if !holds(property, input.clone()) {
+            return Some((seed, case + 1, input));
+        }
+    }
+    None
+}
+
+// The simplest input the property still fails for
+fn shrink<T: Arbitrary, O: Outcome>(mut input: T, property: &impl Fn(T) -> O) -> T {
+    // Shrinking stops eventually anyway, but this keeps it from taking too long
+    for _ in 0..1000 {
+        let simpler = input
+            .shrink()
+            .into_iter()
+            .find(|simpler| !holds(property, simpler.clone()));
+        match simpler {
+            Some(simpler) => input = simpler,
+            None => break,
+        }
+    }
+    input
+}
+
+fn fail<T: Debug>(seed: u64, case: usize, input: &T, shrunk: Option<&T>) -> ! {
+    let shrunk = shrunk.filter(|shrunk| format!("{shrunk:?}") != format!("{input:?}"));
+    match shrunk {
+        Some(shrunk) => panic!(
+            "The property doesn't hold for {shrunk:?}\n\
+             It first failed for {input:?} in case {case}.\n\
+             Run again with RUSTLINGS_PROPERTY_SEED={seed} to repeat this."
+        ),
+        None => panic!(
+            "The property doesn't hold for {input:?}, which failed in case {case}.\n\
+             Run again with RUSTLINGS_PROPERTY_SEED={seed} to repeat this."
+        ),
+    }
+}
+
+thread_local! {
+    // Set while checking a property, to keep its panics from being printed
+    static CHECKING: Cell<bool> = const { Cell::new(false) };
+}
+
+// Whether the property holds for the input, counting panics as not holding
+fn holds<T, O: Outcome>(property: &impl Fn(T) -> O, input: T) -> bool {
+    static QUIET_HOOK: Once = Once::new();
+    QUIET_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CHECKING.with(Cell::get) {
+                default_hook(info);
+            }
+        }));
+    });
+
+    CHECKING.with(|checking| checking.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| property(input).holds()));
+    CHECKING.with(|checking| checking.set(false));
+    result.unwrap_or(false)
+}
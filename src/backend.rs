@@ -0,0 +1,384 @@
+// Building exercises with cargo instead of rustc, with `backend = "cargo"`
+// in rustlings.toml. Every exercise gets a package of its own with a manifest
+// pointing at its source, and all of them share a target directory, so that
+// cargo compiles them incrementally and builds the crates in their `deps`
+// itself. Compile, fmt, test and bench exercises are built this way. The
+// binaries are still run by rustlings, so that the limits and the sandbox
+// apply to them. Project exercises are cargo packages already, which are built
+// the same way whatever the backend.
+use crate::config::CONFIG;
+use crate::exercise::{COMPILE_CACHE_DIR, RUSTC_EDITION_ARGS};
+use crate::limits::Limits;
+use crate::output::LoggedCommand;
+use serde::Deserialize;
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// What exercises are compiled with
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Rustc,
+    Cargo,
+}
+
+pub fn backend() -> Backend {
+    CONFIG.backend.unwrap_or(Backend::Rustc)
+}
+
+// How an exercise is built
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Profile {
+    // A binary, like with `cargo build`
+    Dev,
+    // A test harness, like with `cargo test --no-run`
+    Test,
+    // A binary with optimizations, like with `cargo build --release`
+    Release,
+}
+
+impl Profile {
+    fn target(self) -> Target {
+        match self {
+            Profile::Dev | Profile::Release => Target::Bin,
+            Profile::Test => Target::Test,
+        }
+    }
+}
+
+// What kind of crate the exercise is to cargo
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Target {
+    Bin,
+    Test,
+    Lib,
+}
+
+// A message of `cargo --message-format=json`
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    // The diagnostic of rustc, for compiler messages
+    #[serde(default)]
+    message: Option<Value>,
+    #[serde(default)]
+    executable: Option<PathBuf>,
+    // What was built, for artifacts
+    #[serde(default)]
+    target: Option<ArtifactTarget>,
+    #[serde(default)]
+    profile: Option<ArtifactProfile>,
+}
+
+#[derive(Deserialize)]
+struct ArtifactTarget {
+    // Like `lib`, `bin` or `test`
+    kind: Vec<String>,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ArtifactProfile {
+    // Whether the artifact is a test harness
+    test: bool,
+}
+
+// The package building a single exercise
+pub struct Package<'a> {
+    pub name: &'a str,
+    // The file that's compiled, which may be a copy with hidden tests
+    pub source: &'a Path,
+    pub deps: &'a [String],
+}
+
+impl Package<'_> {
+    // Build the exercise with the given cargo command, copying the binary to
+    // `binary` if that worked. Like with rustc, the diagnostics are in stderr
+    // as JSON, along with anything cargo itself complained about.
+    pub fn build(
+        &self,
+        mut cargo: Command,
+        profile: Profile,
+        binary: &Path,
+        limits: &Limits,
+    ) -> io::Result<Output> {
+        match profile {
+            Profile::Dev => cargo.arg("build"),
+            Profile::Test => cargo.args(["test", "--no-run"]),
+            Profile::Release => cargo.args(["build", "--release"]),
+        };
+        let (output, messages) = self.run(cargo, profile.target(), &[], limits)?;
+        let mut stderr = diagnostics_in(&messages);
+        if output.status.success() {
+            let executable = messages
+                .iter()
+                .filter(|message| message.reason == "compiler-artifact")
+                .find_map(|message| message.executable.as_ref())
+                .ok_or_else(|| io::Error::other("cargo didn't build an executable"))?;
+            fs::copy(executable, binary)?;
+        } else if stderr.is_empty() {
+            // Like when a crate in `deps` doesn't exist
+            stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        }
+        Ok(Output {
+            status: output.status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        })
+    }
+
+    // Lint the exercise with clippy, with the given lint levels on top of the
+    // default ones. Like with `build`, the diagnostics are in stderr as JSON.
+    pub fn lint(
+        &self,
+        mut cargo: Command,
+        target: Target,
+        lints: &[&String],
+        limits: &Limits,
+    ) -> io::Result<Output> {
+        cargo.arg("clippy");
+        // Only libraries and binaries are linted otherwise
+        if target == Target::Test {
+            cargo.arg("--tests");
+        }
+        let (output, messages) = self.run(cargo, target, lints, limits)?;
+        let mut stderr = diagnostics_in(&messages);
+        if !output.status.success() && stderr.is_empty() {
+            stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        }
+        Ok(Output {
+            status: output.status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        })
+    }
+
+    // Write the package and run the cargo command on it, passing the given
+    // arguments on to the compiler, with the messages cargo printed
+    fn run(
+        &self,
+        mut cargo: Command,
+        target: Target,
+        compiler_args: &[&String],
+        limits: &Limits,
+    ) -> io::Result<(Output, Vec<Message>)> {
+        let dir = Path::new(COMPILE_CACHE_DIR).join("cargo");
+        let manifest = dir.join(self.name).join("Cargo.toml");
+        fs::create_dir_all(dir.join(self.name))?;
+        fs::write(&manifest, self.manifest(target)?)?;
+
+        cargo
+            .args(["--message-format=json", "--manifest-path"])
+            .arg(&manifest)
+            .arg("--target-dir")
+            .arg(dir.join("target"))
+            .env("CARGO_ENCODED_RUSTFLAGS", rustflags()?);
+        if !compiler_args.is_empty() {
+            cargo.arg("--").args(compiler_args);
+        }
+        let output = cargo.logged_output_within(limits)?;
+        let messages = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok((output, messages))
+    }
+
+    // The manifest of the package, in a workspace of its own so that it's
+    // never taken for part of another one
+    fn manifest(&self, target: Target) -> io::Result<String> {
+        let (section, name) = match target {
+            Target::Bin => ("[[bin]]", self.name.to_string()),
+            Target::Test => ("[[test]]", self.name.to_string()),
+            // Libraries are used by their crate name
+            Target::Lib => ("[lib]", self.name.replace('-', "_")),
+        };
+        // Relative to the manifest otherwise
+        let source = fs::canonicalize(self.source)?;
+        let mut manifest = format!(
+            "[package]\n\
+             name = \"{}\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"{}\"\n\
+             publish = false\n\n\
+             {section}\n\
+             name = \"{name}\"\n\
+             path = {:?}\n\n\
+             [workspace]\n\n\
+             [dependencies]\n",
+            self.name,
+            RUSTC_EDITION_ARGS[1],
+            source.to_string_lossy()
+        );
+        for name in self.deps {
+            manifest.push_str(&format!("{name} = \"*\"\n"));
+        }
+        Ok(manifest)
+    }
+}
+
+// A project exercise, which is a cargo package with a Cargo.toml of its own
+pub struct Project<'a> {
+    // The directory of the package
+    pub dir: &'a Path,
+}
+
+impl Project<'_> {
+    // Build the test harnesses of the package with the given cargo command,
+    // like `cargo test --no-run`, copying them into the directory `harnesses`
+    // named so that they sort in the order `cargo test` runs them: the
+    // library first, then the binaries, then the tests in `tests`. Like with
+    // packages, the diagnostics are in stderr as JSON.
+    pub fn build_tests(
+        &self,
+        mut cargo: Command,
+        harnesses: &Path,
+        limits: &Limits,
+    ) -> io::Result<Output> {
+        cargo.args(["test", "--no-run"]);
+        let (output, messages) = self.run(cargo, &[], limits)?;
+        let mut stderr = diagnostics_in(&messages);
+        if output.status.success() {
+            let mut built: Vec<(usize, &str, &Path)> = messages
+                .iter()
+                .filter(|message| message.profile.as_ref().is_some_and(|p| p.test))
+                .filter_map(|message| {
+                    let target = message.target.as_ref()?;
+                    let order = ["lib", "bin", "test"]
+                        .iter()
+                        .position(|kind| target.kind.iter().any(|k| k == kind))
+                        .unwrap_or(3);
+                    Some((order, target.name.as_str(), message.executable.as_deref()?))
+                })
+                .collect();
+            built.sort();
+            let _ignored = fs::remove_dir_all(harnesses);
+            fs::create_dir_all(harnesses)?;
+            for (i, (_, _, executable)) in built.iter().enumerate() {
+                let name = executable.file_name().unwrap_or_default().to_string_lossy();
+                fs::copy(executable, harnesses.join(format!("{i:03}-{name}")))?;
+            }
+        } else if stderr.is_empty() {
+            // Like when the Cargo.toml is broken
+            stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        }
+        Ok(Output {
+            status: output.status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        })
+    }
+
+    // Lint all targets of the package with clippy, with the given lint levels
+    // on top of the default ones
+    pub fn lint(
+        &self,
+        mut cargo: Command,
+        lints: &[&String],
+        limits: &Limits,
+    ) -> io::Result<Output> {
+        cargo.args(["clippy", "--all-targets"]);
+        let (output, messages) = self.run(cargo, lints, limits)?;
+        let mut stderr = diagnostics_in(&messages);
+        if !output.status.success() && stderr.is_empty() {
+            stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        }
+        Ok(Output {
+            status: output.status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        })
+    }
+
+    // Run the cargo command on the package, in the target directory the
+    // packages of exercises share, with the messages cargo printed
+    fn run(
+        &self,
+        mut cargo: Command,
+        compiler_args: &[&String],
+        limits: &Limits,
+    ) -> io::Result<(Output, Vec<Message>)> {
+        let target = Path::new(COMPILE_CACHE_DIR).join("cargo").join("target");
+        // Cargo gives rustc the paths of the package relative to its
+        // directory, which are made relative to the current one instead
+        let mut dir = self.dir.as_os_str().to_owned();
+        dir.push(std::path::MAIN_SEPARATOR_STR);
+        let rustflags = format!(
+            "{}\x1f--remap-path-prefix=={}",
+            rustflags()?,
+            dir.to_string_lossy()
+        );
+        cargo
+            .args(["--message-format=json", "--manifest-path"])
+            .arg(self.dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(target)
+            .env("CARGO_ENCODED_RUSTFLAGS", rustflags);
+        if !compiler_args.is_empty() {
+            cargo.arg("--").args(compiler_args);
+        }
+        let output = cargo.logged_output_within(limits)?;
+        let messages = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok((output, messages))
+    }
+}
+
+// The diagnostics of rustc among the messages of cargo, one per line
+fn diagnostics_in(messages: &[Message]) -> String {
+    let mut diagnostics = String::new();
+    for message in messages {
+        if let (Some(diagnostic), "compiler-message") = (&message.message, &*message.reason) {
+            diagnostics.push_str(&format!("{diagnostic}\n"));
+        }
+    }
+    diagnostics
+}
+
+// The flags rustc gets on top of the ones set with RUSTFLAGS. The exercises
+// are outside of their packages, so cargo gives rustc their absolute paths,
+// which are made relative again for diagnostics and panics.
+fn rustflags() -> io::Result<String> {
+    let mut flags: Vec<String> = env::var("RUSTFLAGS")
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let mut prefix = env::current_dir()?.into_os_string();
+    prefix.push(std::path::MAIN_SEPARATOR_STR);
+    flags.push(format!("--remap-path-prefix={}=", prefix.to_string_lossy()));
+    // The separator of CARGO_ENCODED_RUSTFLAGS, which allows spaces in paths
+    Ok(flags.join("\x1f"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let package = Package {
+            name: "hello",
+            source: Path::new("Cargo.toml"),
+            deps: &["rand".to_string()],
+        };
+        let manifest = package.manifest(Profile::Test.target()).unwrap();
+        let source = fs::canonicalize("Cargo.toml").unwrap();
+        assert!(manifest.contains(&format!(
+            "[[test]]\nname = \"hello\"\npath = {:?}\n",
+            source.to_string_lossy()
+        )));
+        assert!(manifest.contains("[workspace]\n"));
+        assert!(manifest.ends_with("[dependencies]\nrand = \"*\"\n"));
+        let manifest = package.manifest(Profile::Release.target()).unwrap();
+        assert!(manifest.contains("[[bin]]\nname = \"hello\""));
+        let manifest = package.manifest(Target::Lib).unwrap();
+        assert!(manifest.contains("[lib]\nname = \"hello\""));
+    }
+}
@@ -0,0 +1,296 @@
+// `rustlings check-metadata` checks info.toml for exercise authors and CI:
+// that the paths of the exercises exist and every exercise file has an
+// entry, that names are unique, modes are valid and hints aren't empty, and
+// that exercises are numbered in the order they come in, and that described
+// topics have exercises. Problems are
+// printed like compiler errors, with the line of info.toml they're at.
+use crate::exercise::{parse_mode, Exercise};
+use crate::topics::Topic;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+// Where an entry of the `exercises` array is in info.toml
+#[derive(Default, Debug)]
+pub struct EntrySpan {
+    // The line of its `[[exercises]]` header, counted from one
+    pub line: usize,
+    // The name it gives, if it gives one as a plain string
+    pub name: Option<String>,
+    // The lines its keys are on, counted from one
+    pub keys: HashMap<String, usize>,
+    // The byte offset right after its last line, leaving out the blank lines
+    // and comments that follow it
+    pub end: usize,
+}
+
+// Where the entries of the `exercises` array are in info.toml, in order.
+// This only looks at the layout, so it works on files that don't parse.
+pub fn entry_spans(info: &str) -> Vec<EntrySpan> {
+    let mut spans: Vec<EntrySpan> = Vec::new();
+    let mut offset = 0;
+    let mut in_string = false;
+    for (i, line) in info.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        let was_in_string = in_string;
+        // Hints are multi-line strings, which may contain anything
+        in_string ^= line.matches("\"\"\"").count() % 2 == 1;
+        offset += line.len();
+        if !was_in_string && trimmed == "[[exercises]]" {
+            spans.push(EntrySpan {
+                line: i + 1,
+                ..EntrySpan::default()
+            });
+        } else if !was_in_string && trimmed.starts_with('[') {
+            // Another table ends the entry
+            break;
+        }
+        let Some(span) = spans.last_mut() else {
+            continue;
+        };
+        if !was_in_string {
+            if let Some((key, value)) = trimmed.split_once('=') {
+                let key = key.trim();
+                if key == "name" {
+                    span.name = Some(value.trim().trim_matches('"').to_string());
+                }
+                span.keys.entry(key.to_string()).or_insert(i + 1);
+            }
+        }
+        if was_in_string || !(trimmed.is_empty() || trimmed.starts_with('#')) {
+            span.end = offset - (line.len() - line.trim_end().len());
+        }
+    }
+    spans
+}
+
+// A problem with info.toml
+struct Problem {
+    // The file the problem is in, and its line if it's info.toml
+    file: PathBuf,
+    line: Option<usize>,
+    message: String,
+}
+
+// Check info.toml and print the problems found. Returns whether there were none.
+pub fn run(info_file: &Path) -> bool {
+    let problems = check(info_file);
+    for problem in &problems {
+        match problem.line {
+            Some(line) => println!("{}:{line}: {}", problem.file.display(), problem.message),
+            None => println!("{}: {}", problem.file.display(), problem.message),
+        }
+    }
+    if problems.is_empty() {
+        success!("{} looks good", info_file.display());
+    } else {
+        println!();
+        warn!(
+            "{}",
+            format!(
+                "Found {} problem{} in {}",
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" },
+                info_file.display()
+            )
+        );
+    }
+    problems.is_empty()
+}
+
+fn check(info_file: &Path) -> Vec<Problem> {
+    let problem = |line: Option<usize>, message: String| Problem {
+        file: info_file.to_path_buf(),
+        line,
+        message,
+    };
+    let info = match fs::read_to_string(info_file) {
+        Ok(info) => info,
+        Err(e) => return vec![problem(None, format!("can't be read: {e}"))],
+    };
+    let parsed: Value = match info.parse() {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let line = e.line_col().map(|(line, _)| line + 1);
+            return vec![problem(line, format!("isn't valid TOML: {e}"))];
+        }
+    };
+    let Some(entries) = parsed.get("exercises").and_then(Value::as_array) else {
+        return vec![problem(None, "has no `exercises` array".to_string())];
+    };
+    let spans = entry_spans(&info);
+    let base = info_file.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut problems = Vec::new();
+    let mut names: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut paths: Vec<PathBuf> = Vec::new();
+    // The highest number seen so far per topic folder and name, like
+    // `exercises/variables` and `variables`, along with its exercise
+    let mut numbering: HashMap<(PathBuf, String), (u32, &str)> = HashMap::new();
+    let mut topics: Vec<String> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let span = spans.get(i);
+        let found = problems.len();
+        let line_of = |key: &str| span.and_then(|s| s.keys.get(key).or(Some(&s.line)).copied());
+
+        let Some(name) = entry.get("name").and_then(Value::as_str) else {
+            problems.push(problem(
+                line_of("name"),
+                "the exercise has no name".to_string(),
+            ));
+            continue;
+        };
+        if let Some(first) = names.insert(name, line_of("name").unwrap_or_default()) {
+            problems.push(problem(
+                line_of("name"),
+                format!("`{name}` is also the name of the exercise at line {first}"),
+            ));
+        }
+
+        match entry.get("path").and_then(Value::as_str) {
+            Some(path) => {
+                let path = Path::new(path);
+                if !base.join(path).exists() {
+                    problems.push(problem(
+                        line_of("path"),
+                        format!("the path of `{name}`, {}, doesn't exist", path.display()),
+                    ));
+                }
+                if let Some(number) = trailing_number(name) {
+                    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+                    let key = (
+                        path.parent().unwrap_or(path).to_path_buf(),
+                        prefix.to_string(),
+                    );
+                    match numbering.get(&key) {
+                        Some(&(highest, previous)) if number < highest => {
+                            problems.push(problem(
+                                line_of("name"),
+                                format!("`{name}` comes after `{previous}`, but is numbered lower"),
+                            ));
+                        }
+                        _ => {
+                            numbering.insert(key, (number, name));
+                        }
+                    }
+                }
+                paths.push(base.join(path));
+            }
+            None => problems.push(problem(line_of("name"), format!("`{name}` has no path"))),
+        }
+
+        match entry.get("mode").and_then(Value::as_str) {
+            Some(mode) => {
+                if let Err(e) = parse_mode(mode) {
+                    problems.push(problem(line_of("mode"), format!("`{name}` has an {e}")));
+                }
+            }
+            None => problems.push(problem(line_of("name"), format!("`{name}` has no mode"))),
+        }
+
+        if !hint_is_given(entry.get("hint")) {
+            problems.push(problem(line_of("hint"), format!("`{name}` needs a hint")));
+        }
+
+        // Anything else that keeps rustlings from loading the exercise
+        if problems.len() == found {
+            match entry.clone().try_into::<Exercise>() {
+                Ok(exercise) => topics.push(exercise.topic()),
+                Err(e) => problems.push(problem(
+                    span.map(|s| s.line),
+                    format!("`{name}` is invalid: {e}"),
+                )),
+            }
+        }
+    }
+
+    // Topic descriptions that no exercise would show
+    let described = parsed.get("topics").and_then(Value::as_array);
+    for entry in described.into_iter().flatten() {
+        match entry.clone().try_into::<Topic>() {
+            Ok(topic) if !topics.contains(&topic.name) => problems.push(problem(
+                None,
+                format!("the topic `{}` has no exercises", topic.name),
+            )),
+            Ok(_) => {}
+            Err(e) => problems.push(problem(None, format!("a topic is invalid: {e}"))),
+        }
+    }
+
+    // Exercise files that no exercise in info.toml points at
+    let mut files = Vec::new();
+    rust_files(&base.join("exercises"), &mut files);
+    files.sort();
+    for file in files {
+        if !paths.iter().any(|path| file.starts_with(path)) {
+            problems.push(Problem {
+                file,
+                line: None,
+                message: "isn't the path of any exercise in info.toml".to_string(),
+            });
+        }
+    }
+    problems
+}
+
+// Whether the hint, or every level of it, has some text
+fn hint_is_given(hint: Option<&Value>) -> bool {
+    match hint {
+        Some(Value::String(hint)) => !hint.trim().is_empty(),
+        Some(Value::Array(levels)) => {
+            !levels.is_empty()
+                && levels
+                    .iter()
+                    .all(|level| level.as_str().is_some_and(|level| !level.trim().is_empty()))
+        }
+        _ => false,
+    }
+}
+
+// The number at the end of the name, like 3 for `variables3`
+fn trailing_number(name: &str) -> Option<u32> {
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    name[prefix.len()..].parse().ok()
+}
+
+// All Rust files in the folder and the folders in it
+fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            rust_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry_spans() {
+        let info = "# INTRO\n\n[[exercises]]\nname = \"intro1\"\npath = \"a.rs\"\nhint = \"\"\"\n[[not a table]]\nname = \"not a key\"\n# nor a comment\"\"\"\n\n# VARIABLES\n\n[[exercises]]\nname = \"variables1\"\nhint = \"\"\n";
+        let spans = entry_spans(info);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].line, 3);
+        assert_eq!(spans[0].name.as_deref(), Some("intro1"));
+        assert_eq!(spans[0].keys["path"], 5);
+        assert_eq!(spans[0].keys["name"], 4);
+        assert!(info[..spans[0].end].ends_with("# nor a comment\"\"\""));
+        assert_eq!(spans[1].name.as_deref(), Some("variables1"));
+        assert_eq!(spans[1].end, info.len() - 1);
+    }
+
+    #[test]
+    fn test_trailing_number() {
+        assert_eq!(trailing_number("variables3"), Some(3));
+        assert_eq!(trailing_number("quiz10"), Some(10));
+        assert_eq!(trailing_number("intro"), None);
+    }
+}
@@ -0,0 +1,161 @@
+// Shell completion scripts for `rustlings completions <shell>`.
+// Exercise names are completed for the commands taking one by asking
+// `rustlings list --names` at completion time, so the scripts work with
+// any info.toml. Lines that aren't exercise names, like the progress summary,
+// are filtered out.
+
+// The shells completion scripts are available for
+#[derive(PartialEq, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+pub fn parse_shell(value: &str) -> Result<Shell, String> {
+    match value {
+        "bash" => Ok(Shell::Bash),
+        "zsh" => Ok(Shell::Zsh),
+        "fish" => Ok(Shell::Fish),
+        "powershell" => Ok(Shell::Powershell),
+        _ => Err(format!(
+            "unknown shell `{value}`, expected `bash`, `zsh`, `fish` or `powershell`"
+        )),
+    }
+}
+
+const SUBCOMMANDS: &[&str] = &[
+    "init",
+    "verify",
+    "watch",
+    "run",
+    "reset",
+    "hint",
+    "next-hint",
+    "about",
+    "search",
+    "list",
+    "lsp",
+    "language-server",
+    "progress",
+    "export-progress",
+    "import-progress",
+    "sync",
+    "stats",
+    "report",
+    "graph",
+    "update",
+    "streak",
+    "completions",
+    "doctor",
+    "grade",
+    "open",
+    "serve",
+    "solution",
+    "review",
+    "exam",
+    "submit",
+    "done",
+    "new-exercise",
+    "check-metadata",
+    "prepare",
+    "lint",
+];
+
+// Subcommands whose positional argument is the name of an exercise
+const EXERCISE_SUBCOMMANDS: &[&str] =
+    &["run", "hint", "about", "reset", "solution", "open", "done"];
+
+const SHELLS: &str = "bash zsh fish powershell";
+
+const BASH: &str = r#"_rustlings() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    if [ "$COMP_CWORD" -eq 1 ]; then
+        COMPREPLY=($(compgen -W "{subcommands}" -- "$cur"))
+        return
+    fi
+    case "${COMP_WORDS[1]}" in
+        {exercise_subcommands})
+            local names="$(rustlings list --names 2>/dev/null | grep -E '^[[:alnum:]_-]+$')"
+            COMPREPLY=($(compgen -W "$names" -- "$cur"))
+            ;;
+        completions)
+            COMPREPLY=($(compgen -W "{shells}" -- "$cur"))
+            ;;
+    esac
+}
+complete -F _rustlings rustlings
+"#;
+
+const ZSH: &str = r#"#compdef rustlings
+
+_rustlings() {
+    if (( CURRENT == 2 )); then
+        compadd -- {subcommands}
+        return
+    fi
+    case "${words[2]}" in
+        {exercise_subcommands})
+            compadd -- ${(f)"$(rustlings list --names 2>/dev/null | grep -E '^[[:alnum:]_-]+$')"}
+            ;;
+        completions)
+            compadd -- {shells}
+            ;;
+    esac
+}
+
+compdef _rustlings rustlings
+"#;
+
+const FISH: &str = r#"function __rustlings_exercises
+    rustlings list --names 2>/dev/null | string match -r '^[[:alnum:]_-]+$'
+end
+
+complete -c rustlings -f
+complete -c rustlings -n __fish_use_subcommand -a "{subcommands}"
+complete -c rustlings -n "__fish_seen_subcommand_from {exercise_subcommands}" -a "(__rustlings_exercises)"
+complete -c rustlings -n "__fish_seen_subcommand_from completions" -a "{shells}"
+"#;
+
+const POWERSHELL: &str = r#"Register-ArgumentCompleter -Native -CommandName rustlings -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements | ForEach-Object { $_.ToString() })
+    # The index of the word being completed
+    $position = $words.Count
+    if ($wordToComplete -ne '') { $position -= 1 }
+    if ($position -eq 1) {
+        $candidates = '{subcommands}' -split ' '
+    } elseif (@({exercise_subcommands}) -contains $words[1]) {
+        $candidates = rustlings list --names 2>$null | Where-Object { $_ -match '^[\w-]+$' }
+    } elseif ($words[1] -eq 'completions') {
+        $candidates = '{shells}' -split ' '
+    } else {
+        $candidates = @()
+    }
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
+"#;
+
+// The completion script for the given shell
+pub fn script(shell: &Shell) -> String {
+    let (template, exercise_subcommands) = match shell {
+        Shell::Bash => (BASH, EXERCISE_SUBCOMMANDS.join("|")),
+        Shell::Zsh => (ZSH, EXERCISE_SUBCOMMANDS.join("|")),
+        Shell::Fish => (FISH, EXERCISE_SUBCOMMANDS.join(" ")),
+        Shell::Powershell => (
+            POWERSHELL,
+            EXERCISE_SUBCOMMANDS
+                .iter()
+                .map(|s| format!("'{s}'"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    };
+    template
+        .replace("{subcommands}", &SUBCOMMANDS.join(" "))
+        .replace("{exercise_subcommands}", &exercise_subcommands)
+        .replace("{shells}", SHELLS)
+}
@@ -0,0 +1,284 @@
+use crate::backend::Backend;
+use crate::output::ColorMode;
+use crate::state::DoneStrategy;
+use crate::sync::SyncBackend;
+use crate::theme::Theme;
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+const CONFIG_FILE_PATH: &str = "rustlings.toml";
+
+// The user's configuration. Preferences can be set for all exercises in
+// rustlings/config.toml in the user's config directory, and for a single
+// rustlings directory in a rustlings.toml file next to info.toml, which takes
+// precedence. Command line flags take precedence over both.
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Whether to color the output
+    pub color: Option<ColorMode>,
+    // The colors telling what passed and what failed
+    pub theme: Option<Theme>,
+    // Whether to only print ASCII characters, without emoji
+    pub ascii: Option<bool>,
+    // Whether to print for screen readers, without output updating in place
+    pub a11y: Option<bool>,
+    // The language of messages and hints, like `es`
+    pub lang: Option<String>,
+    // The command to open exercises with, instead of $VISUAL or $EDITOR
+    pub editor: Option<String>,
+    // How many exercises `verify` compiles concurrently
+    pub jobs: Option<usize>,
+    // Whether hints and solutions are only shown once the exercises
+    // an exercise requires are completed
+    pub enforce_prerequisites: Option<bool>,
+    // How exercises are told to be done: by their marker, by the state file,
+    // or by passing
+    pub done: Option<DoneStrategy>,
+    // Glob patterns of the names or paths of exercises to leave out,
+    // like `"exercises/threads/*"`
+    pub exclude: Option<Vec<String>>,
+    // Whether exercises are compiled with rustc or cargo
+    pub backend: Option<Backend>,
+    // The command line exercises compiled for WASI are run with, followed by
+    // the module and its arguments, `wasmtime run` by default
+    pub wasm_runtime: Option<String>,
+    // The runtime of the user's configuration alone, which is the only one
+    // used with `--untrusted`, like `user_hooks`
+    #[serde(skip)]
+    pub user_wasm_runtime: Option<String>,
+    // Defaults for the flags of watch mode
+    #[serde(default)]
+    pub watch: WatchConfig,
+    // Shell commands to run when something happens
+    #[serde(default)]
+    pub hooks: Hooks,
+    // The hooks of the user's configuration alone, which are the only ones
+    // run with `--untrusted`, since rustlings.toml may come with the exercises
+    #[serde(skip)]
+    pub user_hooks: Hooks,
+    // Where `rustlings submit` sends the results to
+    #[serde(default)]
+    pub submit: SubmitConfig,
+    // Where `rustlings sync` keeps the progress
+    #[serde(default)]
+    pub sync: SyncConfig,
+    // How long compiling and running exercises may take
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    // What running exercises may use
+    #[serde(default)]
+    pub limits: LimitConfig,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WatchConfig {
+    pub success_hints: Option<bool>,
+    pub notify: Option<bool>,
+    pub bell: Option<bool>,
+    pub recheck: Option<bool>,
+    pub diff: Option<bool>,
+    // In milliseconds
+    pub debounce: Option<u64>,
+    // Glob patterns of file names, in addition to the ones given to `--ignore`
+    pub ignore: Option<Vec<String>>,
+}
+
+// Shell commands run on events, with the exercise they happened to in
+// the `RUSTLINGS_EXERCISE` and `RUSTLINGS_EXERCISE_PATH` environment variables
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    // Run when an exercise is completed for the first time
+    pub exercise_passed: Option<String>,
+    // Run whenever an exercise fails to compile or its tests fail
+    pub exercise_failed: Option<String>,
+    // Run when all exercises are done
+    pub all_done: Option<String>,
+}
+
+// The endpoint results are submitted to, set up by the instructor
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SubmitConfig {
+    // The URL the results are posted to
+    pub endpoint: Option<String>,
+    // Sent as a bearer token, and used to sign attestations.
+    // The `RUSTLINGS_SUBMIT_TOKEN` environment variable takes precedence.
+    pub token: Option<String>,
+}
+
+// How long exercises may take before they're given up on, in seconds.
+// Exercises can set their own limits in info.toml.
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutConfig {
+    pub compile: Option<u64>,
+    pub run: Option<u64>,
+}
+
+// How much of the output of exercises is kept, in kibibytes of stdout and of
+// stderr each, and how much memory they may use, in mebibytes
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LimitConfig {
+    pub output: Option<u64>,
+    pub memory: Option<u64>,
+}
+
+impl Config {
+    // Load the configuration of the user and of the rustlings directory,
+    // using the defaults for anything neither of them sets
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+        let user = match user_config_path() {
+            Some(path) => Config::load_file(&path)?,
+            None => Config::default(),
+        };
+        let user_hooks = user.hooks.clone();
+        let user_wasm_runtime = user.wasm_runtime.clone();
+        Ok(Config {
+            user_hooks,
+            user_wasm_runtime,
+            ..Config::load_file(Path::new(CONFIG_FILE_PATH))?.or(user)
+        })
+    }
+
+    fn load_file(path: &Path) -> Result<Config, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()).into())
+    }
+
+    // This configuration, with anything it doesn't set taken from the fallback
+    fn or(self, fallback: Config) -> Config {
+        Config {
+            color: self.color.or(fallback.color),
+            theme: self.theme.or(fallback.theme),
+            ascii: self.ascii.or(fallback.ascii),
+            a11y: self.a11y.or(fallback.a11y),
+            lang: self.lang.or(fallback.lang),
+            editor: self.editor.or(fallback.editor),
+            jobs: self.jobs.or(fallback.jobs),
+            enforce_prerequisites: self
+                .enforce_prerequisites
+                .or(fallback.enforce_prerequisites),
+            done: self.done.or(fallback.done),
+            exclude: self.exclude.or(fallback.exclude),
+            backend: self.backend.or(fallback.backend),
+            wasm_runtime: self.wasm_runtime.or(fallback.wasm_runtime),
+            user_wasm_runtime: fallback.user_wasm_runtime,
+            watch: WatchConfig {
+                success_hints: self.watch.success_hints.or(fallback.watch.success_hints),
+                notify: self.watch.notify.or(fallback.watch.notify),
+                bell: self.watch.bell.or(fallback.watch.bell),
+                recheck: self.watch.recheck.or(fallback.watch.recheck),
+                diff: self.watch.diff.or(fallback.watch.diff),
+                debounce: self.watch.debounce.or(fallback.watch.debounce),
+                ignore: self.watch.ignore.or(fallback.watch.ignore),
+            },
+            hooks: Hooks {
+                exercise_passed: self
+                    .hooks
+                    .exercise_passed
+                    .or(fallback.hooks.exercise_passed),
+                exercise_failed: self
+                    .hooks
+                    .exercise_failed
+                    .or(fallback.hooks.exercise_failed),
+                all_done: self.hooks.all_done.or(fallback.hooks.all_done),
+            },
+            user_hooks: fallback.user_hooks,
+            submit: SubmitConfig {
+                endpoint: self.submit.endpoint.or(fallback.submit.endpoint),
+                token: self.submit.token.or(fallback.submit.token),
+            },
+            sync: SyncConfig {
+                backend: self.sync.backend.or(fallback.sync.backend),
+                url: self.sync.url.or(fallback.sync.url),
+                gist: self.sync.gist.or(fallback.sync.gist),
+                path: self.sync.path.or(fallback.sync.path),
+                region: self.sync.region.or(fallback.sync.region),
+                user: self.sync.user.or(fallback.sync.user),
+                token: self.sync.token.or(fallback.sync.token),
+                passphrase: self.sync.passphrase.or(fallback.sync.passphrase),
+                auto: self.sync.auto.or(fallback.sync.auto),
+            },
+            timeouts: TimeoutConfig {
+                compile: self.timeouts.compile.or(fallback.timeouts.compile),
+                run: self.timeouts.run.or(fallback.timeouts.run),
+            },
+            limits: LimitConfig {
+                output: self.limits.output.or(fallback.limits.output),
+                memory: self.limits.memory.or(fallback.limits.memory),
+            },
+        }
+    }
+}
+
+// The user's own backend the progress is synced to, opt-in
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SyncConfig {
+    pub backend: Option<SyncBackend>,
+    // The URL of the object or file for S3 and WebDAV,
+    // or of the API of a GitHub Enterprise server for gists
+    pub url: Option<String>,
+    // The ID of the gist
+    pub gist: Option<String>,
+    // The file for the `file` backend
+    pub path: Option<String>,
+    // The region of the bucket, `us-east-1` by default
+    pub region: Option<String>,
+    // The user name for WebDAV, or the access key for S3
+    pub user: Option<String>,
+    // The GitHub token, the password for WebDAV, or the secret key for S3.
+    // The `RUSTLINGS_SYNC_TOKEN` environment variable takes precedence.
+    pub token: Option<String>,
+    // What the progress is encrypted with.
+    // The `RUSTLINGS_SYNC_PASSPHRASE` environment variable takes precedence.
+    pub passphrase: Option<String>,
+    // Whether watch mode syncs when it starts and when it ends
+    pub auto: Option<bool>,
+}
+
+// The configuration of the user, in $XDG_CONFIG_HOME or ~/.config
+fn user_config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home::home_dir().map(|home| home.join(".config")))?;
+    Some(config_dir.join("rustlings").join("config.toml"))
+}
+
+// The configuration, loaded once when it's first needed. An invalid
+// configuration is reported, and the defaults are used instead.
+pub static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    Config::load().unwrap_or_else(|e| {
+        warn!("Failed to load the configuration, ignoring it: {}", e);
+        Config::default()
+    })
+});
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_workspace_config_takes_precedence() {
+        let user: Config =
+            toml::from_str("jobs = 4\neditor = \"vim\"\n[watch]\nbell = true").unwrap();
+        let workspace: Config = toml::from_str("jobs = 2\n[watch]\nnotify = true").unwrap();
+        let config = workspace.or(user);
+        assert_eq!(config.jobs, Some(2));
+        assert_eq!(config.editor.as_deref(), Some("vim"));
+        assert_eq!(config.watch.bell, Some(true));
+        assert_eq!(config.watch.notify, Some(true));
+    }
+}
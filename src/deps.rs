@@ -0,0 +1,321 @@
+// Building the crates exercises depend on, listed in their `deps` in
+// info.toml, like `deps = ["rand"]`. Cargo builds every set of crates once
+// into a shared cache, resolving them offline if it already downloaded them,
+// and exercises are compiled against the libraries with `--extern`. This
+// happens when an exercise first needs them, or with `rustlings prepare`.
+use crate::exercise::{Exercise, COMPILE_CACHE_DIR};
+use crate::output::{self, LoggedCommand};
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::OsString;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The libraries of the crates an exercise depends on
+#[derive(PartialEq, Debug)]
+pub struct Libraries {
+    // The name every crate is used by in code, and its library
+    externs: Vec<(String, PathBuf)>,
+    // Where the crates they depend on in turn are found
+    dir: PathBuf,
+}
+
+impl Libraries {
+    // The arguments for rustc and rustdoc to compile against the libraries
+    pub fn args(&self) -> Vec<OsString> {
+        let mut dependency = OsString::from("dependency=");
+        dependency.push(&self.dir);
+        let mut args = vec!["-L".into(), dependency];
+        for (name, library) in &self.externs {
+            let mut arg = OsString::from(format!("{name}="));
+            arg.push(library);
+            args.push("--extern".into());
+            args.push(arg);
+        }
+        args
+    }
+}
+
+// A message of `cargo build --message-format=json`, which are all ignored
+// except for the libraries that were built
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    #[serde(default)]
+    target: Option<Target>,
+    #[serde(default)]
+    filenames: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    name: String,
+    kind: Vec<String>,
+}
+
+// The libraries of the given crates built with the toolchain the given
+// commands, like `cargo`, run the tools of, which build them first unless
+// they already were. Returns the errors of cargo if that failed.
+pub fn build(deps: &[String], command: impl Fn(&str) -> Command) -> Result<Libraries, String> {
+    let cargo = || command("cargo");
+    let mut deps = deps.to_vec();
+    deps.sort();
+    deps.dedup();
+    // `rustup update` changes the toolchain without changing its name
+    let version = command("rustc")
+        .arg("-vV")
+        .logged_output()
+        .map(|output| output.stdout)
+        .unwrap_or_default();
+    let cache = Path::new(COMPILE_CACHE_DIR).join("deps");
+    let package = cache.join(package_name(&deps, &version));
+    let list = package.join("libraries.txt");
+    if let Some(libraries) = fs::read_to_string(&list)
+        .ok()
+        .and_then(|list| parse_list(&list))
+    {
+        return Ok(libraries);
+    }
+
+    let manifest = package.join("Cargo.toml");
+    fs::create_dir_all(&package)
+        .and_then(|()| fs::write(&manifest, manifest_for(&deps)))
+        .and_then(|()| fs::write(package.join("lib.rs"), ""))
+        .map_err(|e| format!("Failed to write the package of {}: {e}", deps.join(", ")))?;
+    let build = |offline: bool| {
+        let mut command = cargo();
+        command
+            .args(["build", "--message-format=json", "--manifest-path"])
+            .arg(&manifest)
+            .arg("--target-dir")
+            .arg(cache.join("target"));
+        if offline {
+            command.arg("--offline");
+        }
+        command.logged_output()
+    };
+    // Crates cargo already downloaded don't need the network
+    let output = match build(true) {
+        Ok(output) if output.status.success() => Ok(output),
+        _ => build(false),
+    }
+    .map_err(|e| format!("Failed to build {}: {e}", deps.join(", ")))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to build {}:\n{}",
+            deps.join(", "),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let libraries = libraries_in(&String::from_utf8_lossy(&output.stdout), &deps)?;
+    // Written last, so that a build that was cut short is done again
+    fs::write(&list, render_list(&libraries))
+        .map_err(|e| format!("Failed to write {}: {e}", list.display()))?;
+    Ok(libraries)
+}
+
+// `rustlings prepare` builds the crates of all exercises up front, so that
+// the first exercise needing them doesn't wait for a long build in the middle
+// of watch mode. Returns whether all of them could be built.
+pub fn prepare(exercises: &[Exercise]) -> bool {
+    // Exercises with the same crates and toolchain share their libraries
+    let mut sets: Vec<(Vec<&String>, Option<&str>, &Exercise)> = Vec::new();
+    for exercise in exercises.iter().filter(|e| !e.deps.is_empty()) {
+        let mut deps: Vec<&String> = exercise.deps.iter().collect();
+        deps.sort();
+        deps.dedup();
+        let toolchain = exercise.toolchain.as_deref();
+        if !sets.iter().any(|(d, t, _)| *d == deps && *t == toolchain) {
+            sets.push((deps, toolchain, exercise));
+        }
+    }
+    if sets.is_empty() {
+        info!("No exercise depends on crates, so there's nothing to prepare");
+        return true;
+    }
+
+    let mut built = true;
+    for (deps, _, exercise) in sets {
+        let deps: Vec<&str> = deps.into_iter().map(String::as_str).collect();
+        let deps = deps.join(", ");
+        let spinner = output::spinner(format!("Building {deps}..."));
+        let result = exercise.build_deps();
+        spinner.finish_and_clear();
+        match result {
+            Ok(_) => success!("Built {}", deps),
+            Err(e) => {
+                warn!("{}", e.trim_end());
+                built = false;
+            }
+        }
+    }
+    built
+}
+
+// The manifest of the package the crates are built as dependencies of, in a
+// workspace of its own, so that it's never taken for part of another one
+fn manifest_for(deps: &[String]) -> String {
+    let mut manifest = "[package]\n\
+                        name = \"rustlings-deps\"\n\
+                        version = \"0.0.0\"\n\
+                        edition = \"2021\"\n\
+                        publish = false\n\n\
+                        [lib]\n\
+                        path = \"lib.rs\"\n\n\
+                        [workspace]\n\n\
+                        [dependencies]\n"
+        .to_string();
+    for name in deps {
+        manifest.push_str(&format!("{name} = \"*\"\n"));
+    }
+    manifest
+}
+
+// The libraries of the crates among the artifacts cargo printed
+fn libraries_in(messages: &str, deps: &[String]) -> Result<Libraries, String> {
+    let mut externs = Vec::new();
+    let mut dir = None;
+    let artifacts: Vec<Message> = messages
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|message: &Message| message.reason == "compiler-artifact")
+        .collect();
+    for name in deps {
+        let crate_name = name.replace('-', "_");
+        let library = artifacts
+            .iter()
+            .filter(|message| {
+                message.target.as_ref().is_some_and(|target| {
+                    target.name.replace('-', "_") == crate_name
+                        && target
+                            .kind
+                            .iter()
+                            .any(|kind| kind.ends_with("lib") || kind == "proc-macro")
+                })
+            })
+            .flat_map(|message| &message.filenames)
+            // Not the metadata next to an rlib, which has no code to link
+            .find(|file| file.extension().is_some_and(|e| e != "rmeta"))
+            .ok_or_else(|| format!("cargo didn't build a library for {name}"))?;
+        dir = dir.or_else(|| library.parent().map(Path::to_path_buf));
+        externs.push((crate_name, library.clone()));
+    }
+    Ok(Libraries {
+        externs,
+        dir: dir.unwrap_or_default(),
+    })
+}
+
+// The libraries as written to the cache, one crate per line and the
+// directory of the crates they depend on first
+fn render_list(libraries: &Libraries) -> String {
+    let mut list = format!("{}\n", libraries.dir.display());
+    for (name, library) in &libraries.externs {
+        list.push_str(&format!("{name}={}\n", library.display()));
+    }
+    list
+}
+
+fn parse_list(list: &str) -> Option<Libraries> {
+    let mut lines = list.lines();
+    let dir = PathBuf::from(lines.next()?);
+    let externs = lines
+        .map(|line| {
+            let (name, library) = line.split_once('=')?;
+            Some((name.to_string(), PathBuf::from(library)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    // Like after `cargo clean`
+    if !dir.is_dir() || externs.iter().any(|(_, library)| !library.exists()) {
+        return None;
+    }
+    Some(Libraries { externs, dir })
+}
+
+// What the package of the crates is called in the cache. The libraries
+// only work with the toolchain they were built by, which is told apart by
+// the output of its `rustc -vV`.
+fn package_name(deps: &[String], rustc_version: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    deps.hash(&mut hasher);
+    rustc_version.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_libraries_in() {
+        let messages = r#"{"reason":"compiler-artifact","target":{"name":"serde_derive","kind":["proc-macro"]},"filenames":["/t/debug/deps/libserde_derive-1.so"]}
+{"reason":"compiler-artifact","target":{"name":"build-script-build","kind":["custom-build"]},"filenames":["/t/debug/build/x/build-script-build"]}
+{"reason":"compiler-artifact","target":{"name":"rand","kind":["lib"]},"filenames":["/t/debug/deps/librand-2.rlib","/t/debug/deps/librand-2.rmeta"]}
+{"reason":"build-finished","success":true}"#;
+        let deps = ["rand".to_string(), "serde-derive".to_string()];
+        let libraries = libraries_in(messages, &deps).unwrap();
+        assert_eq!(
+            libraries.externs,
+            [
+                (
+                    "rand".to_string(),
+                    PathBuf::from("/t/debug/deps/librand-2.rlib")
+                ),
+                (
+                    "serde_derive".to_string(),
+                    PathBuf::from("/t/debug/deps/libserde_derive-1.so")
+                ),
+            ]
+        );
+        assert_eq!(libraries.dir, Path::new("/t/debug/deps"));
+        assert!(libraries_in(messages, &["regex".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_args() {
+        let libraries = Libraries {
+            externs: vec![("rand".to_string(), PathBuf::from("deps/librand.rlib"))],
+            dir: PathBuf::from("deps"),
+        };
+        assert_eq!(
+            libraries.args(),
+            [
+                "-L",
+                "dependency=deps",
+                "--extern",
+                "rand=deps/librand.rlib"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_package_name() {
+        let deps = ["rand".to_string()];
+        let name = package_name(&deps, b"rustc 1.80.0 (051478957 2024-07-21)");
+        assert_eq!(
+            package_name(&deps, b"rustc 1.80.0 (051478957 2024-07-21)"),
+            name
+        );
+        assert_ne!(
+            package_name(&deps, b"rustc 1.81.0 (eeb90cda1 2024-09-04)"),
+            name
+        );
+        assert_ne!(
+            package_name(
+                &["regex".to_string()],
+                b"rustc 1.80.0 (051478957 2024-07-21)"
+            ),
+            name
+        );
+    }
+
+    #[test]
+    fn test_manifest_for() {
+        let manifest = manifest_for(&["rand".to_string()]);
+        assert!(manifest.contains("[workspace]\n"));
+        assert!(manifest.ends_with("[dependencies]\nrand = \"*\"\n"));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::theme;
+use console::{style, Style};
+use serde::Deserialize;
+
+// A diagnostic of rustc, as printed with `--error-format=json`.
+// Only the fields that are rendered are deserialized.
+#[derive(Deserialize, Debug)]
+struct Diagnostic {
+    message: String,
+    code: Option<Code>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<Span>,
+    #[serde(default)]
+    children: Vec<Diagnostic>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Code {
+    code: String,
+}
+
+// A part of the source code a diagnostic is about
+#[derive(Deserialize, Debug)]
+struct Span {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+    label: Option<String>,
+    // The source lines of the span, with the columns it covers in each of them
+    text: Vec<SpanLine>,
+    // The code that a suggestion replaces the span with
+    suggested_replacement: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SpanLine {
+    text: String,
+    highlight_start: usize,
+    highlight_end: usize,
+}
+
+// Render the JSON diagnostics rustc printed to stderr for beginners: the
+// source code the diagnostics are about is highlighted, and what doesn't help
+// fixing the exercise is left out. Lines that aren't diagnostics are kept.
+pub fn render(stderr: &str) -> String {
+    let mut rendered = String::new();
+    for line in stderr.lines() {
+        match serde_json::from_str::<Diagnostic>(line) {
+            Ok(diagnostic) if is_noise(&diagnostic) => {}
+            Ok(diagnostic) => {
+                render_diagnostic(&diagnostic, &mut rendered);
+                rendered.push('\n');
+            }
+            Err(_) => {
+                rendered.push_str(line);
+                rendered.push('\n');
+            }
+        }
+    }
+    rendered
+}
+
+// The codes of the errors rustc printed to stderr, like `E0382`
+pub fn error_codes(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+        .filter(|diagnostic| diagnostic.level == "error")
+        .filter_map(|diagnostic| Some(diagnostic.code?.code))
+        .collect()
+}
+
+// The levels of the diagnostics rustc printed to stderr about the code, with
+// their codes, like `("warning", Some("clippy::needless_range_loop"))`.
+// Summaries like "2 warnings emitted" aren't about any code, so are left out.
+pub fn levels(stderr: &str) -> Vec<(String, Option<String>)> {
+    stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+        .filter(|diagnostic| !diagnostic.spans.is_empty())
+        .map(|diagnostic| (diagnostic.level, diagnostic.code.map(|code| code.code)))
+        .collect()
+}
+
+// Summaries like "aborting due to 2 previous errors", pointers to
+// `rustc --explain`, notes about lints being on by default, and anything
+// about the temporary binary rustlings compiles to
+fn is_noise(diagnostic: &Diagnostic) -> bool {
+    diagnostic.level == "failure-note"
+        || diagnostic.message.starts_with("aborting due to")
+        || diagnostic.message.contains("on by default")
+        || diagnostic.message.contains("temp_")
+}
+
+fn level_style(level: &str) -> Style {
+    match level {
+        "error" | "error: internal compiler error" => theme::failure().bold(),
+        "warning" => theme::warning().bold(),
+        _ => Style::new().cyan().bold(),
+    }
+}
+
+fn render_diagnostic(diagnostic: &Diagnostic, rendered: &mut String) {
+    let level_style = level_style(&diagnostic.level);
+    let heading = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", diagnostic.level, code.code),
+        None => diagnostic.level.clone(),
+    };
+    rendered.push_str(&format!(
+        "{}{}\n",
+        level_style.apply_to(heading),
+        style(format!(": {}", diagnostic.message)).bold()
+    ));
+
+    let mut spans: Vec<&Span> = diagnostic.spans.iter().collect();
+    spans.sort_by_key(|span| (span.line_start, span.column_start));
+    let width = spans
+        .iter()
+        .map(|span| (span.line_start + span.text.len()).to_string().len())
+        .max()
+        .unwrap_or(1);
+    let gutter = |number: &str| style(format!("{number:>width$} |")).blue().bold();
+
+    if let Some(primary) = spans.iter().find(|span| span.is_primary).or(spans.first()) {
+        rendered.push_str(&format!(
+            "{}{}:{}:{}\n",
+            style(format!("{:>width$}--> ", "")).blue().bold(),
+            primary.file_name,
+            primary.line_start,
+            primary.column_start
+        ));
+        rendered.push_str(&format!("{}\n", gutter("")));
+    }
+    for span in &spans {
+        let (marker, marker_style) = if span.is_primary {
+            ('^', level_style.clone())
+        } else {
+            ('-', Style::new().blue().bold())
+        };
+        for (i, line) in span.text.iter().enumerate() {
+            let number = (span.line_start + i).to_string();
+            rendered.push_str(&format!("{} {}\n", gutter(&number), line.text));
+
+            let start = line.highlight_start.saturating_sub(1);
+            let len = line
+                .highlight_end
+                .saturating_sub(line.highlight_start)
+                .max(1);
+            let mut marks = marker.to_string().repeat(len);
+            if i + 1 == span.text.len() {
+                if let Some(label) = &span.label {
+                    marks = format!("{marks} {label}");
+                }
+            }
+            rendered.push_str(&format!(
+                "{} {}{}\n",
+                gutter(""),
+                " ".repeat(start),
+                marker_style.apply_to(marks)
+            ));
+        }
+    }
+
+    for child in &diagnostic.children {
+        if is_noise(child) {
+            continue;
+        }
+        rendered.push_str(&format!(
+            "{} {}{}\n",
+            style(format!("{:>width$} =", "")).blue().bold(),
+            style(&child.level).bold(),
+            format_args!(": {}", child.message)
+        ));
+        // Show what the code looks like with suggestions applied
+        for span in &child.spans {
+            let (Some(replacement), Some(line)) = (&span.suggested_replacement, span.text.first())
+            else {
+                continue;
+            };
+            rendered.push_str(&format!(
+                "{}   {}\n",
+                " ".repeat(width),
+                style(apply_suggestion(line, replacement)).green()
+            ));
+        }
+    }
+}
+
+// The source line with the highlighted columns replaced
+fn apply_suggestion(line: &SpanLine, replacement: &str) -> String {
+    let chars: Vec<char> = line.text.chars().collect();
+    let start = line.highlight_start.saturating_sub(1).min(chars.len());
+    let end = line
+        .highlight_end
+        .saturating_sub(1)
+        .clamp(start, chars.len());
+    let before: String = chars[..start].iter().collect();
+    let after: String = chars[end..].iter().collect();
+    format!("{before}{replacement}{after}").trim().to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        console::set_colors_enabled(false);
+        let stderr = r#"{"$message_type":"diagnostic","message":"cannot find value `x` in this scope","code":{"code":"E0425","explanation":null},"level":"error","spans":[{"file_name":"exercises/variables/variables1.rs","byte_start":0,"byte_end":1,"line_start":8,"line_end":8,"column_start":5,"column_end":6,"is_primary":true,"text":[{"text":"    x = 5;","highlight_start":5,"highlight_end":6}],"label":"not found in this scope","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"you might have meant to introduce a new binding","code":null,"level":"help","spans":[{"file_name":"exercises/variables/variables1.rs","byte_start":0,"byte_end":0,"line_start":8,"line_end":8,"column_start":5,"column_end":5,"is_primary":true,"text":[{"text":"    x = 5;","highlight_start":5,"highlight_end":5}],"label":null,"suggested_replacement":"let ","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0425]: cannot find value `x` in this scope"}
+{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}"#;
+        assert_eq!(
+            render(stderr),
+            "error[E0425]: cannot find value `x` in this scope\n \
+             --> exercises/variables/variables1.rs:8:5\n  \
+             |\n\
+             8 |     x = 5;\n  \
+             |     ^ not found in this scope\n  \
+             = help: you might have meant to introduce a new binding\n    \
+             let x = 5;\n\n"
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let stderr = r#"{"message":"borrow of moved value: `v`","code":{"code":"E0382","explanation":null},"level":"error","spans":[],"children":[]}
+{"message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[],"children":[]}
+{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[]}"#;
+        assert_eq!(error_codes(stderr), vec!["E0382"]);
+    }
+
+    #[test]
+    fn test_levels() {
+        let span = r#"{"file_name":"x.rs","line_start":2,"column_start":9,"is_primary":true,"label":null,"text":[],"suggested_replacement":null}"#;
+        let stderr = format!(
+            r#"{{"message":"unused variable: `x`","code":{{"code":"unused_variables","explanation":null}},"level":"warning","spans":[{span}],"children":[]}}
+{{"message":"mismatched types","code":{{"code":"E0308","explanation":null}},"level":"error","spans":[{span}],"children":[]}}
+{{"message":"1 warning emitted","code":null,"level":"warning","spans":[],"children":[]}}"#
+        );
+        assert_eq!(
+            levels(&stderr),
+            [
+                ("warning".to_string(), Some("unused_variables".to_string())),
+                ("error".to_string(), Some("E0308".to_string())),
+            ]
+        );
+    }
+}
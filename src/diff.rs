@@ -0,0 +1,202 @@
+use crate::theme;
+use console::style;
+
+// A line of a diff
+#[derive(PartialEq, Debug)]
+pub enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// The lines of both texts, marking the ones only in `old` as removed and the
+// ones only in `new` as added, based on their longest common subsequence
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence
+    // of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| Line::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| Line::Added(line)));
+    lines
+}
+
+// Changed lines along with the unchanged ones around them, and where they
+// start in both texts, counted from one
+#[derive(PartialEq, Debug)]
+pub struct Hunk<'a> {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<Line<'a>>,
+}
+
+// The changes between both texts with up to `context` unchanged lines around
+// them, like `diff -u`. Changes that are close together share a hunk.
+pub fn hunks<'a>(old: &'a str, new: &'a str, context: usize) -> Vec<Hunk<'a>> {
+    let lines = diff_lines(old, new);
+    let changes: Vec<usize> = (0..lines.len())
+        .filter(|&i| !matches!(lines[i], Line::Same(_)))
+        .collect();
+    let Some(&first) = changes.first() else {
+        return Vec::new();
+    };
+
+    // The first and last change of every hunk
+    let mut groups = vec![(first, first)];
+    for &i in &changes[1..] {
+        let last = groups.last_mut().unwrap();
+        if i - last.1 > 2 * context + 1 {
+            groups.push((i, i));
+        } else {
+            last.1 = i;
+        }
+    }
+
+    let in_old = |line: &Line| !matches!(line, Line::Added(_));
+    let in_new = |line: &Line| !matches!(line, Line::Removed(_));
+    let mut lines = lines.into_iter().enumerate().peekable();
+    let mut hunks = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    for (first, last) in groups {
+        let from = first.saturating_sub(context);
+        let mut hunk = Vec::new();
+        while let Some((i, line)) = lines.next_if(|(i, _)| *i <= last + context) {
+            if i < from {
+                old_line += usize::from(in_old(&line));
+                new_line += usize::from(in_new(&line));
+            } else {
+                hunk.push(line);
+            }
+        }
+        let old_len = hunk.iter().filter(|l| in_old(l)).count();
+        let new_len = hunk.iter().filter(|l| in_new(l)).count();
+        hunks.push(Hunk {
+            // An empty side starts at the line before, like in `diff -u`
+            old_start: old_line + usize::from(old_len > 0),
+            old_len,
+            new_start: new_line + usize::from(new_len > 0),
+            new_len,
+            lines: hunk,
+        });
+        old_line += old_len;
+        new_line += new_len;
+    }
+    hunks
+}
+
+// Print how `new` differs from `old` as a unified diff,
+// with the labels of both in its header
+pub fn print_unified(old_label: &str, old: &str, new_label: &str, new: &str) {
+    println!("{}", theme::failure().apply_to(format!("--- {old_label}")));
+    println!("{}", theme::success().apply_to(format!("+++ {new_label}")));
+    for hunk in hunks(old, new, 3) {
+        println!(
+            "{}",
+            style(format!(
+                "@@ -{},{} +{},{} @@",
+                hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+            ))
+            .cyan()
+        );
+        for line in hunk.lines {
+            match line {
+                Line::Same(line) => println!(" {line}"),
+                Line::Removed(line) => {
+                    println!("{}", theme::failure().apply_to(format!("-{line}")))
+                }
+                Line::Added(line) => println!("{}", theme::success().apply_to(format!("+{line}"))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let attempt = "fn main() {\n    // I AM NOT DONE\n    let x = 5\n}";
+        let solution = "fn main() {\n    let x = 5;\n    println!(\"{x}\");\n}";
+        assert_eq!(
+            diff_lines(attempt, solution),
+            vec![
+                Line::Same("fn main() {"),
+                Line::Removed("    // I AM NOT DONE"),
+                Line::Removed("    let x = 5"),
+                Line::Added("    let x = 5;"),
+                Line::Added("    println!(\"{x}\");"),
+                Line::Same("}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hunks() {
+        let old: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let new: String = (1..=20)
+            .filter(|&i| i != 18)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                i => format!("{i}\n"),
+            })
+            .collect();
+        let hunks = hunks(&old, &new, 1);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            hunks[0],
+            Hunk {
+                old_start: 1,
+                old_len: 3,
+                new_start: 1,
+                new_len: 3,
+                lines: vec![
+                    Line::Same("1"),
+                    Line::Removed("2"),
+                    Line::Added("two"),
+                    Line::Same("3"),
+                ],
+            }
+        );
+        assert_eq!(
+            hunks[1],
+            Hunk {
+                old_start: 17,
+                old_len: 3,
+                new_start: 17,
+                new_len: 2,
+                lines: vec![Line::Same("17"), Line::Removed("18"), Line::Same("19")],
+            }
+        );
+        assert!(super::hunks("same\n", "same\n", 3).is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+use crate::exercise::{exercises_dir, Exercise, ExerciseList};
+use crate::project::cargo_manifests;
+use crate::registry::Registry;
+use crate::theme;
+use crate::wasm;
+use console::Term;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// The outcome of a single check, along with what was found or how to fix it
+enum Outcome {
+    Pass(String),
+    // Something that only makes rustlings less pleasant to use
+    Warn(String),
+    // Something that keeps rustlings from working
+    Fail(String),
+}
+
+// Check the environment rustlings runs in, printing how to fix any problems.
+// Returns whether rustlings should work.
+pub fn run(info_file: &Path) -> bool {
+    let checks = [
+        ("rustc", tool_version("rustc", &["--version"])),
+        ("cargo", tool_version("cargo", &["--version"])),
+        ("clippy", clippy()),
+        ("rustfmt", rustfmt()),
+        ("info.toml", info(info_file)),
+        ("exercises", exercises(info_file)),
+        ("dependencies", dependencies(info_file)),
+        ("plugins", plugins(info_file)),
+        ("webassembly", webassembly(info_file)),
+        ("colors", colors()),
+        ("write access", write_access(info_file)),
+    ];
+
+    let mut works = true;
+    for (name, outcome) in checks {
+        match outcome {
+            Outcome::Pass(found) => success!("{}", format!("{name}: {found}")),
+            Outcome::Warn(fix) => {
+                println!("{}", theme::warning().apply_to(format!("? {name}")));
+                println!("    {fix}");
+            }
+            Outcome::Fail(fix) => {
+                works = false;
+                warn!("{}", name);
+                println!("    {fix}");
+            }
+        }
+    }
+    works
+}
+
+// The version a tool prints, if it can be run
+fn version_of(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn tool_version(program: &str, args: &[&str]) -> Outcome {
+    match version_of(program, args) {
+        Some(version) => Outcome::Pass(version),
+        None => Outcome::Fail(format!(
+            "`{program}` can't be run. Install Rust from https://rustup.rs, or make sure it's in your PATH."
+        )),
+    }
+}
+
+fn clippy() -> Outcome {
+    match version_of("cargo", &["clippy", "--version"]) {
+        Some(version) => Outcome::Pass(version),
+        None => Outcome::Fail(
+            "Clippy isn't installed, which the clippy exercises need. Run `rustup component add clippy`."
+                .to_string(),
+        ),
+    }
+}
+
+fn rustfmt() -> Outcome {
+    match version_of("rustfmt", &["--version"]) {
+        Some(version) => Outcome::Pass(version),
+        None => Outcome::Fail(
+            "rustfmt isn't installed, which the fmt exercises need. Run `rustup component add rustfmt`."
+                .to_string(),
+        ),
+    }
+}
+
+fn info(info_file: &Path) -> Outcome {
+    if !info_file.exists() {
+        return Outcome::Fail(format!(
+            "{} doesn't exist. Run rustlings from the rustlings directory, or set up the exercises with `rustlings init`.",
+            info_file.display()
+        ));
+    }
+    match ExerciseList::load(info_file) {
+        Ok(list) => Outcome::Pass(format!("{} exercises", list.exercises.len())),
+        Err(e) => Outcome::Fail(format!(
+            "{} is invalid, fix it or run `git checkout {}`: {e}",
+            info_file.display(),
+            info_file.display()
+        )),
+    }
+}
+
+fn exercises(info_file: &Path) -> Outcome {
+    let dir = exercises_dir(info_file);
+    if dir.is_dir() {
+        Outcome::Pass(format!("found in {}", dir.display()))
+    } else {
+        Outcome::Fail(format!(
+            "{} doesn't exist. Run rustlings from the rustlings directory, or set up the exercises with `rustlings init`.",
+            dir.display()
+        ))
+    }
+}
+
+// Whether rust-analyzer can find the sources of the crates exercises depend on
+fn dependencies(info_file: &Path) -> Outcome {
+    let Ok(list) = ExerciseList::load(info_file) else {
+        return Outcome::Warn("Can't be checked without a valid info.toml.".to_string());
+    };
+    let mut names: Vec<&str> = list
+        .exercises
+        .iter()
+        .flat_map(|e| &e.deps)
+        .map(String::as_str)
+        .collect();
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Outcome::Pass("none needed".to_string());
+    }
+
+    let dir = exercises_dir(info_file);
+    let base = dir.parent().unwrap_or_else(|| Path::new(""));
+    let registry = Registry::discover(cargo_manifests(base));
+    let missing: Vec<&str> = names
+        .iter()
+        .filter(|name| {
+            !registry
+                .as_ref()
+                .is_ok_and(|r| r.find(name, None).is_some())
+        })
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        Outcome::Pass(format!("sources of {} found", names.join(", ")))
+    } else {
+        Outcome::Warn(format!(
+            "The sources of {} aren't in the cargo registry, so rust-analyzer can't resolve them. \
+             Run `rustlings lsp --cargo` and `cargo fetch --manifest-path .rustlings-cargo/Cargo.toml` to download them.",
+            missing.join(", ")
+        ))
+    }
+}
+
+// Whether the plugins that verify plugin exercises are installed
+fn plugins(info_file: &Path) -> Outcome {
+    let Ok(list) = ExerciseList::load(info_file) else {
+        return Outcome::Warn("Can't be checked without a valid info.toml.".to_string());
+    };
+    // One exercise for every plugin, which it's looked for like
+    let mut plugins: Vec<&Exercise> = Vec::new();
+    for exercise in list.exercises.iter().filter(|e| e.plugin.is_some()) {
+        if !plugins.iter().any(|p| p.plugin == exercise.plugin) {
+            plugins.push(exercise);
+        }
+    }
+    let (found, missing): (Vec<&Exercise>, Vec<&Exercise>) =
+        plugins.into_iter().partition(|e| e.find_plugin().is_some());
+    let found: Vec<&str> = found.iter().filter_map(|e| e.plugin.as_deref()).collect();
+    let missing: Vec<String> = missing
+        .iter()
+        .map(|e| {
+            format!(
+                "`{}` ({})",
+                e.plugin.as_deref().unwrap_or_default(),
+                e.plugin_file_name()
+            )
+        })
+        .collect();
+    if !missing.is_empty() {
+        Outcome::Fail(format!(
+            "Can't find the plugins {}, so the exercises they verify can't be checked. \
+             Put them into the plugins folder next to info.toml, or into your PATH.",
+            missing.join(", ")
+        ))
+    } else if found.is_empty() {
+        Outcome::Pass("none needed".to_string())
+    } else {
+        Outcome::Pass(format!("{} found", found.join(", ")))
+    }
+}
+
+// Whether the targets exercises are compiled for are installed, and the
+// runtime the ones for WASI are run with
+fn webassembly(info_file: &Path) -> Outcome {
+    let Ok(list) = ExerciseList::load(info_file) else {
+        return Outcome::Warn("Can't be checked without a valid info.toml.".to_string());
+    };
+    let mut targets: Vec<&str> = list
+        .exercises
+        .iter()
+        .filter_map(|e| e.target.as_deref())
+        .collect();
+    targets.sort();
+    targets.dedup();
+    if targets.is_empty() {
+        return Outcome::Pass("none needed".to_string());
+    }
+
+    // The standard library of every installed target is in the sysroot
+    let sysroot = version_of("rustc", &["--print", "sysroot"]).unwrap_or_default();
+    let missing: Vec<&str> = targets
+        .iter()
+        .filter(|target| {
+            !Path::new(&sysroot)
+                .join("lib/rustlib")
+                .join(target)
+                .is_dir()
+        })
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Outcome::Fail(format!(
+            "The exercises compiled to WebAssembly need the {} target. Run `rustup target add {}`.",
+            missing.join(", "),
+            missing.join(" ")
+        ));
+    }
+    if !targets.iter().any(|target| wasm::is_runnable(target)) {
+        return Outcome::Pass(format!("{} installed", targets.join(", ")));
+    }
+    let program = wasm::runtime()
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    match version_of(program, &["--version"]) {
+        Some(version) => Outcome::Pass(format!("{} installed, {version}", targets.join(", "))),
+        None => Outcome::Fail(format!(
+            "`{program}` can't be run, which the exercises compiled for WASI are run with. \
+             Install wasmtime from https://wasmtime.dev, or set `wasm_runtime` in rustlings.toml."
+        )),
+    }
+}
+
+fn colors() -> Outcome {
+    if Term::stdout().features().colors_supported() {
+        Outcome::Pass("supported".to_string())
+    } else {
+        Outcome::Warn(
+            "Your terminal doesn't seem to support colors, so the output of rustlings is harder to read. \
+             Try a different terminal, or set CLICOLOR_FORCE=1 if it does support them."
+                .to_string(),
+        )
+    }
+}
+
+// Rustlings saves the progress and other state next to info.toml
+fn write_access(info_file: &Path) -> Outcome {
+    let dir = info_file.parent().unwrap_or_else(|| Path::new(""));
+    let probe = dir.join(".rustlings-doctor");
+    match fs::write(&probe, "").and_then(|_| fs::remove_file(&probe)) {
+        Ok(()) => Outcome::Pass("ok".to_string()),
+        Err(e) => Outcome::Fail(format!(
+            "Rustlings can't write to the rustlings directory, which it needs to save your progress. \
+             Check its permissions: {e}"
+        )),
+    }
+}
@@ -0,0 +1,144 @@
+// The settings `rustlings lsp --editor <editor>` writes, so that the
+// editor's rust-analyzer picks up the generated project. The settings
+// are merged into the ones that are already there.
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The editors settings can be written for
+#[derive(PartialEq, Debug)]
+pub enum Editor {
+    Vscode,
+    Helix,
+    Neovim,
+}
+
+pub fn parse_editor(value: &str) -> Result<Editor, String> {
+    match value {
+        "vscode" => Ok(Editor::Vscode),
+        "helix" => Ok(Editor::Helix),
+        "neovim" => Ok(Editor::Neovim),
+        _ => Err(format!(
+            "unknown editor `{value}`, expected `vscode`, `helix` or `neovim`"
+        )),
+    }
+}
+
+// The first line of the settings rustlings writes for neovim, which marks
+// the file as one that can be overwritten
+const NEOVIM_HEADER: &str = "-- Generated by `rustlings lsp --editor neovim`";
+
+// Write the settings pointing rust-analyzer at the given project, which is
+// either rust-project.json or a Cargo.toml. Returns the files written.
+pub fn write_settings(editor: &Editor, project: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let project = project.display().to_string();
+    match editor {
+        Editor::Vscode => {
+            let dir = Path::new(".vscode");
+            fs::create_dir_all(dir)?;
+            let settings = dir.join("settings.json");
+            merge_json(&settings, |json| {
+                json["rust-analyzer.linkedProjects"] = json!([project]);
+            })?;
+            let extensions = dir.join("extensions.json");
+            merge_json(&extensions, |json| {
+                let recommendations = &mut json["recommendations"];
+                if !recommendations.is_array() {
+                    *recommendations = json!([]);
+                }
+                let recommendations = recommendations.as_array_mut().unwrap();
+                if !recommendations.contains(&json!("rust-lang.rust-analyzer")) {
+                    recommendations.push(json!("rust-lang.rust-analyzer"));
+                }
+            })?;
+            Ok(vec![settings, extensions])
+        }
+        Editor::Helix => {
+            let dir = Path::new(".helix");
+            fs::create_dir_all(dir)?;
+            let languages = dir.join("languages.toml");
+            let mut toml: toml::Value = match fs::read_to_string(&languages) {
+                Ok(contents) => contents.parse()?,
+                Err(_) => toml::Value::Table(Default::default()),
+            };
+            let config = ["language-server", "rust-analyzer", "config"]
+                .iter()
+                .try_fold(&mut toml, |value, key| {
+                    value.as_table_mut().map(|table| {
+                        table
+                            .entry(*key)
+                            .or_insert(toml::Value::Table(Default::default()))
+                    })
+                })
+                .and_then(toml::Value::as_table_mut)
+                .ok_or_else(|| format!("{} isn't laid out as expected", languages.display()))?;
+            config.insert(
+                "linkedProjects".to_string(),
+                toml::Value::Array(vec![toml::Value::String(project)]),
+            );
+            fs::write(&languages, toml::to_string(&toml)?)?;
+            Ok(vec![languages])
+        }
+        Editor::Neovim => {
+            // Neovim runs this file when `exrc` is set
+            let path = PathBuf::from(".nvim.lua");
+            if let Ok(existing) = fs::read_to_string(&path) {
+                if !existing.starts_with(NEOVIM_HEADER) {
+                    return Err(format!(
+                        "{} has settings of your own, add this to it:\n{}",
+                        path.display(),
+                        neovim_settings(&project)
+                    )
+                    .into());
+                }
+            }
+            fs::write(
+                &path,
+                format!("{NEOVIM_HEADER}\n{}", neovim_settings(&project)),
+            )?;
+            Ok(vec![path])
+        }
+    }
+}
+
+fn neovim_settings(project: &str) -> String {
+    format!(
+        "vim.lsp.config(\"rust_analyzer\", {{\n  \
+           settings = {{ [\"rust-analyzer\"] = {{ linkedProjects = {{ {project:?} }} }} }},\n\
+         }})\n"
+    )
+}
+
+// Change the JSON file at the given path, which is created if it doesn't exist
+fn merge_json(
+    path: &Path,
+    change: impl FnOnce(&mut serde_json::Value),
+) -> Result<(), Box<dyn Error>> {
+    let mut json = match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?,
+        Err(_) => json!({}),
+    };
+    if !json.is_object() {
+        return Err(format!("{} doesn't contain an object", path.display()).into());
+    }
+    change(&mut json);
+    fs::write(path, serde_json::to_string_pretty(&json)? + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neovim_settings() {
+        assert_eq!(
+            neovim_settings("rust-project.json"),
+            "vim.lsp.config(\"rust_analyzer\", {\n  \
+             settings = { [\"rust-analyzer\"] = { linkedProjects = { \"rust-project.json\" } } },\n\
+             })\n"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// The info.toml and exercises rustlings was built with, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+// Write the embedded exercises into a new directory
+pub fn init(dir: &Path) -> io::Result<()> {
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+    for (name, contents) in EMBEDDED_FILES {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+// The original contents of the file at the given path, if it was embedded
+pub fn original(path: &Path) -> Option<&'static [u8]> {
+    let name = path.to_string_lossy().replace('\\', "/");
+    let name = name.strip_prefix("./").unwrap_or(&name);
+    EMBEDDED_FILES
+        .iter()
+        .find(|(embedded, _)| *embedded == name)
+        .map(|(_, contents)| *contents)
+}
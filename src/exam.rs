@@ -0,0 +1,196 @@
+// `rustlings exam <topic>` tests what was learned in a topic: a few of its
+// exercises are copied into EXAM_DIR in their original form, and have to be
+// solved there without hints, optionally within a time limit. Handing in,
+// solving all exercises or running out of time ends the exam with a score.
+use crate::exercise::Exercise;
+use crate::output::symbol;
+use crate::progress::now;
+use crate::review::shuffle;
+use crate::run::copy_original;
+use crate::theme;
+use crate::verify::check;
+use console::style;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+// Where the copies of the exercises of an exam are solved
+pub const EXAM_DIR: &str = ".rustlings-exam";
+// The exam that is in progress, next to the copies
+const STATE_FILE: &str = "exam.toml";
+
+// The exam that is in progress, so that hints stay disabled
+// for other rustlings commands until it ends
+#[derive(Serialize, Deserialize)]
+struct ExamState {
+    topic: String,
+    // When the exam ends, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deadline: Option<u64>,
+}
+
+// Whether an exam is in progress, during which hints and solutions are disabled
+pub fn in_progress() -> bool {
+    fs::read_to_string(Path::new(EXAM_DIR).join(STATE_FILE))
+        .ok()
+        .and_then(|state| toml::from_str::<ExamState>(&state).ok())
+        .is_some_and(|state| state.deadline.is_none_or(|deadline| now() < deadline))
+}
+
+// Hold an exam on `count` random exercises of the topic, which has to be
+// done within the given number of minutes, if any
+pub fn run(
+    exercises: &[Exercise],
+    topic: &str,
+    count: usize,
+    minutes: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let mut selected: Vec<&Exercise> = exercises.iter().filter(|e| e.topic() == topic).collect();
+    if selected.is_empty() {
+        return Err(format!("There is no topic called `{topic}`").into());
+    }
+    shuffle(&mut selected);
+    selected.truncate(count.max(1));
+
+    // Start from scratch, even if the last exam wasn't finished
+    let dir = Path::new(EXAM_DIR);
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    let copies = selected
+        .iter()
+        .map(|exercise| copy_original(exercise, dir))
+        .collect::<Result<Vec<Exercise>, _>>()?;
+    let deadline = minutes.map(|minutes| now() + minutes * 60);
+    let state = ExamState {
+        topic: topic.to_string(),
+        deadline,
+    };
+    fs::write(dir.join(STATE_FILE), toml::to_string(&state)?)?;
+
+    println!(
+        "{} Exam on {}: solve these exercises without hints{}.",
+        symbol("📝", ">"),
+        style(topic).bold(),
+        minutes.map_or(String::new(), |minutes| format!(
+            " within {minutes} minutes"
+        ))
+    );
+    for copy in &copies {
+        println!("  {}", copy.path.display());
+    }
+    println!("They're checked whenever you save them. Type `submit` to hand in early.");
+
+    let result = hold(&copies, deadline);
+    fs::remove_file(dir.join(STATE_FILE)).ok();
+    result?;
+    print_score(&copies);
+    Ok(())
+}
+
+// Check the exercises whenever they change, until all of them pass,
+// the exam is handed in, or the time runs out
+fn hold(copies: &[Exercise], deadline: Option<u64>) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
+    watcher.watch(EXAM_DIR, RecursiveMode::Recursive)?;
+
+    let (submit_tx, submit_rx) = channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            match line {
+                Ok(line) if line.trim() == "submit" => {
+                    let _ignored = submit_tx.send(());
+                    return;
+                }
+                Ok(_) => println!("Type `submit` to hand in."),
+                Err(_) => return,
+            }
+        }
+    });
+
+    let mut passing = vec![false; copies.len()];
+    loop {
+        if submit_rx.try_recv().is_ok() {
+            return Ok(());
+        }
+        if deadline.is_some_and(|deadline| now() >= deadline) {
+            println!();
+            warn!("{}", "Time is up!");
+            return Ok(());
+        }
+        let event = match rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Err("Stopped watching the exam".into()),
+        };
+        let (DebouncedEvent::Create(path) | DebouncedEvent::Write(path)) = event else {
+            continue;
+        };
+        let Some(index) = copies.iter().position(|copy| copy.contains(&path)) else {
+            continue;
+        };
+        passing[index] = report(&copies[index]);
+        if passing.iter().all(|passes| *passes) {
+            return Ok(());
+        }
+        if let Some(deadline) = deadline {
+            let left = deadline.saturating_sub(now()).div_ceil(60);
+            println!("{}", style(format!("{left} minutes left")).dim());
+        }
+    }
+}
+
+// Check the copy of an exercise, printing how it went.
+// Returns whether it passes.
+fn report(copy: &Exercise) -> bool {
+    match check(copy) {
+        Ok(true) => {
+            success!("{} passes", copy);
+            true
+        }
+        Ok(false) => {
+            warn!("{} passes, remove its `I AM NOT DONE` comment", copy);
+            false
+        }
+        Err(output) => {
+            warn!("{} doesn't pass yet:", copy);
+            for text in [&output.stdout, &output.stderr] {
+                if !text.trim().is_empty() {
+                    println!("{}", text.trim_end());
+                }
+            }
+            false
+        }
+    }
+}
+
+// Check all exercises once more, and print what they scored
+fn print_score(copies: &[Exercise]) {
+    println!();
+    let mut score = 0;
+    let mut total = 0;
+    for copy in copies {
+        let points = copy.points.unwrap_or(1);
+        let passes = matches!(check(copy), Ok(true));
+        total += points;
+        if passes {
+            score += points;
+        }
+        let (mark, earned) = if passes {
+            (theme::success().apply_to(symbol("✅", "+")), points)
+        } else {
+            (theme::failure().apply_to(symbol("❌", "x")), 0)
+        };
+        println!("{mark} {:<24} {earned}/{points}", copy.name);
+    }
+    let percentage = score as f32 / total.max(1) as f32 * 100.0;
+    println!();
+    println!("Score: {score} / {total} points ({percentage:.1} %)");
+}
@@ -1,31 +1,85 @@
-use regex::Regex;
-use serde::Deserialize;
+use crate::backend::{self, Backend, Package, Profile, Project, Target};
+use crate::config::CONFIG;
+use crate::deps;
+use crate::diagnostics;
+use crate::graph;
+use crate::hint::Hint;
+use crate::limits::Limits;
+use crate::output::{self, LoggedCommand};
+use crate::panics;
+use crate::plugin::{self, Executable, ModeRunner};
+use crate::progress::Progress;
+use crate::property;
+use crate::sandbox::{self, Sandbox};
+use crate::session;
+use crate::state::{self, DoneStrategy, MARKER};
+use crate::theme;
+use crate::topics::Topic;
+use crate::wasm;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::env;
+use std::error::Error;
+use std::ffi::OsString;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
-const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+// The diagnostics of rustc are rendered by rustlings itself
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json"];
+pub const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+// Line tables, so that the backtraces of failing tests point to the exercise
+const RUSTC_TEST_ARGS: &[&str] = &["-C", "debuginfo=line-tables-only"];
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
+pub const COMPILE_CACHE_DIR: &str = ".rustlings-cache";
+const HIDDEN_TESTS_CACHE_DIR: &str = ".rustlings-cache/hidden";
+// How often the binaries of bench exercises are run to time them
+const BENCH_RUNS: usize = 5;
 
-// Get a temporary file name that is hopefully unique
-#[inline]
+// How long compiling the last exercise took, for the footer of watch mode
+static LAST_COMPILE_TIME: Mutex<Option<Duration>> = Mutex::new(None);
+
+pub fn last_compile_time() -> Option<Duration> {
+    *LAST_COMPILE_TIME.lock().unwrap()
+}
+
+// The temporary file the current thread compiles exercises to, in the
+// build directory of this run
 fn temp_file() -> String {
-    let thread_id: String = format!("{:?}", std::thread::current().id())
+    let path = session::dir().join(format!("temp_{}", thread_id()));
+    path.to_string_lossy().to_string()
+}
+
+// The temporary directory the test harnesses of a project exercise are
+// copied to, in the order they're run
+fn temp_tests() -> String {
+    let path = session::dir().join(format!("tests_{}", thread_id()));
+    path.to_string_lossy().to_string()
+}
+
+// The temporary file for a library, which rustdoc only links doc tests
+// against if it's named like one
+fn temp_library() -> String {
+    let path = session::dir().join(format!("libtemp_{}.rlib", thread_id()));
+    path.to_string_lossy().to_string()
+}
+
+fn thread_id() -> String {
+    format!("{:?}", std::thread::current().id())
         .chars()
         .filter(|c| c.is_alphanumeric())
-        .collect();
-
-    format!("./temp_{}_{thread_id}", process::id())
+        .collect()
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, PartialEq, Debug, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -34,25 +88,404 @@ pub enum Mode {
     Test,
     // Indicates that the exercise should be linted with clippy
     Clippy,
+    // Indicates that the exercise should be compiled with optimizations,
+    // and has to run within `max_time_ms`
+    Bench,
+    // Indicates that the exercise should fail to compile with the error
+    // `expected_error`, like to show what the borrow checker forbids
+    #[serde(rename = "compile_fail")]
+    CompileFail,
+    // Indicates that the exercise should be compiled as a test harness,
+    // linked against the property testing harness of rustlings
+    Property,
+    // Indicates that the exercise should be compiled as a binary, and be
+    // formatted the way rustfmt formats it
+    Fmt,
+    // Indicates that the exercise should be compiled as a library, and that
+    // the examples in its doc comments should pass as doc tests
+    Doctest,
+    // Indicates that the exercise is a cargo package of its own, with a
+    // Cargo.toml, modules and tests, whose tests should pass like with
+    // `cargo test`
+    Project,
+    // Indicates that the exercise is verified by the plugin `plugin`,
+    // an executable speaking the protocol described in plugin.rs
+    Plugin,
+}
+
+// How difficult an exercise is, for curating learning paths
+#[derive(Deserialize, Serialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+pub fn parse_mode(value: &str) -> Result<Mode, String> {
+    match value {
+        "compile" => Ok(Mode::Compile),
+        "test" => Ok(Mode::Test),
+        "clippy" => Ok(Mode::Clippy),
+        "bench" => Ok(Mode::Bench),
+        "compile_fail" => Ok(Mode::CompileFail),
+        "property" => Ok(Mode::Property),
+        "fmt" => Ok(Mode::Fmt),
+        "doctest" => Ok(Mode::Doctest),
+        "project" => Ok(Mode::Project),
+        "plugin" => Ok(Mode::Plugin),
+        _ => Err(format!(
+            "unknown mode `{value}`, expected `compile`, `test`, `clippy`, `bench`, `compile_fail`, `property`, `fmt`, `doctest`, `project` or `plugin`"
+        )),
+    }
+}
+
+pub fn parse_difficulty(value: &str) -> Result<Difficulty, String> {
+    match value {
+        "easy" => Ok(Difficulty::Easy),
+        "medium" => Ok(Difficulty::Medium),
+        "hard" => Ok(Difficulty::Hard),
+        _ => Err(format!(
+            "unknown difficulty `{value}`, expected `easy`, `medium` or `hard`"
+        )),
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
+    // The exercises that are skipped in info.toml or excluded by the
+    // configuration, which nothing else sees
+    #[serde(skip)]
+    pub excluded: Vec<Exercise>,
+    // What the topics are about, for when they are finished in watch mode
+    #[serde(default)]
+    pub topics: Vec<Topic>,
+}
+
+impl ExerciseList {
+    // Read the exercises from the given info.toml file.
+    // The paths of the exercises are relative to the directory of that file,
+    // which allows loading exercise packs from other directories.
+    pub fn load(info_file: &Path) -> Result<ExerciseList, Box<dyn Error>> {
+        let mut list: ExerciseList = toml::from_str(&fs::read_to_string(info_file)?)?;
+        let base = info_file.parent().unwrap_or_else(|| Path::new(""));
+        let exclude = CONFIG.exclude.as_deref().unwrap_or_default();
+        for exercise in &mut list.exercises {
+            exercise.skip |= is_excluded(exercise, exclude);
+            if let (Mode::Bench, None) = (exercise.mode, exercise.max_time_ms) {
+                return Err(
+                    format!("bench exercise {} has no `max_time_ms`", exercise.name).into(),
+                );
+            }
+            if let Some(output_file) = exercise.output_file.take() {
+                if exercise.expected_output.is_some() {
+                    return Err(format!(
+                        "exercise {} has both an `output` and an `output_file`",
+                        exercise.name
+                    )
+                    .into());
+                }
+                let output_file = base.join(output_file);
+                exercise.expected_output = Some(
+                    fs::read_to_string(&output_file)
+                        .map_err(|e| format!("failed to read {}: {e}", output_file.display()))?,
+                );
+            }
+            if let (
+                Some(_),
+                Mode::Test
+                | Mode::Clippy
+                | Mode::Bench
+                | Mode::CompileFail
+                | Mode::Property
+                | Mode::Doctest
+                | Mode::Project
+                | Mode::Plugin,
+            ) = (&exercise.expected_output, exercise.mode)
+            {
+                return Err(format!(
+                    "exercise {} has an expected output, but only compile exercises can have one",
+                    exercise.name
+                )
+                .into());
+            }
+            match (exercise.mode, &exercise.plugin) {
+                (Mode::Plugin, None) => {
+                    return Err(format!("plugin exercise {} has no `plugin`", exercise.name).into())
+                }
+                (Mode::Plugin, Some(_)) => {}
+                (_, Some(_)) => {
+                    return Err(format!(
+                        "exercise {} has a `plugin`, but only plugin exercises can have one",
+                        exercise.name
+                    )
+                    .into())
+                }
+                (_, None) => {}
+            }
+            exercise.info_dir = base.to_path_buf();
+            if let Some(target) = &exercise.target {
+                check_target(exercise, target)?;
+            }
+            if let (Mode::CompileFail, None) = (exercise.mode, &exercise.expected_error) {
+                return Err(format!(
+                    "compile_fail exercise {} has no `expected_error`",
+                    exercise.name
+                )
+                .into());
+            }
+            exercise.path = base.join(&exercise.path);
+            exercise.find_files()?;
+            exercise.solution = match &exercise.solution {
+                Some(solution) => Some(base.join(solution)),
+                None => exercise
+                    .path
+                    .strip_prefix(exercises_dir(info_file))
+                    .ok()
+                    .map(|path| solutions_dir(info_file).join(path)),
+            };
+            exercise.hidden_tests = match &exercise.hidden_tests {
+                Some(hidden_tests) => Some(base.join(hidden_tests)),
+                None => exercise
+                    .path
+                    .strip_prefix(exercises_dir(info_file))
+                    .ok()
+                    .map(|path| hidden_tests_dir(info_file).join(path))
+                    .filter(|path| path.exists()),
+            };
+            if let (
+                Some(_),
+                Mode::Compile
+                | Mode::Clippy
+                | Mode::Bench
+                | Mode::CompileFail
+                | Mode::Fmt
+                | Mode::Doctest
+                | Mode::Project
+                | Mode::Plugin,
+            ) = (&exercise.hidden_tests, exercise.mode)
+            {
+                return Err(format!(
+                    "exercise {} has hidden tests, but only test and property exercises can have them",
+                    exercise.name
+                )
+                .into());
+            }
+        }
+        // Everything goes through the exercises in this order,
+        // so prerequisites are always done first
+        let (excluded, mut exercises): (Vec<Exercise>, Vec<Exercise>) =
+            graph::sort(list.exercises)?
+                .into_iter()
+                .partition(|e| e.skip);
+        // Nothing waits for exercises that are left out
+        for exercise in &mut exercises {
+            exercise
+                .requires
+                .retain(|name| !excluded.iter().any(|e| e.name == *name));
+        }
+        list.exercises = exercises;
+        list.excluded = excluded;
+        Ok(list)
+    }
+}
+
+// Why the exercise can't be compiled for the target, if it can't
+fn check_target(exercise: &Exercise, target: &str) -> Result<(), String> {
+    let name = &exercise.name;
+    if !wasm::is_supported(target) {
+        return Err(format!(
+            "unknown target `{target}` of exercise {name}, expected a WebAssembly one like \
+             `wasm32-wasip1` or `wasm32-unknown-unknown`"
+        ));
+    }
+    if !matches!(exercise.mode, Mode::Compile | Mode::Test) {
+        return Err(format!(
+            "exercise {name} has a `target`, but only compile and test exercises can have one"
+        ));
+    }
+    // Their rlibs are built for the machine rustlings runs on
+    if !exercise.deps.is_empty() {
+        return Err(format!(
+            "exercise {name} has a `target`, so it can't depend on crates"
+        ));
+    }
+    let runs =
+        exercise.mode == Mode::Test || exercise.interactive || exercise.expected_output.is_some();
+    if runs && !wasm::is_runnable(target) {
+        return Err(format!(
+            "exercise {name} has to be run, which `{target}` exercises can't be, \
+             use a WASI target like `wasm32-wasip1`"
+        ));
+    }
+    Ok(())
+}
+
+// Whether the name of the exercise, or its path as given in info.toml,
+// matches one of the given glob patterns
+fn is_excluded(exercise: &Exercise, patterns: &[String]) -> bool {
+    patterns.iter().any(|pattern| {
+        glob::Pattern::new(pattern).is_ok_and(|pattern| {
+            pattern.matches(&exercise.name) || pattern.matches_path(&exercise.path)
+        })
+    })
+}
+
+// The exercises folder next to the given info.toml file
+pub fn exercises_dir(info_file: &Path) -> PathBuf {
+    info_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("exercises")
+}
+
+// The solutions folder next to the given info.toml file
+pub fn solutions_dir(info_file: &Path) -> PathBuf {
+    info_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("solutions")
+}
+
+// The hidden_tests folder next to the given info.toml file
+pub fn hidden_tests_dir(info_file: &Path) -> PathBuf {
+    info_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("hidden_tests")
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
-    // The path to the file containing the exercise's source code
+    // The path to the file containing the exercise's source code.
+    // In info.toml, this may also be a directory containing the files of the
+    // exercise, in which case this becomes the path to its `entry` file.
     pub path: PathBuf,
+    // The file of a directory exercise that is compiled, `main.rs` by default
+    #[serde(default)]
+    pub entry: Option<String>,
+    // The other source files of a directory exercise, like its modules.
+    // These are found when loading info.toml.
+    #[serde(skip)]
+    pub files: Vec<PathBuf>,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
-    // The hint text associated with the exercise
-    pub hint: String,
+    // The hint text associated with the exercise, possibly split into levels
+    pub hint: Hint,
+    // The crates the exercise depends on, which it's compiled against and
+    // rust-analyzer is pointed at
+    #[serde(default)]
+    pub deps: Vec<String>,
+    // Additional lint levels for clippy exercises
+    #[serde(default)]
+    pub clippy_lints: Vec<ClippyLint>,
+    // How difficult the exercise is
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    // How many points the exercise is worth in `rustlings grade`, one by default
+    #[serde(default)]
+    pub points: Option<u32>,
+    // Tags describing what the exercise is about, like `traits`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The rustup toolchain to compile the exercise with, like `nightly`.
+    // By default, the toolchain rustup picks is used, which respects
+    // `RUSTUP_TOOLCHAIN` and overrides.
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    // The reference solution of the exercise. By default, this is the file
+    // with the same path in the solutions folder as the exercise has in the
+    // exercises folder.
+    #[serde(default)]
+    pub solution: Option<PathBuf>,
+    // Tests that are compiled along with the exercise without being shown to
+    // the learner, so that hardcoding answers doesn't get past them. By default,
+    // this is the file with the same path in the hidden_tests folder as the
+    // exercise has in the exercises folder, if there is one.
+    #[serde(default)]
+    pub hidden_tests: Option<PathBuf>,
+    // How many failed attempts unlock the solution, three by default
+    #[serde(default)]
+    pub solution_after: Option<u32>,
+    // Whether the exercise reads input from stdin, in which case it is only
+    // compiled when verifying it, and `rustlings run` connects it to the terminal
+    #[serde(default)]
+    pub interactive: bool,
+    // How long a bench exercise may take to run at most, in milliseconds
+    #[serde(default)]
+    pub max_time_ms: Option<u64>,
+    // How long compiling the exercise and running it may take, in seconds,
+    // instead of the configured timeouts
+    #[serde(default)]
+    pub compile_timeout: Option<u64>,
+    #[serde(default)]
+    pub run_timeout: Option<u64>,
+    // The code of the error a compile_fail exercise has to fail with, like `E0382`
+    #[serde(default)]
+    pub expected_error: Option<String>,
+    // What a compile exercise has to print to pass, given as `output` in info.toml.
+    // This is also read from `output_file` when loading info.toml.
+    #[serde(default, rename = "output")]
+    pub expected_output: Option<String>,
+    // The file with the expected output, relative to info.toml
+    #[serde(default)]
+    pub output_file: Option<PathBuf>,
+    // The names of the exercises that have to be done before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // Whether the exercise is left out, like one of a topic a course doesn't cover
+    #[serde(default)]
+    pub skip: bool,
+    // The name of the plugin that verifies a plugin exercise
+    #[serde(default)]
+    pub plugin: Option<String>,
+    // Settings the plugin is given as they are, like which tool to check with
+    #[serde(default)]
+    pub plugin_options: Option<toml::Value>,
+    // The folder of info.toml, which its paths are relative to
+    #[serde(skip)]
+    info_dir: PathBuf,
+    // The target a compile or test exercise is compiled for instead of the
+    // machine rustlings runs on, a WebAssembly one like `wasm32-wasip1`
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+// A lint level to pass to clippy, written as `level:lint` in info.toml,
+// like `deny:clippy::needless_range_loop`
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(try_from = "String")]
+pub struct ClippyLint {
+    // The command line flag for the level, like `-D`
+    flag: String,
+    // The name of the lint, like `clippy::needless_range_loop`
+    lint: String,
+}
+
+impl TryFrom<String> for ClippyLint {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<ClippyLint, String> {
+        let (level, lint) = value
+            .split_once(':')
+            .ok_or_else(|| format!("expected `level:lint`, found `{value}`"))?;
+        let flag = match level {
+            "allow" => "-A",
+            "warn" => "-W",
+            "deny" => "-D",
+            "forbid" => "-F",
+            _ => return Err(format!("unknown lint level `{level}` in `{value}`")),
+        };
+        Ok(ClippyLint {
+            flag: flag.to_string(),
+            lint: lint.to_string(),
+        })
+    }
 }
 
 // An enum to track of the state of an Exercise.
@@ -79,13 +512,43 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    // The compiled binary, which is either the temporary file or a cached binary
+    binary: PathBuf,
+    // The rendered errors of a compile_fail exercise, which has no binary
+    errors: Option<String>,
     _handle: FileHandle,
+    // Held until the exercise was run
+    _lock: Option<File>,
 }
 
 impl<'a> CompiledExercise<'a> {
-    // Run the compiled exercise
+    // Run the compiled exercise.
+    // For compile_fail exercises, the output are the errors they failed with.
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        self.run_with_args(&[])
+    }
+
+    // Run the compiled exercise with the given command line arguments
+    pub fn run_with_args(&self, args: &[String]) -> Result<ExerciseOutput, ExerciseOutput> {
+        if let Some(errors) = &self.errors {
+            return Ok(ExerciseOutput {
+                stdout: errors.clone(),
+                stderr: String::new(),
+            });
+        }
+        self.exercise.run(&self.binary, args)
+    }
+
+    // Run the compiled exercise connected to the terminal, so that it can read
+    // from stdin, returning whether it succeeded
+    pub fn run_interactively(&self, args: &[String]) -> bool {
+        output::log(format!("Running {} interactively", self.binary.display()));
+        let (mut command, _sandbox) = self.exercise.command_for(&self.binary);
+        command
+            .args(args)
+            .status()
+            .expect("Failed to run 'run' command")
+            .success()
     }
 }
 
@@ -98,7 +561,12 @@ pub struct ExerciseOutput {
     pub stderr: String,
 }
 
-struct FileHandle;
+// Removes the temporary files of the compiled exercise once it's dropped.
+// A cached binary comes with a shared lock on the cache of the exercise,
+// which keeps other runs from removing it as outdated while it's in use.
+struct FileHandle {
+    _cache_lock: Option<File>,
+}
 
 impl Drop for FileHandle {
     fn drop(&mut self) {
@@ -107,121 +575,907 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
-        let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        // Other runs of rustlings wait until this one is done with the exercise
+        let lock = session::lock_exercise(&self.name);
+        let start = Instant::now();
+        let compilation = self.build();
+        *LAST_COMPILE_TIME.lock().unwrap() = Some(start.elapsed());
+        compilation.map(|compiled| CompiledExercise {
+            _lock: lock,
+            ..compiled
+        })
+    }
+
+    fn build(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        // Plugins build whatever they need to themselves once they're run
+        if let Mode::Plugin = self.mode {
+            let plugin = self.find_plugin().ok_or_else(|| ExerciseOutput {
+                stdout: String::new(),
+                stderr: format!(
+                    "The plugin `{}` that verifies {} isn't installed. It has to be an \
+                     executable called `{}` in {} or on your PATH.",
+                    self.plugin.as_deref().unwrap_or_default(),
+                    self.name,
+                    self.plugin_file_name(),
+                    self.plugins_dir().display()
+                ),
+            })?;
+            return Ok(CompiledExercise {
+                exercise: self,
+                binary: plugin.path,
+                errors: None,
+                _handle: FileHandle { _cache_lock: None },
+                _lock: None,
+            });
+        }
+
+        // Binaries of unchanged exercises are reused instead of compiling them again
+        let cached_binary = self.cached_binary();
+        let cache_lock = cached_binary.as_ref().and_then(|_| self.lock_cache());
+        if let Some(binary) = cached_binary.as_ref().filter(|b| b.exists()) {
+            output::log(format!("Reusing the binary {}", binary.display()));
+            return Ok(CompiledExercise {
+                exercise: self,
+                binary: binary.clone(),
+                errors: None,
+                _handle: FileHandle {
+                    _cache_lock: cache_lock,
+                },
+                _lock: None,
+            });
+        }
+
+        let source = self.test_source()?;
+        let profile = self.cargo_profile();
+        // Cargo builds the crates itself
+        let deps = match profile {
+            Some(_) => Vec::new(),
+            None => self.deps_args()?,
+        };
+        let limits = Limits::compiling(self.compile_timeout);
+        let cmd = match (self.mode, profile) {
+            // Handed to the plugin as they are above
+            (Mode::Plugin, _) => unreachable!("plugin exercises aren't built by rustlings"),
+            (Mode::Project, _) => Project {
+                dir: self.location(),
+            }
+            .build_tests(self.command("cargo"), Path::new(&temp_tests()), &limits),
+            (_, Some(profile)) => Package {
+                name: &self.name,
+                source: &source,
+                deps: &self.deps,
+            }
+            .build(
+                self.command("cargo"),
+                profile,
+                Path::new(&temp_file()),
+                &limits,
+            ),
+            (Mode::Compile | Mode::Fmt, None) => self
+                .command("rustc")
+                .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(self.target_args())
+                .args(RUSTC_JSON_ARGS)
+                .args(RUSTC_EDITION_ARGS)
+                .args(&deps)
+                .logged_output_within(&limits),
+            (Mode::Test, None) => self
+                .command("rustc")
+                .args(["--test", source.to_str().unwrap(), "-o", &temp_file()])
+                .args(self.target_args())
+                .args(RUSTC_TEST_ARGS)
+                .args(RUSTC_JSON_ARGS)
+                .args(RUSTC_EDITION_ARGS)
+                .args(&deps)
+                .logged_output_within(&limits),
+            (Mode::Bench, None) => self
+                .command("rustc")
+                .args([self.path.to_str().unwrap(), "-O", "-o", &temp_file()])
+                .args(RUSTC_JSON_ARGS)
+                .args(RUSTC_EDITION_ARGS)
+                .args(&deps)
+                .logged_output_within(&limits),
+            (Mode::Property, None) => {
+                let harness = match property::harness(self.command("rustc")) {
+                    Ok(harness) => harness,
+                    Err(stderr) => {
+                        return Err(ExerciseOutput {
+                            stdout: String::new(),
+                            stderr,
+                        })
+                    }
+                };
+                self.command("rustc")
+                    .args(["--test", source.to_str().unwrap(), "-o", &temp_file()])
+                    .arg("--extern")
+                    .arg(format!("{}={}", property::CRATE_NAME, harness.display()))
+                    .args(RUSTC_TEST_ARGS)
+                    .args(RUSTC_JSON_ARGS)
+                    .args(RUSTC_EDITION_ARGS)
+                    .args(&deps)
+                    .logged_output_within(&limits)
+            }
+            // Checking the code is enough to get its errors, like with `cargo check`
+            (Mode::CompileFail, None) => self
+                .command("rustc")
+                .args([
+                    self.path.to_str().unwrap(),
+                    "--emit=metadata",
+                    "-o",
+                    &temp_file(),
+                ])
+                .args(RUSTC_JSON_ARGS)
                 .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+                .args(&deps)
+                .logged_output_within(&limits),
+            // The doc tests are run against the library later on
+            (Mode::Doctest, None) => self
+                .command("rustc")
+                .args([self.path.to_str().unwrap(), "--crate-type", "lib"])
+                .args(["--crate-name", &self.crate_name(), "-o", &temp_library()])
+                .args(RUSTC_JSON_ARGS)
                 .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Clippy => {
+                .args(&deps)
+                .logged_output_within(&limits),
+            (Mode::Clippy, None) => {
+                // The manifest lives next to the exercise, in the clippy folder
+                let cargo_toml_path = self.path.with_file_name("Cargo.toml");
+                // A build script next to the exercise would run while linting,
+                // which untrusted exercises mustn't be able to sneak in
+                let build = if sandbox::enabled() {
+                    "build = false\n"
+                } else {
+                    ""
+                };
+                let dependencies: String = self
+                    .deps
+                    .iter()
+                    .map(|name| format!("\n{name} = \"*\""))
+                    .collect();
                 let cargo_toml = format!(
                     r#"[package]
 name = "{}"
 version = "0.0.1"
 edition = "2021"
-[[bin]]
+{build}[[bin]]
 name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
+path = "{}"
+
+[dependencies]{dependencies}"#,
+                    self.name,
+                    self.name,
+                    self.path.file_name().unwrap().to_string_lossy()
                 );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+                let cargo_toml_error_msg = if output::ascii() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
                 };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                fs::write(&cargo_toml_path, cargo_toml).expect(cargo_toml_error_msg);
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
+                let prebuilt = self
+                    .command("rustc")
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
                     .args(RUSTC_COLOR_ARGS)
                     .args(RUSTC_EDITION_ARGS)
-                    .output()
-                    .expect("Failed to compile!");
+                    .args(&deps)
+                    .logged_output_within(&limits);
+                self.within_limit(prebuilt, true, "Failed to compile!")?;
                 // Due to an issue with Clippy, a cargo clean is required to catch all lints.
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
                 // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
                 // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                self.command("cargo")
+                    .args(["clean", "--manifest-path"])
+                    .arg(&cargo_toml_path)
                     .args(RUSTC_COLOR_ARGS)
-                    .output()
+                    .logged_output()
                     .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                self.command("cargo")
+                    .args(["clippy", "--manifest-path"])
+                    .arg(&cargo_toml_path)
                     .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
-                    .output()
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .args(self.clippy_lints.iter().flat_map(|l| [&l.flag, &l.lint]))
+                    .logged_output_within(&limits)
             }
-        }
-        .expect("Failed to run 'compile' command.");
+        };
+        let cmd = self.within_limit(cmd, true, "Failed to run 'compile' command.")?;
 
+        if let Mode::CompileFail = self.mode {
+            return self.expect_error(&cmd);
+        }
+        if let (Mode::Fmt, true) = (self.mode, cmd.status.success()) {
+            self.check_formatting(&limits)?;
+        }
         if cmd.status.success() {
+            let binary = match self.mode {
+                Mode::Doctest => PathBuf::from(temp_library()),
+                Mode::Project => PathBuf::from(temp_tests()),
+                _ => cached_binary
+                    .and_then(|binary| cache_binary(self, binary, cache_lock.as_ref()))
+                    .unwrap_or_else(|| PathBuf::from(temp_file())),
+            };
             Ok(CompiledExercise {
                 exercise: self,
-                _handle: FileHandle,
+                binary,
+                errors: None,
+                _handle: FileHandle {
+                    _cache_lock: cache_lock,
+                },
+                _lock: None,
             })
         } else {
             clean();
+            let stderr = String::from_utf8_lossy(&cmd.stderr);
+            let stderr = match self.mode {
+                Mode::Clippy => stderr.to_string(),
+                _ => self.unhide(diagnostics::render(&stderr)),
+            };
             Err(ExerciseOutput {
                 stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                stderr,
             })
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            _ => "",
+    // The source to compile a test exercise from: the exercise itself, or a
+    // copy with its hidden tests added as a module. The copy is kept in the
+    // cache, so that the learner doesn't come across it and watch mode doesn't
+    // notice it. Modules of directory exercises are still found next to it.
+    fn test_source(&self) -> Result<PathBuf, ExerciseOutput> {
+        let Some(hidden_tests) = &self.hidden_tests else {
+            return Ok(self.path.clone());
+        };
+        let with_hidden_tests = || -> io::Result<PathBuf> {
+            let dir = match self.path.parent() {
+                Some(dir) if dir != Path::new("") => dir.canonicalize()?,
+                _ => PathBuf::from(".").canonicalize()?,
+            };
+            let module =
+                Regex::new(r"(?m)^([ \t]*)((?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;)").unwrap();
+            let source = fs::read_to_string(&self.path)?;
+            let source = module.replace_all(&source, |caps: &Captures| {
+                let name = &caps[3];
+                let file = [
+                    dir.join(format!("{name}.rs")),
+                    dir.join(name).join("mod.rs"),
+                ]
+                .into_iter()
+                .find(|file| file.exists());
+                match file {
+                    // On the same line, so that line numbers stay the same
+                    Some(file) => format!("{}#[path = {:?}] {}", &caps[1], file, &caps[2]),
+                    None => caps[0].to_string(),
+                }
+            });
+            let source = format!(
+                "{source}\n#[cfg(test)]\n#[path = {:?}]\nmod rustlings_hidden_tests;\n",
+                hidden_tests.canonicalize()?
+            );
+            let path = self.hidden_source();
+            fs::create_dir_all(HIDDEN_TESTS_CACHE_DIR)?;
+            fs::write(&path, source)?;
+            Ok(path)
+        };
+        with_hidden_tests().map_err(|e| ExerciseOutput {
+            stdout: String::new(),
+            stderr: format!("Failed to add the hidden tests of {self}: {e}"),
+        })
+    }
+
+    // Build the crates the exercise depends on with its toolchain, unless
+    // they already were. Returns the errors of cargo if that failed.
+    pub fn build_deps(&self) -> Result<Option<deps::Libraries>, String> {
+        if self.deps.is_empty() {
+            return Ok(None);
+        }
+        deps::build(&self.deps, |program| self.command(program)).map(Some)
+    }
+
+    // How the exercise is built with the cargo backend, if it's built with
+    // cargo at all. Other modes need more from rustc than cargo can pass on.
+    fn cargo_profile(&self) -> Option<Profile> {
+        // The packages cargo builds are for the machine rustlings runs on
+        if backend::backend() != Backend::Cargo || self.target.is_some() {
+            return None;
+        }
+        match self.mode {
+            Mode::Compile | Mode::Fmt => Some(Profile::Dev),
+            Mode::Test => Some(Profile::Test),
+            Mode::Bench => Some(Profile::Release),
+            _ => None,
+        }
+    }
+
+    // Lint the exercise with clippy for `rustlings lint`, whatever its mode.
+    // The diagnostics are in stderr as JSON. Returns `None` for exercises
+    // clippy can't check on their own: compile_fail ones are meant not to
+    // compile, property ones need the harness of rustlings, and what plugin
+    // ones are like is up to their plugin.
+    pub fn lint(&self) -> Option<Result<Output, ExerciseOutput>> {
+        let target = match self.mode {
+            Mode::Compile | Mode::Fmt | Mode::Clippy | Mode::Bench => Some(Target::Bin),
+            Mode::Test => Some(Target::Test),
+            Mode::Doctest => Some(Target::Lib),
+            // Linted as the package it is
+            Mode::Project => None,
+            Mode::CompileFail | Mode::Property | Mode::Plugin => return None,
+        };
+        let lints: Vec<&String> = self
+            .clippy_lints
+            .iter()
+            .flat_map(|l| [&l.flag, &l.lint])
+            .collect();
+        let limits = Limits::compiling(self.compile_timeout);
+        let output = match target {
+            Some(target) => Package {
+                name: &self.name,
+                source: &self.path,
+                deps: &self.deps,
+            }
+            .lint(self.command("cargo"), target, &lints, &limits),
+            None => Project {
+                dir: self.location(),
+            }
+            .lint(self.command("cargo"), &lints, &limits),
+        };
+        Some(self.within_limit(output, true, "Failed to run 'cargo clippy'"))
+    }
+
+    // The arguments to compile the exercise against the crates it depends on
+    fn deps_args(&self) -> Result<Vec<OsString>, ExerciseOutput> {
+        match self.build_deps() {
+            Ok(libraries) => Ok(libraries.map(|l| l.args()).unwrap_or_default()),
+            Err(stderr) => Err(ExerciseOutput {
+                stdout: String::new(),
+                stderr,
+            }),
+        }
+    }
+
+    // Where the copy of the exercise with its hidden tests is kept
+    fn hidden_source(&self) -> PathBuf {
+        Path::new(HIDDEN_TESTS_CACHE_DIR).join(format!("{}.rs", self.name))
+    }
+
+    // Point output about the copy with the hidden tests to the exercise itself
+    fn unhide(&self, output: String) -> String {
+        if self.hidden_tests.is_none() {
+            return output;
+        }
+        output.replace(
+            &*self.hidden_source().to_string_lossy(),
+            &self.path.to_string_lossy(),
+        )
+    }
+
+    // A fmt exercise passes once `rustfmt --check` finds nothing to change.
+    // Otherwise, the diff of what rustfmt would change is the output.
+    fn check_formatting(&self, limits: &Limits) -> Result<(), ExerciseOutput> {
+        let color = if console::colors_enabled() {
+            "always"
+        } else {
+            "never"
+        };
+        let cmd = self
+            .command("rustfmt")
+            .args(["--check", "--color", color])
+            .args(RUSTC_EDITION_ARGS)
+            .args(self.sources())
+            .logged_output_within(limits);
+        let cmd = match cmd {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: "rustfmt can't be found, install it with                              `rustup component add rustfmt`"
+                        .to_string(),
+                });
+            }
+            Err(e) if e.kind() != io::ErrorKind::TimedOut => {
+                return Err(ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: format!("Failed to run rustfmt: {e}"),
+                });
+            }
+            cmd => self.within_limit(cmd, true, "Failed to run rustfmt")?,
+        };
+        if cmd.status.success() {
+            return Ok(());
+        }
+        clean();
+        let diff = String::from_utf8_lossy(&cmd.stdout);
+        let stderr = if diff.trim().is_empty() {
+            // Like when rustfmt isn't installed for the toolchain
+            String::from_utf8_lossy(&cmd.stderr).to_string()
+        } else {
+            format!(
+                "The code isn't formatted the way rustfmt formats it:\n{}\n\
+                 (- is how it's formatted, + is how rustfmt formats it)\n",
+                diff.trim_end()
+            )
+        };
+        Err(ExerciseOutput {
+            stdout: String::new(),
+            stderr,
+        })
+    }
+
+    // A compile_fail exercise passes if rustc rejected it with the expected error
+    fn expect_error(&self, cmd: &Output) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        clean();
+        let expected = self.expected_error.as_deref().unwrap_or_default();
+        let stderr = String::from_utf8_lossy(&cmd.stderr);
+        let errors = diagnostics::render(&stderr);
+        let codes = diagnostics::error_codes(&stderr);
+        if codes.iter().any(|code| code == expected) {
+            return Ok(CompiledExercise {
+                exercise: self,
+                binary: PathBuf::from(temp_file()),
+                errors: Some(errors),
+                _handle: FileHandle { _cache_lock: None },
+                _lock: None,
+            });
+        }
+        let explanation = if cmd.status.success() {
+            format!("The code compiles, but it should fail to compile with error {expected}.")
+        } else if codes.is_empty() {
+            format!("The code should fail to compile with error {expected}, but the errors above have no code.")
+        } else {
+            format!(
+                "The code should fail to compile with error {expected}, but it fails with {} instead.",
+                codes.join(", ")
+            )
         };
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        Err(ExerciseOutput {
+            stdout: String::new(),
+            stderr: format!("{errors}{explanation}"),
+        })
+    }
+
+    // A command running the given rustup proxy, like `rustc`,
+    // with the toolchain of the exercise
+    fn command(&self, program: &str) -> Command {
+        let mut command = Command::new(program);
+        if let Some(toolchain) = &self.toolchain {
+            command.env("RUSTUP_TOOLCHAIN", toolchain);
+        }
+        command
+    }
+
+    // The output of a command compiling or running the exercise, or else the
+    // diagnostic for when it took too long. Panics with the message if the
+    // command couldn't be run at all.
+    fn within_limit(
+        &self,
+        output: io::Result<Output>,
+        compiling: bool,
+        message: &str,
+    ) -> Result<Output, ExerciseOutput> {
+        match output {
+            Ok(output) => Ok(output),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => Err(self.timed_out(e, compiling)),
+            Err(e) => panic!("{message}: {e:?}"),
+        }
+    }
+
+    // The diagnostic for when compiling or running the exercise took too long
+    fn timed_out(&self, e: io::Error, compiling: bool) -> ExerciseOutput {
+        let stderr = if compiling {
+            clean();
+            format!(
+                "Compiling {} {e} and was stopped. Is the compiler stuck on something, like \
+                 a constant that takes forever to evaluate? The limit can be raised with \
+                 `compile_timeout` in info.toml, or `compile` in the `[timeouts]` section \
+                 of rustlings.toml.",
+                self.name
+            )
+        } else {
+            format!(
+                "{} {e} and was stopped. Is it stuck in an infinite loop, or waiting for \
+                 something that never happens, like a thread that never finishes? The limit \
+                 can be raised with `run_timeout` in info.toml, or `run` in the `[timeouts]` \
+                 section of rustlings.toml.",
+                self.name
+            )
+        };
+        ExerciseOutput {
+            stdout: String::new(),
+            stderr,
+        }
+    }
+
+    fn run(&self, binary: &Path, args: &[String]) -> Result<ExerciseOutput, ExerciseOutput> {
+        match self.mode {
+            Mode::Bench => return self.bench(binary, args),
+            Mode::Doctest => return self.doctest(binary),
+            Mode::Project => return self.test_project(binary, args),
+            Mode::Plugin => {
+                let plugin = Executable {
+                    path: binary.to_path_buf(),
+                };
+                return self.verify_with(&plugin, args);
+            }
+            _ => {}
+        }
+        // There's nothing to run modules for `wasm32-unknown-unknown` with
+        if self
+            .target
+            .as_deref()
+            .is_some_and(|t| !wasm::is_runnable(t))
+        {
+            return Ok(ExerciseOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            });
+        }
+        let (mut command, _sandbox) = self.command_for(binary);
+        if let Mode::Test | Mode::Property = self.mode {
+            // Panics abort test harnesses compiled to WebAssembly, which loses
+            // the output they captured, along with the message of the panic
+            command.arg(match self.target {
+                Some(_) => "--nocapture",
+                None => "--show-output",
+            });
+            // For the functions of the exercise a panic came through
+            if env::var_os("RUST_BACKTRACE").is_none() {
+                command.env("RUST_BACKTRACE", "1");
+            }
+        }
+        let limits = Limits::running(self.run_timeout);
+        let cmd = command.args(args).logged_output_within(&limits);
+        if let (Some(_), Err(e)) = (&self.target, &cmd) {
+            if e.kind() == io::ErrorKind::NotFound {
+                return Err(ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: wasm::missing_runtime(&self.name),
+                });
+            }
+        }
+        let cmd = self.within_limit(cmd, false, "Failed to run 'run' command")?;
+
+        let mut output = ExerciseOutput {
+            stdout: self.unhide(String::from_utf8_lossy(&cmd.stdout).to_string()),
+            stderr: self.unhide(String::from_utf8_lossy(&cmd.stderr).to_string()),
+        };
+
+        if !cmd.status.success() {
+            // The whole output of libtest is there with `--verbose`
+            if let (Mode::Test | Mode::Property, false) = (self.mode, output::verbose()) {
+                let files: Vec<&Path> = self.sources().collect();
+                if let Some(rendered) = panics::render(&output.stdout, &files) {
+                    output.stdout = rendered;
+                }
+            }
+            return Err(output);
+        }
+        // Exercises that compile can still get the logic wrong
+        let expected = self.expected_output.as_deref();
+        if let Some(mismatch) = expected.and_then(|e| output_mismatch(e, &output.stdout)) {
+            output.stderr.push_str(&mismatch);
+            return Err(output);
+        }
+        Ok(output)
+    }
+
+    // The command that runs the compiled exercise, or the WebAssembly runtime
+    // for exercises compiled to WebAssembly, in the sandbox if there is one
+    fn command_for(&self, binary: &Path) -> (Command, Option<Sandbox>) {
+        match &self.target {
+            Some(_) => wasm::command(binary),
+            None => sandbox::command(binary),
+        }
+    }
+
+    // The arguments that make rustc compile the exercise for its target
+    fn target_args(&self) -> Vec<&str> {
+        match &self.target {
+            Some(target) => vec!["--target", target],
+            None => Vec::new(),
+        }
+    }
 
+    // Ask the plugin of a plugin exercise whether it passes. Plugins often
+    // compile the exercise first, so they get as long as compiling takes.
+    fn verify_with(
+        &self,
+        plugin: &dyn ModeRunner,
+        args: &[String],
+    ) -> Result<ExerciseOutput, ExerciseOutput> {
+        let request = plugin::Request::verify(self, args, output::verbose());
+        let limits = Limits::compiling(self.compile_timeout);
+        let response = plugin
+            .verify(&request, &limits)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::TimedOut => self.timed_out(e, true),
+                _ => ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: format!(
+                        "The plugin `{}` failed to verify {}: {e}",
+                        self.plugin.as_deref().unwrap_or_default(),
+                        self.name
+                    ),
+                },
+            })?;
         let output = ExerciseOutput {
+            stdout: response.stdout,
+            stderr: response.stderr,
+        };
+        if response.passed {
+            Ok(output)
+        } else {
+            Err(output)
+        }
+    }
+
+    // The executable of the plugin of a plugin exercise, if it's installed
+    pub fn find_plugin(&self) -> Option<Executable> {
+        plugin::find(self.plugin.as_deref()?, &self.plugins_dir())
+    }
+
+    // Where the plugins of the exercises are looked for first,
+    // the plugins folder next to info.toml
+    fn plugins_dir(&self) -> PathBuf {
+        self.info_dir.join("plugins")
+    }
+
+    // The folder of the info.toml the exercise comes from
+    pub fn info_dir(&self) -> &Path {
+        &self.info_dir
+    }
+
+    // What the executable of the plugin of a plugin exercise is called
+    pub fn plugin_file_name(&self) -> String {
+        plugin::file_name(self.plugin.as_deref().unwrap_or_default())
+    }
+
+    // Run the doc tests of a doctest exercise against its compiled library.
+    // An exercise without any doc tests doesn't pass, since writing them is
+    // what it's about.
+    fn doctest(&self, library: &Path) -> Result<ExerciseOutput, ExerciseOutput> {
+        let crate_name = self.crate_name();
+        let deps = self.deps_args()?;
+        let color = if console::colors_enabled() {
+            "always"
+        } else {
+            "never"
+        };
+        // rustdoc compiles every doc test before running it
+        let limits = Limits {
+            time: Limits::compiling(self.compile_timeout).time,
+            ..Limits::running(self.run_timeout)
+        };
+        let mut command = self.command("rustdoc");
+        // Backtraces only lead into the `main` function rustdoc wraps the
+        // examples in
+        if !output::verbose() {
+            command.env("RUST_BACKTRACE", "0");
+        }
+        let cmd = command
+            .args(["--test", self.path.to_str().unwrap()])
+            .args(["--crate-name", &crate_name, "--extern"])
+            .arg(format!("{crate_name}={}", library.display()))
+            .args(["--color", color])
+            .args(RUSTC_EDITION_ARGS)
+            .args(&deps)
+            .logged_output_within(&limits);
+        let cmd = self.within_limit(cmd, false, "Failed to run rustdoc")?;
+
+        let mut output = ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
         };
+        if !cmd.status.success() {
+            return Err(output);
+        }
+        if output.stdout.contains("running 0 tests") {
+            output.stdout.clear();
+            output.stderr.push_str(&format!(
+                "There are no doc tests in {} yet. Write an example in a ``` code block in \
+                 the doc comment of an item, using the library as `{crate_name}`.",
+                self.path.display()
+            ));
+            return Err(output);
+        }
+        Ok(output)
+    }
 
-        if cmd.status.success() {
+    // Run the test harnesses of a project exercise one after another, like
+    // `cargo test` does, stopping at the first one that fails. A project
+    // without any tests doesn't pass, since they're what it's verified by.
+    fn test_project(
+        &self,
+        harnesses: &Path,
+        args: &[String],
+    ) -> Result<ExerciseOutput, ExerciseOutput> {
+        // Absolute, since they run in the directory of the project
+        let mut harnesses: Vec<PathBuf> = fs::read_dir(harnesses)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| fs::canonicalize(entry.path()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        harnesses.sort();
+        let mut output = ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        let mut tested = false;
+        for harness in harnesses {
+            let (mut command, sandbox) = sandbox::command(&harness);
+            // Tests find the files of the project like they do with `cargo test`
+            if sandbox.is_none() {
+                command.current_dir(self.location());
+            }
+            command.arg("--show-output");
+            if env::var_os("RUST_BACKTRACE").is_none() {
+                command.env("RUST_BACKTRACE", "1");
+            }
+            let cmd = command
+                .args(args)
+                .logged_output_within(&Limits::running(self.run_timeout));
+            let cmd = self.within_limit(cmd, false, "Failed to run 'run' command")?;
+            let stdout = String::from_utf8_lossy(&cmd.stdout).to_string();
+            output
+                .stderr
+                .push_str(&String::from_utf8_lossy(&cmd.stderr));
+            if !cmd.status.success() {
+                // Only the harness that failed matters then
+                output.stdout = stdout;
+                if !output::verbose() {
+                    let files: Vec<&Path> = self.sources().collect();
+                    if let Some(rendered) = panics::render(&output.stdout, &files) {
+                        output.stdout = rendered;
+                    }
+                }
+                return Err(output);
+            }
+            tested |= !stdout.contains("running 0 tests");
+            output.stdout.push_str(&stdout);
+        }
+        if !tested {
+            output.stdout.clear();
+            output.stderr.push_str(&format!(
+                "There are no tests in {} yet. Write them in its `tests` folder, or in \
+                 `#[cfg(test)]` modules next to the code.",
+                self.location().display()
+            ));
+            return Err(output);
+        }
+        Ok(output)
+    }
+
+    // The name of the library a doctest exercise is compiled to, which its
+    // doc tests use it by
+    fn crate_name(&self) -> String {
+        self.name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    // Run the binary of a bench exercise several times, passing if the fastest
+    // run took at most `max_time_ms`. Taking the fastest run leaves out most
+    // of the noise of other programs running at the same time.
+    fn bench(&self, binary: &Path, args: &[String]) -> Result<ExerciseOutput, ExerciseOutput> {
+        let mut fastest = Duration::MAX;
+        let mut output = ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        for _ in 0..BENCH_RUNS {
+            let start = Instant::now();
+            let (mut command, _sandbox) = sandbox::command(binary);
+            let cmd = command
+                .args(args)
+                .logged_output_within(&Limits::running(self.run_timeout));
+            let cmd = self.within_limit(cmd, false, "Failed to run 'run' command")?;
+            fastest = fastest.min(start.elapsed());
+            output = ExerciseOutput {
+                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            };
+            if !cmd.status.success() {
+                return Err(output);
+            }
+        }
+
+        let max_time = Duration::from_millis(self.max_time_ms.unwrap_or(u64::MAX));
+        let timing = format!(
+            "The fastest of {BENCH_RUNS} runs took {:.1} ms, at most {} ms are allowed",
+            fastest.as_secs_f64() * 1000.0,
+            max_time.as_millis()
+        );
+        if fastest <= max_time {
+            output.stdout.push_str(&timing);
             Ok(output)
         } else {
+            output.stderr.push_str(&timing);
             Err(output)
         }
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
-
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
-        };
+    // The path the compiled binary of the exercise is cached at.
+    // The file name contains a hash of the source, so that it changes whenever
+    // the exercise is edited. Clippy exercises are always compiled again,
+    // since clippy has to run on them anyway, and compile_fail and doctest
+    // exercises don't have a binary.
+    fn cached_binary(&self) -> Option<PathBuf> {
+        if let Mode::Clippy
+        | Mode::CompileFail
+        | Mode::Fmt
+        | Mode::Doctest
+        | Mode::Project
+        | Mode::Plugin = self.mode
+        {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        for path in self.sources() {
+            fs::read(path).ok()?.hash(&mut hasher);
+        }
+        if let Some(hidden_tests) = &self.hidden_tests {
+            fs::read(hidden_tests).ok()?.hash(&mut hasher);
+        }
+        self.mode.hash(&mut hasher);
+        self.deps.hash(&mut hasher);
+        if let Mode::Test | Mode::Property = self.mode {
+            RUSTC_TEST_ARGS.hash(&mut hasher);
+        }
+        if let Mode::Property = self.mode {
+            property::HARNESS.hash(&mut hasher);
+        }
+        self.toolchain.hash(&mut hasher);
+        self.target.hash(&mut hasher);
+        RUSTC_EDITION_ARGS.hash(&mut hasher);
+        Some(PathBuf::from(COMPILE_CACHE_DIR).join(format!(
+            "{}-{:016x}",
+            self.name,
+            hasher.finish()
+        )))
+    }
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+    // A shared lock on the cached binaries of the exercise, or `None` if the
+    // lock can't be taken
+    fn lock_cache(&self) -> Option<File> {
+        fs::create_dir_all(COMPILE_CACHE_DIR).ok()?;
+        let path = Path::new(COMPILE_CACHE_DIR).join(format!("{}.lock", self.name));
+        let lock = File::create(path).ok()?;
+        lock.lock_shared().ok()?;
+        Some(lock)
+    }
 
-        if !re.is_match(&source) {
-            return State::Done;
+    // Whether the exercise is done, given that it passes. With the marker, it
+    // is done once none of its files contain the `I AM NOT DONE` marker.
+    // The context is taken from the first file that still contains it.
+    // Without it, there's no context to show.
+    pub fn state(&self) -> State {
+        match state::strategy() {
+            DoneStrategy::Marker => {}
+            DoneStrategy::State if !Progress::load().is_completed(self) => {
+                return State::Pending(Vec::new())
+            }
+            DoneStrategy::State | DoneStrategy::Passing => return State::Done,
         }
+        let Some(path) = self.sources().find(|path| state::is_pending(path)) else {
+            return State::Done;
+        };
+        let mut source_file = File::open(path).expect("We were unable to open the exercise file!");
+        let mut source = String::new();
+        source_file
+            .read_to_string(&mut source)
+            .expect("We were unable to read the exercise file!");
 
         let matched_line_index = source
             .lines()
             .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
+            .find_map(|(i, line)| if MARKER.is_match(line) { Some(i) } else { None })
             .expect("This should not happen at all");
 
         let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
@@ -241,14 +1495,147 @@ path = "{}.rs""#,
         State::Pending(context)
     }
 
+    // Where the `I AM NOT DONE` marker is: the file, and the line and column
+    // its comment starts at, both counted from one. The exercise's own file is
+    // returned for exercises that don't have a marker anymore.
+    pub fn marker(&self) -> (&Path, usize, usize) {
+        let Some(path) = self.sources().find(|path| state::is_pending(path)) else {
+            return (&self.path, 1, 1);
+        };
+        let source = fs::read_to_string(path).unwrap_or_default();
+        source
+            .lines()
+            .enumerate()
+            .find(|(_, line)| MARKER.is_match(line))
+            .map_or((path, 1, 1), |(i, line)| {
+                let column = line.len() - line.trim_start().len();
+                (path, i + 1, column + 1)
+            })
+    }
+
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
     // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
+    // the exercise; which would be both costly and counterintuitive.
+    // Unless the marker tells, whether it was done when it was last
+    // checked is taken from the state file instead.
     pub fn looks_done(&self) -> bool {
-        self.state() == State::Done
+        match state::strategy() {
+            DoneStrategy::Marker => !self.sources().any(state::is_pending),
+            DoneStrategy::State => Progress::load().is_completed(self),
+            DoneStrategy::Passing => {
+                let progress = Progress::load();
+                progress.is_completed(self)
+                    && !progress.stats.get(&self.name).is_some_and(|s| s.failing)
+            }
+        }
+    }
+
+    // Whether the exercise is done, given that it passes
+    pub fn done_if_passing(&self) -> bool {
+        match state::strategy() {
+            DoneStrategy::Marker => !self.sources().any(state::is_pending),
+            DoneStrategy::State => Progress::load().is_completed(self),
+            DoneStrategy::Passing => true,
+        }
+    }
+
+    // Whether the exercise has all of the given tags and the given difficulty, if any
+    pub fn has_metadata(&self, tags: &[String], difficulty: Option<Difficulty>) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+            && difficulty.is_none_or(|difficulty| self.difficulty == Some(difficulty))
+    }
+
+    // All source files of the exercise, starting with the one that is compiled
+    pub fn sources(&self) -> impl Iterator<Item = &Path> {
+        std::iter::once(self.path.as_path()).chain(self.files.iter().map(PathBuf::as_path))
+    }
+
+    // Whether the given file is one of the source files of the exercise
+    pub fn contains(&self, file: &Path) -> bool {
+        self.sources().any(|path| file.ends_with(path))
+    }
+
+    // If the exercise is a directory, point `path` at its entry file,
+    // and collect the other source files in the directory. The entry of a
+    // project exercise is its library, or else its binary, and its Cargo.toml
+    // is one of its files, while what cargo built into `target` isn't.
+    fn find_files(&mut self) -> Result<(), Box<dyn Error>> {
+        let project = self.mode == Mode::Project;
+        if !self.path.is_dir() {
+            if project {
+                return Err(format!(
+                    "project exercise {} has to be a directory with a Cargo.toml",
+                    self.name
+                )
+                .into());
+            }
+            self.entry = None;
+            return Ok(());
+        }
+        let dir = self.path.clone();
+        if project && !dir.join("Cargo.toml").exists() {
+            return Err(format!(
+                "project exercise {} has no Cargo.toml in {}",
+                self.name,
+                dir.display()
+            )
+            .into());
+        }
+        let default_entry = if !project {
+            "main.rs"
+        } else if dir.join("src/main.rs").exists() && !dir.join("src/lib.rs").exists() {
+            "src/main.rs"
+        } else {
+            "src/lib.rs"
+        };
+        let entry = self.entry.get_or_insert_with(|| default_entry.to_string());
+        self.path = dir.join(entry);
+        let pattern = format!("{}/**/*.rs", glob::Pattern::escape(&dir.to_string_lossy()));
+        for file in glob::glob(&pattern)? {
+            let file = file?;
+            if file != self.path && !(project && file.starts_with(dir.join("target"))) {
+                self.files.push(file);
+            }
+        }
+        if project {
+            self.files.push(dir.join("Cargo.toml"));
+        }
+        Ok(())
+    }
+
+    // The file of the exercise, or its directory if it spans several files
+    fn location(&self) -> &Path {
+        match &self.entry {
+            Some(entry) => self
+                .path
+                .ancestors()
+                .nth(Path::new(entry).components().count())
+                .unwrap_or(&self.path),
+            None => &self.path,
+        }
+    }
+
+    // The directory the exercise lives in, which contains the README of its topic
+    pub fn topic_dir(&self) -> &Path {
+        self.location().parent().unwrap_or_else(|| Path::new(""))
+    }
+
+    // The topic of the exercise, which is the name of the directory it lives in.
+    // Exercises that live directly in the exercises folder (like the quizzes)
+    // are their own topic.
+    pub fn topic(&self) -> String {
+        let path = self.location();
+        let topic = if path.components().count() > 2 {
+            path.parent().and_then(|p| p.file_name())
+        } else {
+            path.file_stem()
+        };
+        topic
+            .map(|t| t.to_string_lossy().to_string())
+            .unwrap_or_default()
     }
 }
 
@@ -258,9 +1645,96 @@ impl Display for Exercise {
     }
 }
 
+// Move the freshly compiled temporary binary into the cache, replacing any
+// outdated binaries of the same exercise. Returns None if that failed,
+// in which case the temporary binary is used as is. Outdated binaries are
+// only removed when no other run holds the lock on them, and are left to
+// a later run otherwise.
+fn cache_binary(exercise: &Exercise, binary: PathBuf, lock: Option<&File>) -> Option<PathBuf> {
+    fs::create_dir_all(COMPILE_CACHE_DIR).ok()?;
+    let exclusive = match lock {
+        Some(lock) => lock.unlock().is_ok() && lock.try_lock().is_ok(),
+        None => true,
+    };
+    if exclusive {
+        let outdated = format!(
+            "{}/{}-*",
+            COMPILE_CACHE_DIR,
+            glob::Pattern::escape(&exercise.name)
+        );
+        for path in glob::glob(&outdated).ok()?.flatten() {
+            if path != binary && is_binary_of(&path, &exercise.name) {
+                let _ignored = remove_file(path);
+            }
+        }
+    }
+    if let Some(lock) = lock {
+        lock.unlock().ok()?;
+        lock.lock_shared().ok()?;
+    }
+    fs::rename(temp_file(), &binary).ok()?;
+    output::log(format!("Cached {} as {}", temp_file(), binary.display()));
+    Some(binary)
+}
+
+// Whether the file is a cached binary of the exercise with the given name,
+// and not one of another exercise whose name starts the same, like `vecs1-2`
+// for `vecs1`
+fn is_binary_of(path: &Path, name: &str) -> bool {
+    path.file_name()
+        .and_then(|file| file.to_str()?.strip_prefix(name)?.strip_prefix('-'))
+        .is_some_and(|hash| hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// Where the output of an exercise differs from the expected output, if it does.
+// Line endings and whitespace at the end of lines and of the output are ignored.
+fn output_mismatch(expected: &str, actual: &str) -> Option<String> {
+    let lines = |output: &str| -> Vec<String> {
+        let mut lines: Vec<String> = output.lines().map(|l| l.trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    let (expected, actual) = (lines(expected), lines(actual));
+    if expected == actual {
+        return None;
+    }
+    let mut mismatch = String::from("The output isn't the expected one:\n");
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => mismatch.push_str(&format!("  {e}\n")),
+            (e, a) => {
+                if let Some(e) = e {
+                    mismatch.push_str(&format!(
+                        "{}\n",
+                        theme::success().apply_to(format!("- {e}"))
+                    ));
+                }
+                if let Some(a) = a {
+                    mismatch.push_str(&format!(
+                        "{}\n",
+                        theme::failure().apply_to(format!("+ {a}"))
+                    ));
+                }
+            }
+        }
+    }
+    mismatch.push_str("(- is expected, + is printed)\n");
+    Some(mismatch)
+}
+
 #[inline]
 fn clean() {
-    let _ignored = remove_file(&temp_file());
+    for file in [temp_file(), temp_library()] {
+        if remove_file(&file).is_ok() {
+            output::log(format!("Removed {file}"));
+        }
+    }
+    let tests = temp_tests();
+    if fs::remove_dir_all(&tests).is_ok() {
+        output::log(format!("Removed {tests}"));
+    }
 }
 
 #[cfg(test)]
@@ -268,28 +1742,123 @@ mod test {
     use super::*;
     use std::path::Path;
 
+    // An exercise with the given name, path and mode, and nothing else
+    fn exercise(name: &str, path: &str, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            mode,
+            hint: String::new().into(),
+            deps: Vec::new(),
+            clippy_lints: Vec::new(),
+            entry: None,
+            files: Vec::new(),
+            difficulty: None,
+            points: None,
+            tags: Vec::new(),
+            toolchain: None,
+            solution: None,
+            hidden_tests: None,
+            solution_after: None,
+            skip: false,
+            interactive: false,
+            max_time_ms: None,
+            compile_timeout: None,
+            run_timeout: None,
+            expected_error: None,
+            expected_output: None,
+            output_file: None,
+            requires: Vec::new(),
+            plugin: None,
+            plugin_options: None,
+            info_dir: PathBuf::new(),
+            target: None,
+        }
+    }
+
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
+        File::create(temp_file()).unwrap();
+        let exercise = exercise(
+            "example",
+            "tests/fixture/state/pending_exercise.rs",
+            Mode::Compile,
+        );
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
     }
 
+    #[test]
+    fn test_compiled_binary_is_cached() {
+        let exercise = exercise(
+            "cached_exercise",
+            "tests/fixture/success/compSuccess.rs",
+            Mode::Compile,
+        );
+        let binary = exercise.compile().unwrap().binary;
+        assert!(binary.starts_with(COMPILE_CACHE_DIR));
+        assert!(binary.exists());
+        assert_eq!(exercise.compile().unwrap().binary, binary);
+    }
+
+    #[test]
+    fn test_project_files() {
+        let list = ExerciseList::load(Path::new("tests/fixture/project/info.toml")).unwrap();
+        let inventory = &list.exercises[0];
+        assert_eq!(
+            inventory.path,
+            Path::new("tests/fixture/project/inventory/src/lib.rs")
+        );
+        assert_eq!(
+            inventory.location(),
+            Path::new("tests/fixture/project/inventory")
+        );
+        assert_eq!(inventory.topic(), "project");
+        assert!(inventory
+            .files
+            .contains(&PathBuf::from("tests/fixture/project/inventory/Cargo.toml")));
+        assert!(inventory.files.contains(&PathBuf::from(
+            "tests/fixture/project/inventory/tests/inventory.rs"
+        )));
+    }
+
+    #[test]
+    fn test_is_binary_of() {
+        let cached = Path::new(".rustlings-cache/vecs1-0123456789abcdef");
+        assert!(is_binary_of(cached, "vecs1"));
+        assert!(!is_binary_of(cached, "vecs"));
+        assert!(!is_binary_of(
+            Path::new(".rustlings-cache/vecs1-2-0123456789abcdef"),
+            "vecs1"
+        ));
+        assert!(!is_binary_of(
+            Path::new(".rustlings-cache/vecs1.lock"),
+            "vecs1"
+        ));
+    }
+
+    #[test]
+    fn test_clippy_lint_levels() {
+        let lint = ClippyLint::try_from(String::from("deny:clippy::needless_range_loop"));
+        assert_eq!(
+            lint,
+            Ok(ClippyLint {
+                flag: String::from("-D"),
+                lint: String::from("clippy::needless_range_loop"),
+            })
+        );
+        assert!(ClippyLint::try_from(String::from("clippy::needless_range_loop")).is_err());
+        assert!(ClippyLint::try_from(String::from("nope:clippy::float_cmp")).is_err());
+    }
+
     #[test]
     fn test_pending_state() {
-        let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = exercise(
+            "pending_exercise",
+            "tests/fixture/state/pending_exercise.rs",
+            Mode::Compile,
+        );
 
         let state = exercise.state();
         let expected = vec![
@@ -325,25 +1894,147 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = exercise(
+            "finished_exercise",
+            "tests/fixture/state/finished_exercise.rs",
+            Mode::Compile,
+        );
 
         assert_eq!(exercise.state(), State::Done);
     }
 
     #[test]
     fn test_exercise_with_output() {
-        let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
-        };
+        let exercise = exercise(
+            "exercise_with_output",
+            "tests/fixture/success/testSuccess.rs",
+            Mode::Test,
+        );
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_output_mismatch() {
+        console::set_colors_enabled(false);
+        assert_eq!(output_mismatch("a\nb\n", "a  \r\nb\n\n"), None);
+        assert_eq!(
+            output_mismatch("a\nb\n", "a\nc\nd\n").unwrap(),
+            "The output isn't the expected one:\n  a\n- b\n+ c\n+ d\n(- is expected, + is printed)\n"
+        );
+    }
+
+    // Passes exercises that it's given `pass = true` for
+    struct Judge;
+
+    impl ModeRunner for Judge {
+        fn verify(&self, request: &plugin::Request, _: &Limits) -> io::Result<plugin::Response> {
+            let passed = request
+                .options
+                .and_then(|options| options.get("pass"))
+                .and_then(toml::Value::as_bool)
+                .ok_or_else(|| io::Error::other("no `pass` option"))?;
+            Ok(plugin::Response {
+                passed,
+                stdout: format!("{} with {:?}", request.exercise.name, request.args),
+                stderr: String::new(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_verify_with_plugin() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+[[exercises]]
+name = "judged"
+path = "exercises/judged.rs"
+mode = "plugin"
+plugin = "judge"
+plugin_options = { pass = true }
+hint = ""
+
+[[exercises]]
+name = "rejected"
+path = "exercises/rejected.rs"
+mode = "plugin"
+plugin = "judge"
+plugin_options = { pass = false }
+hint = ""
+
+[[exercises]]
+name = "confused"
+path = "exercises/confused.rs"
+mode = "plugin"
+plugin = "judge"
+hint = ""
+"#,
+        )
+        .unwrap();
+        let args = ["x".to_string()];
+        let passed = list.exercises[0].verify_with(&Judge, &args).unwrap();
+        assert_eq!(passed.stdout, r#"judged with ["x"]"#);
+        assert!(list.exercises[1].verify_with(&Judge, &[]).is_err());
+        let failed = list.exercises[2].verify_with(&Judge, &[]).unwrap_err();
+        assert_eq!(
+            failed.stderr,
+            "The plugin `judge` failed to verify confused: no `pass` option"
+        );
+    }
+
+    #[test]
+    fn test_check_target() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+[[exercises]]
+name = "wasi"
+path = "exercises/wasm/wasi.rs"
+mode = "test"
+target = "wasm32-wasip1"
+hint = ""
+
+[[exercises]]
+name = "bare"
+path = "exercises/wasm/bare.rs"
+mode = "compile"
+target = "wasm32-unknown-unknown"
+hint = ""
+
+[[exercises]]
+name = "bareTest"
+path = "exercises/wasm/bare_test.rs"
+mode = "test"
+target = "wasm32-unknown-unknown"
+hint = ""
+
+[[exercises]]
+name = "clippyWasm"
+path = "exercises/wasm/clippy.rs"
+mode = "clippy"
+target = "wasm32-wasip1"
+hint = ""
+
+[[exercises]]
+name = "native"
+path = "exercises/wasm/native.rs"
+mode = "compile"
+target = "x86_64-unknown-linux-gnu"
+hint = ""
+"#,
+        )
+        .unwrap();
+        let check =
+            |exercise: &Exercise| check_target(exercise, exercise.target.as_deref().unwrap());
+        assert_eq!(check(&list.exercises[0]), Ok(()));
+        assert_eq!(check(&list.exercises[1]), Ok(()));
+        assert!(check(&list.exercises[2])
+            .unwrap_err()
+            .contains("use a WASI target like `wasm32-wasip1`"));
+        assert!(check(&list.exercises[3])
+            .unwrap_err()
+            .contains("only compile and test exercises can have one"));
+        assert!(check(&list.exercises[4])
+            .unwrap_err()
+            .starts_with("unknown target `x86_64-unknown-linux-gnu`"));
+    }
 }
@@ -0,0 +1,142 @@
+use crate::exercise::Exercise;
+use crate::report::{Report, Status};
+use crate::topics::by_topic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// How many points exercises are worth, overriding the `points` in info.toml.
+// This is read from a grading.toml file, so that instructors can weigh
+// exercises without touching info.toml.
+#[derive(Deserialize, Default, Debug)]
+pub struct Rubric {
+    // Points per exercise name
+    #[serde(default)]
+    exercises: BTreeMap<String, u32>,
+    // Points for every exercise of a topic
+    #[serde(default)]
+    topics: BTreeMap<String, u32>,
+}
+
+impl Rubric {
+    pub fn load(path: &Path) -> Result<Rubric, Box<dyn Error>> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    // The points the exercise is worth, one unless configured otherwise
+    fn points(&self, exercise: &Exercise) -> u32 {
+        self.exercises
+            .get(&exercise.name)
+            .or_else(|| self.topics.get(&exercise.topic()))
+            .copied()
+            .or(exercise.points)
+            .unwrap_or(1)
+    }
+}
+
+// The grade of a single exercise
+#[derive(Serialize, Debug)]
+pub struct ExerciseGrade<'a> {
+    name: &'a str,
+    topic: String,
+    status: Status,
+    score: u32,
+    max_score: u32,
+}
+
+// The grades of all exercises of a topic added up
+#[derive(Serialize, Debug)]
+pub struct TopicGrade {
+    name: String,
+    score: u32,
+    max_score: u32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Grade<'a> {
+    score: u32,
+    max_score: u32,
+    topics: Vec<TopicGrade>,
+    exercises: Vec<ExerciseGrade<'a>>,
+}
+
+// Verify all exercises and grade them. An exercise scores its points once
+// it is done, and none otherwise.
+pub fn grade<'a>(exercises: &'a [Exercise], rubric: &Rubric) -> Grade<'a> {
+    let mut grade = Grade {
+        score: 0,
+        max_score: 0,
+        topics: Vec::new(),
+        exercises: Vec::new(),
+    };
+    for exercise in exercises {
+        let report = Report::verify(exercise);
+        let max_score = rubric.points(exercise);
+        let score = if report.status == Status::Done {
+            max_score
+        } else {
+            0
+        };
+
+        grade.score += score;
+        grade.max_score += max_score;
+        grade.exercises.push(ExerciseGrade {
+            name: &exercise.name,
+            topic: exercise.topic(),
+            status: report.status,
+            score,
+            max_score,
+        });
+    }
+    for (name, in_topic) in by_topic(exercises) {
+        let graded = grade
+            .exercises
+            .iter()
+            .filter(|g| in_topic.iter().any(|e| e.name == g.name));
+        let (score, max_score) = graded.fold((0, 0), |(score, max_score), g| {
+            (score + g.score, max_score + g.max_score)
+        });
+        grade.topics.push(TopicGrade {
+            name,
+            score,
+            max_score,
+        });
+    }
+    grade
+}
+
+impl Grade<'_> {
+    pub fn print_text(&self) {
+        println!("{:<17}\t{:>9}", "Topic", "Score");
+        for topic in &self.topics {
+            println!(
+                "{:<17}\t{:>9}",
+                topic.name,
+                format!("{}/{}", topic.score, topic.max_score)
+            );
+        }
+        println!();
+        println!("Total score: {}/{}", self.score, self.max_score);
+    }
+
+    // One row per exercise, followed by one per topic and the total,
+    // which are told apart by the first column
+    pub fn print_csv(&self) {
+        println!("kind,name,topic,status,score,max_score");
+        for exercise in &self.exercises {
+            println!(
+                "exercise,{},{},{},{},{}",
+                exercise.name, exercise.topic, exercise.status, exercise.score, exercise.max_score
+            );
+        }
+        for topic in &self.topics {
+            println!(
+                "topic,{},{},,{},{}",
+                topic.name, topic.name, topic.score, topic.max_score
+            );
+        }
+        println!("total,,,,{},{}", self.score, self.max_score);
+    }
+}
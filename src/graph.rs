@@ -0,0 +1,134 @@
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use std::error::Error;
+
+// Order the exercises so that every exercise comes after the exercises it
+// `requires`. Otherwise, the order of info.toml is kept, so exercises only
+// move down as far as their prerequisites make them.
+pub fn sort(exercises: Vec<Exercise>) -> Result<Vec<Exercise>, Box<dyn Error>> {
+    for exercise in &exercises {
+        if let Some(unknown) = exercise
+            .requires
+            .iter()
+            .find(|name| !exercises.iter().any(|e| e.name == **name))
+        {
+            return Err(format!("{} requires unknown exercise {unknown}", exercise.name).into());
+        }
+    }
+
+    let mut remaining = exercises;
+    let mut sorted: Vec<Exercise> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let is_placed = |name: &String| sorted.iter().any(|e| e.name == *name);
+        let Some(next) = remaining
+            .iter()
+            .position(|e| e.requires.iter().all(is_placed))
+        else {
+            let cycle: Vec<&str> = remaining.iter().map(|e| e.name.as_str()).collect();
+            return Err(format!(
+                "the prerequisites of these exercises form a cycle: {}",
+                cycle.join(", ")
+            )
+            .into());
+        };
+        sorted.push(remaining.remove(next));
+    }
+    Ok(sorted)
+}
+
+// The prerequisites of the exercise that haven't been completed yet
+pub fn missing_prerequisites<'a>(exercise: &'a Exercise, progress: &Progress) -> Vec<&'a str> {
+    exercise
+        .requires
+        .iter()
+        .filter(|name| !progress.completed.contains(*name))
+        .map(String::as_str)
+        .collect()
+}
+
+// The prerequisites of the exercises as a graph in the DOT format of Graphviz,
+// with an edge from each prerequisite to the exercises requiring it
+pub fn to_dot(exercises: &[Exercise], progress: &Progress) -> String {
+    let mut dot = String::from("digraph rustlings {\n    node [shape=box];\n");
+    for exercise in exercises {
+        let style = if progress.is_completed(exercise) {
+            " [style=filled, fillcolor=palegreen]"
+        } else {
+            ""
+        };
+        dot.push_str(&format!("    \"{}\"{style};\n", exercise.name));
+    }
+    for exercise in exercises {
+        for prerequisite in &exercise.requires {
+            dot.push_str(&format!(
+                "    \"{prerequisite}\" -> \"{}\";\n",
+                exercise.name
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercises(requires: &[(&str, &[&str])]) -> Vec<Exercise> {
+        let info: String = requires
+            .iter()
+            .map(|(name, requires)| {
+                format!(
+                    "[[exercises]]\nname = \"{name}\"\npath = \"{name}.rs\"\nmode = \"compile\"\nhint = \"\"\nrequires = {requires:?}\n"
+                )
+            })
+            .collect();
+        toml::from_str::<crate::exercise::ExerciseList>(&info)
+            .unwrap()
+            .exercises
+    }
+
+    fn names(exercises: &[Exercise]) -> Vec<&str> {
+        exercises.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_keeps_order_of_info_toml() {
+        let sorted = sort(exercises(&[
+            ("functions1", &["variables1"]),
+            ("intro1", &[]),
+            ("variables1", &["intro1"]),
+            ("quiz1", &["functions1", "variables1"]),
+        ]))
+        .unwrap();
+        assert_eq!(
+            names(&sorted),
+            ["intro1", "variables1", "functions1", "quiz1"]
+        );
+    }
+
+    #[test]
+    fn test_sort_rejects_cycles_and_unknown_exercises() {
+        let cycle = sort(exercises(&[("a", &["b"]), ("b", &["a"]), ("c", &[])]));
+        assert!(cycle.unwrap_err().to_string().ends_with("cycle: a, b"));
+        let unknown = sort(exercises(&[("a", &["missing"])]));
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let exercises = exercises(&[("intro1", &[]), ("variables1", &["intro1"])]);
+        let progress = Progress {
+            completed: ["intro1".to_string()].into(),
+            ..Progress::default()
+        };
+        assert_eq!(
+            to_dot(&exercises, &progress),
+            "digraph rustlings {\n    node [shape=box];\n    \"intro1\" [style=filled, fillcolor=palegreen];\n    \"variables1\";\n    \"intro1\" -> \"variables1\";\n}\n"
+        );
+        assert_eq!(
+            missing_prerequisites(&exercises[1], &Progress::default()),
+            ["intro1"]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::exam;
+use crate::i18n::{self, DEFAULT_LANGUAGE};
+use crate::progress::Progress;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+// The hints of an exercise.
+// In info.toml, this is either a single hint or an array of hints that
+// are revealed one level at a time. Either can be translated by giving
+// them per language instead, like `hint.en = "..."` and `hint.es = "..."`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Hint {
+    Single(String),
+    Levels(Vec<String>),
+    Localized(BTreeMap<String, Hint>),
+}
+
+impl Hint {
+    // The levels of the hint in the language of the user, or in English
+    // if it wasn't translated to that language
+    pub fn levels(&self) -> &[String] {
+        match self {
+            Hint::Single(hint) => std::slice::from_ref(hint),
+            Hint::Levels(levels) => levels,
+            Hint::Localized(hints) => hints
+                .get(i18n::language())
+                .or_else(|| hints.get(DEFAULT_LANGUAGE))
+                .or_else(|| hints.values().next())
+                .map_or(&[], Hint::levels),
+        }
+    }
+}
+
+impl From<&str> for Hint {
+    fn from(hint: &str) -> Hint {
+        Hint::Single(hint.to_string())
+    }
+}
+
+impl From<String> for Hint {
+    fn from(hint: String) -> Hint {
+        Hint::Single(hint)
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.levels().join("\n\n"))
+    }
+}
+
+// Print the hints of the exercise up to the first level that hasn't been
+// seen yet, and remember that it has been seen now
+pub fn reveal(name: &str, hint: &Hint) {
+    if exam::in_progress() {
+        println!("{}", t!("exam-no-hints"));
+        return;
+    }
+    let level = Progress::load().hint_level(name) + 1;
+    reveal_up_to(name, hint, level);
+}
+
+// Print all hints of the exercise at once
+pub fn reveal_all(name: &str, hint: &Hint) {
+    if exam::in_progress() {
+        println!("{}", t!("exam-no-hints"));
+        return;
+    }
+    reveal_up_to(name, hint, hint.levels().len());
+}
+
+// Print only the first level of the hints that hasn't been seen yet,
+// and remember that it has been seen now
+pub fn reveal_next(name: &str, hint: &Hint) {
+    if exam::in_progress() {
+        println!("{}", t!("exam-no-hints"));
+        return;
+    }
+    let levels = hint.levels();
+    let mut progress = Progress::load();
+    let level = progress.hint_level(name) + 1;
+    match levels {
+        [hint] => println!("{hint}"),
+        _ if level > levels.len() => {
+            println!(
+                "You've seen all {} hints for {name} already. Run `rustlings hint --all {name}` to see them again.",
+                levels.len()
+            );
+            return;
+        }
+        _ => {
+            println!("Hint {level}/{} for {name}:", levels.len());
+            println!("{}", levels[level - 1]);
+            if level < levels.len() {
+                println!();
+                println!("Ask for the next hint again to reveal the one after it.");
+            }
+        }
+    }
+
+    if progress.see_hint_level(name, level.min(levels.len())) && progress.save().is_err() {
+        warn!("Failed to save which hints you've seen for {}", name);
+    }
+}
+
+fn reveal_up_to(name: &str, hint: &Hint, level: usize) {
+    let levels = hint.levels();
+    if let [hint] = levels {
+        println!("{hint}");
+    }
+
+    let mut progress = Progress::load();
+    let shown = level.min(levels.len());
+    if levels.len() > 1 {
+        for (i, level) in levels[..shown].iter().enumerate() {
+            println!("Hint {}/{}:", i + 1, levels.len());
+            println!("{level}");
+            println!();
+        }
+        if shown < levels.len() {
+            println!("Ask for a hint again to reveal the next one.");
+        }
+    }
+
+    if progress.see_hint_level(name, shown) && progress.save().is_err() {
+        warn!("Failed to save which hints you've seen for {}", name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_localized_hint_falls_back_to_english() {
+        let hint: Hint = toml::from_str::<BTreeMap<String, Hint>>(
+            "hint.en = [\"A nudge\", \"A bigger nudge\"]\nhint.ja = \"ヒント\"",
+        )
+        .unwrap()
+        .remove("hint")
+        .unwrap();
+        assert_eq!(hint.levels(), ["A nudge", "A bigger nudge"]);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::config::CONFIG;
+use crate::exercise::Exercise;
+use crate::sandbox;
+use std::process::Command;
+
+// Something that happened, which runs the hook configured for it
+pub enum Event<'a> {
+    ExercisePassed(&'a Exercise),
+    ExerciseFailed(&'a Exercise),
+    AllDone,
+}
+
+// Run the shell command configured for the event, if any
+pub fn run(event: Event) {
+    // The rustlings.toml of untrusted exercises mustn't run commands
+    let hooks = if sandbox::enabled() {
+        &CONFIG.user_hooks
+    } else {
+        &CONFIG.hooks
+    };
+    let (name, hook, exercise) = match event {
+        Event::ExercisePassed(exercise) => {
+            ("exercise_passed", &hooks.exercise_passed, Some(exercise))
+        }
+        Event::ExerciseFailed(exercise) => {
+            ("exercise_failed", &hooks.exercise_failed, Some(exercise))
+        }
+        Event::AllDone => ("all_done", &hooks.all_done, None),
+    };
+    let Some(hook) = hook else {
+        return;
+    };
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(hook);
+    if let Some(exercise) = exercise {
+        command
+            .env("RUSTLINGS_EXERCISE", &exercise.name)
+            .env("RUSTLINGS_EXERCISE_PATH", &exercise.path);
+    }
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("The {} hook failed", format!("{name} ({status})")),
+        Err(e) => warn!("The {} hook couldn't be run", format!("{name} ({e})")),
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, OnceLock};
+
+// The language messages fall back to, which has all of them
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// The message catalogs by language, built into the binary
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("es", include_str!("../locales/es.toml")),
+];
+
+static MESSAGES: LazyLock<HashMap<&str, HashMap<String, String>>> = LazyLock::new(|| {
+    CATALOGS
+        .iter()
+        .map(|(language, catalog)| {
+            let messages = toml::from_str(catalog)
+                .unwrap_or_else(|e| panic!("The {language} message catalog is invalid: {e}"));
+            (*language, messages)
+        })
+        .collect()
+});
+
+static LANGUAGE: OnceLock<String> = OnceLock::new();
+
+pub fn parse_language(value: &str) -> Result<String, String> {
+    if CATALOGS.iter().any(|(language, _)| *language == value) {
+        return Ok(value.to_string());
+    }
+    let languages: Vec<&str> = CATALOGS.iter().map(|(language, _)| *language).collect();
+    Err(format!(
+        "unknown language `{value}`, expected one of {}",
+        languages.join(", ")
+    ))
+}
+
+// Show messages and hints in the given language from now on.
+// This only has an effect before the first message is shown.
+pub fn set_language(language: String) {
+    let _ignored = LANGUAGE.set(language);
+}
+
+// The language messages and hints are shown in
+pub fn language() -> &'static str {
+    LANGUAGE.get_or_init(|| DEFAULT_LANGUAGE.to_string())
+}
+
+// The message with the given ID in the current language, with its
+// `{name}` placeholders replaced by the arguments of the same name
+pub fn message(id: &str, args: &[(&str, String)]) -> String {
+    let template = [language(), DEFAULT_LANGUAGE]
+        .iter()
+        .find_map(|language| MESSAGES.get(language)?.get(id))
+        .unwrap_or_else(|| panic!("There is no message with the ID {id}"));
+    args.iter()
+        .fold(template.clone(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_catalogs_match_the_default_language() {
+        let placeholder = Regex::new(r"\{[a-z_]+\}").unwrap();
+        let placeholders = |message: &str| -> BTreeSet<String> {
+            placeholder
+                .find_iter(message)
+                .map(|m| m.as_str().to_string())
+                .collect()
+        };
+        let default = &MESSAGES[DEFAULT_LANGUAGE];
+        for (language, messages) in MESSAGES.iter() {
+            for (id, message) in messages {
+                let Some(default_message) = default.get(id) else {
+                    panic!("{id} is translated to {language}, but missing in {DEFAULT_LANGUAGE}");
+                };
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(default_message),
+                    "{id} has other placeholders in {language}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            message("no-exercise", &[("name", "intro1".to_string())]),
+            "No exercise found for 'intro1'!"
+        );
+        assert!(parse_language("es").is_ok());
+        assert!(parse_language("xx").is_err());
+    }
+}
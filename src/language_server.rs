@@ -0,0 +1,329 @@
+// A tiny language server for `rustlings language-server`, next to the
+// rust-analyzer setup of `rustlings lsp`. It only knows about rustlings:
+// it points out the `I AM NOT DONE` marker of exercises, and offers code
+// actions to remove it and to show the next hint of the exercise.
+// Messages are JSON-RPC over stdin and stdout, framed by `Content-Length`.
+
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use crate::state::MARKER;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+// The command behind the "Show rustlings hint" code action
+const HINT_COMMAND: &str = "rustlings.hint";
+
+// Error code of the JSON-RPC spec for requests the server doesn't handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// Serve editors on stdin and stdout until they ask the server to exit
+pub fn run(exercises: &[Exercise]) -> io::Result<()> {
+    let mut server = Server {
+        exercises,
+        documents: HashMap::new(),
+        shut_down: false,
+    };
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        if method == "exit" {
+            std::process::exit(if server.shut_down { 0 } else { 1 });
+        }
+        for reply in server.handle(method, &message["params"], message.get("id")) {
+            write_message(&mut io::stdout().lock(), &reply)?;
+        }
+    }
+    Ok(())
+}
+
+struct Server<'a> {
+    exercises: &'a [Exercise],
+    // The text of the documents open in the editor, by their URI
+    documents: HashMap<String, String>,
+    // Whether the editor asked the server to shut down
+    shut_down: bool,
+}
+
+impl Server<'_> {
+    // Handle a request or notification, returning the messages to send back
+    fn handle(&mut self, method: &str, params: &Value, id: Option<&Value>) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    // The whole text is sent on every change
+                    "textDocumentSync": 1,
+                    "codeActionProvider": true,
+                    "executeCommandProvider": { "commands": [HINT_COMMAND] },
+                },
+                "serverInfo": { "name": "rustlings" },
+            }),
+            "shutdown" => {
+                self.shut_down = true;
+                Value::Null
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                return vec![self.diagnostics(&uri)];
+            }
+            "textDocument/didChange" => {
+                // With full sync, the last change holds the whole text
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return vec![self.diagnostics(&uri)];
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![self.diagnostics(&uri)];
+            }
+            "textDocument/codeAction" => self.code_actions(&uri, &params["range"]),
+            "workspace/executeCommand" if params["command"] == HINT_COMMAND => {
+                let name = params["arguments"][0].as_str().unwrap_or_default();
+                let reply = id.map(|id| json!({ "jsonrpc": "2.0", "id": id, "result": null }));
+                return self.show_hint(name).into_iter().chain(reply).collect();
+            }
+            _ => {
+                // Notifications that aren't handled are ignored
+                let Some(id) = id else {
+                    return Vec::new();
+                };
+                return vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method {method}") },
+                })];
+            }
+        };
+        match id {
+            Some(id) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => Vec::new(),
+        }
+    }
+
+    // The exercise the document is a source file of, if any
+    fn exercise(&self, uri: &str) -> Option<&Exercise> {
+        let path = uri_to_path(uri)?;
+        self.exercises.iter().find(|e| e.contains(&path))
+    }
+
+    // Point out the marker of an open exercise, or clear the diagnostics
+    // of documents that were closed or aren't exercises
+    fn diagnostics(&self, uri: &str) -> Value {
+        let text = self.documents.get(uri).map(String::as_str);
+        let diagnostics: Vec<Value> = match (self.exercise(uri), text) {
+            (Some(exercise), Some(text)) => markers(text)
+                .map(|range| {
+                    json!({
+                        "range": range,
+                        "severity": 3,
+                        "source": "rustlings",
+                        "message": format!(
+                            "Remove this comment to move on once `{}` is solved",
+                            exercise.name
+                        ),
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    // Offer to remove the markers in the given range, and to show a hint
+    // anywhere in an exercise
+    fn code_actions(&self, uri: &str, range: &Value) -> Value {
+        let Some(exercise) = self.exercise(uri) else {
+            return json!([]);
+        };
+        let first = range["start"]["line"].as_u64().unwrap_or(0);
+        let last = range["end"]["line"].as_u64().unwrap_or(first);
+        let text = self.documents.get(uri).map_or("", String::as_str);
+        let mut actions: Vec<Value> = markers(text)
+            .filter(|marker| (first..=last).contains(&marker["start"]["line"].as_u64().unwrap()))
+            .map(|marker| {
+                let line = marker["start"]["line"].as_u64().unwrap();
+                // Remove the whole line, not just the comment
+                let edit = json!({
+                    "range": {
+                        "start": { "line": line, "character": 0 },
+                        "end": { "line": line + 1, "character": 0 },
+                    },
+                    "newText": "",
+                });
+                json!({
+                    "title": "Remove `I AM NOT DONE`",
+                    "kind": "quickfix",
+                    "edit": { "changes": { uri: [edit] } },
+                })
+            })
+            .collect();
+        actions.push(json!({
+            "title": "Show rustlings hint",
+            "command": {
+                "title": "Show rustlings hint",
+                "command": HINT_COMMAND,
+                "arguments": [exercise.name],
+            },
+        }));
+        Value::Array(actions)
+    }
+
+    // Reveal the next hint of the exercise, like `rustlings hint` does,
+    // and show all hints revealed so far in the editor
+    fn show_hint(&self, name: &str) -> Option<Value> {
+        let exercise = self.exercises.iter().find(|e| e.name == name)?;
+        let levels = exercise.hint.levels();
+        let mut progress = Progress::load();
+        let shown = (progress.hint_level(name) + 1).min(levels.len());
+        if progress.see_hint_level(name, shown) {
+            let _ignored = progress.save();
+        }
+        let message = match levels {
+            [hint] => hint.clone(),
+            _ => levels[..shown]
+                .iter()
+                .enumerate()
+                .map(|(i, hint)| format!("Hint {}/{}: {hint}", i + 1, levels.len()))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+        Some(json!({
+            "jsonrpc": "2.0",
+            "method": "window/showMessage",
+            "params": { "type": 3, "message": message },
+        }))
+    }
+}
+
+// The ranges of the `I AM NOT DONE` comments in the text, from where the
+// comment starts to the end of its line. Columns are counted in UTF-16
+// code units, as the protocol wants.
+fn markers(text: &str) -> impl Iterator<Item = Value> + '_ {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| MARKER.is_match(line))
+        .map(|(i, line)| {
+            let indent = line.len() - line.trim_start().len();
+            json!({
+                "start": { "line": i, "character": line[..indent].encode_utf16().count() },
+                "end": { "line": i, "character": line.encode_utf16().count() },
+            })
+        })
+}
+
+// The path of a `file://` URI, with percent-encoded bytes decoded
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let [byte, tail @ ..] = rest {
+        match (byte, tail) {
+            (b'%', [high, low, tail @ ..]) => {
+                let hex = std::str::from_utf8(&[*high, *low]).ok()?.to_string();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+                rest = tail;
+            }
+            _ => {
+                bytes.push(*byte);
+                rest = tail;
+            }
+        }
+    }
+    // Windows paths come as `file:///C:/...`
+    let path = String::from_utf8(bytes).ok()?;
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => &path[1..],
+        _ => &path,
+    };
+    Some(PathBuf::from(path))
+}
+
+// Read the next message, or None once the editor closed stdin
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let Some(length) = length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message without a Content-Length header",
+        ));
+    };
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(io::Error::from)
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers() {
+        let text = "fn main() {}\n    // I AM NOT DONE\n// IAMNOTDONE\n";
+        let ranges: Vec<Value> = markers(text).collect();
+        assert_eq!(
+            ranges,
+            vec![json!({
+                "start": { "line": 1, "character": 4 },
+                "end": { "line": 1, "character": 20 },
+            })]
+        );
+    }
+
+    #[test]
+    fn test_uri_to_path() {
+        assert_eq!(
+            uri_to_path("file:///home/me/my%20rustlings/exercises/intro1.rs"),
+            Some(PathBuf::from("/home/me/my rustlings/exercises/intro1.rs"))
+        );
+        assert_eq!(
+            uri_to_path("file:///C:/rustlings/intro1.rs"),
+            Some(PathBuf::from("C:/rustlings/intro1.rs"))
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+    }
+
+    #[test]
+    fn test_read_message() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#;
+        let input = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+        let message = read_message(&mut input.as_bytes()).unwrap().unwrap();
+        assert_eq!(message["method"], "shutdown");
+        assert!(read_message(&mut "".as_bytes()).unwrap().is_none());
+    }
+}
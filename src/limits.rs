@@ -0,0 +1,309 @@
+// Keeping exercises that loop forever from hanging `watch` and `verify`, and
+// from eating up memory: compiling and running them is given up on after a
+// while, killing the process along with any processes it started, and so is
+// running one that prints more than is kept of its output. Its memory can be
+// limited as well. The time limits can be set for all exercises in the
+// configuration, and for one in info.toml.
+use crate::config::CONFIG;
+use crate::signals;
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Enough for clippy exercises, which are compiled from scratch
+pub const DEFAULT_COMPILE_TIMEOUT: u64 = 120;
+pub const DEFAULT_RUN_TIMEOUT: u64 = 10;
+// How much of the output of an exercise is kept, in kibibytes. Far more than
+// exercises print, and little enough to show.
+pub const DEFAULT_OUTPUT_LIMIT: u64 = 1024;
+// How long to wait for the output once the processes were killed, in case
+// one of them got away
+const KILL_GRACE: Duration = Duration::from_secs(1);
+
+// What a command may use
+pub struct Limits {
+    pub time: Duration,
+    // The bytes kept of stdout and of stderr each, all of them if `None`
+    pub output: Option<usize>,
+    // The bytes of memory, as in address space, not limited if `None`
+    pub memory: Option<u64>,
+}
+
+impl Limits {
+    // The limits of compiling an exercise, in seconds if it sets them itself
+    pub fn compiling(timeout: Option<u64>) -> Limits {
+        Limits {
+            time: Duration::from_secs(
+                timeout
+                    .or(CONFIG.timeouts.compile)
+                    .unwrap_or(DEFAULT_COMPILE_TIMEOUT),
+            ),
+            output: None,
+            memory: None,
+        }
+    }
+
+    // The limits of running an exercise, in seconds if it sets them itself
+    pub fn running(timeout: Option<u64>) -> Limits {
+        Limits {
+            time: Duration::from_secs(
+                timeout
+                    .or(CONFIG.timeouts.run)
+                    .unwrap_or(DEFAULT_RUN_TIMEOUT),
+            ),
+            output: Some(CONFIG.limits.output.unwrap_or(DEFAULT_OUTPUT_LIMIT) as usize * 1024),
+            memory: CONFIG.limits.memory.map(|mib| mib * 1024 * 1024),
+        }
+    }
+}
+
+// The program and arguments to run the command line with, so that it keeps
+// to the configured memory limit. On Linux, the shell sets the limit for the
+// program with `ulimit` before turning into it.
+pub fn argv(command_line: Vec<OsString>) -> Vec<OsString> {
+    match CONFIG.limits.memory {
+        Some(mib) if cfg!(target_os = "linux") => {
+            let mut argv: Vec<OsString> = vec![
+                "sh".into(),
+                "-c".into(),
+                "ulimit -v \"$1\" && shift && exec \"$@\"".into(),
+                "sh".into(),
+                (mib * 1024).to_string().into(),
+            ];
+            argv.extend(command_line);
+            argv
+        }
+        _ => command_line,
+    }
+}
+
+// What happens while a command runs
+enum Event {
+    Exited(io::Result<ExitStatus>),
+    // It printed more than is kept
+    Overflowed,
+}
+
+// Run the command like `Command::output`, but kill it and everything it
+// started once it takes longer than its time limit, failing with
+// `io::ErrorKind::TimedOut`, or once it prints more than its output limit
+pub fn output(command: &mut Command, limits: &Limits) -> io::Result<Output> {
+    // Like `Command::output`, which doesn't connect stdin either
+    output_with_input(command, limits, None)
+}
+
+// Like `output`, writing the input to the stdin of the command, if there is any
+pub fn output_with_input(
+    command: &mut Command,
+    limits: &Limits,
+    input: Option<&[u8]>,
+) -> io::Result<Output> {
+    let stdin = match input {
+        Some(_) => Stdio::piped(),
+        None => Stdio::null(),
+    };
+    let mut child = command
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    let _tracked = signals::track(pid);
+    // Written on a thread of its own, so that a command that prints a lot
+    // before reading all of its input can't get stuck
+    if let (Some(mut pipe), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_vec();
+        thread::spawn(move || pipe.write_all(&input));
+    }
+    let (sender, receiver) = mpsc::channel();
+    let stdout = capture(child.stdout.take(), limits.output, sender.clone());
+    let stderr = capture(child.stderr.take(), limits.output, sender.clone());
+    thread::spawn(move || wait(child, sender));
+
+    let deadline = Instant::now() + limits.time;
+    let status = loop {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Event::Exited(status)) => break status?,
+            Ok(Event::Overflowed) => kill_tree(pid),
+            Err(_) => {
+                kill_tree(pid);
+                receiver.recv_timeout(KILL_GRACE).ok();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("timed out after {} s", limits.time.as_secs()),
+                ));
+            }
+        }
+    };
+
+    let (mut stdout, stdout_len) = stdout.join().unwrap_or_default();
+    let (mut stderr, stderr_len) = stderr.join().unwrap_or_default();
+    for (output, len) in [(&mut stdout, stdout_len), (&mut stderr, stderr_len)] {
+        if len > output.len() {
+            let kept = output.len();
+            output.extend(truncation_note(kept, len).into_bytes());
+        }
+    }
+    if let Some(memory) = limits.memory {
+        // What Rust's allocator aborts with
+        if !status.success() && String::from_utf8_lossy(&stderr).contains("memory allocation of") {
+            stderr.extend(
+                format!(
+                    "\nIt ran out of the {} MiB of memory it may use. The limit can be raised \
+                     with `memory` in the `[limits]` section of rustlings.toml.\n",
+                    memory / 1024 / 1024
+                )
+                .into_bytes(),
+            );
+        }
+    }
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+fn wait(mut child: Child, sender: Sender<Event>) {
+    sender.send(Event::Exited(child.wait())).ok();
+}
+
+// Read the pipe until it's closed, keeping at most `limit` bytes, and telling
+// once there's more. Returns what was kept and how much there was in total.
+fn capture<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    limit: Option<usize>,
+    sender: Sender<Event>,
+) -> JoinHandle<(Vec<u8>, usize)> {
+    thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return (Vec::new(), 0);
+        };
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut kept = Vec::new();
+        let mut len = 0;
+        let mut buffer = [0; 8192];
+        loop {
+            let n = match pipe.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let room = limit.saturating_sub(kept.len()).min(n);
+            kept.extend_from_slice(&buffer[..room]);
+            if len <= limit && len + n > limit {
+                sender.send(Event::Overflowed).ok();
+            }
+            len += n;
+        }
+        (kept, len)
+    })
+}
+
+fn truncation_note(kept: usize, len: usize) -> String {
+    format!(
+        "\n... The output was cut off after {kept} of the {len} bytes that were printed, and \
+         the program was stopped. The limit can be raised with `output` in the `[limits]` \
+         section of rustlings.toml.\n"
+    )
+}
+
+// Kill the process and all of its descendants, like the test threads of a
+// test binary or the rustc processes of cargo
+#[cfg(unix)]
+pub fn kill_tree(pid: u32) {
+    let mut pids = vec![pid];
+    if let Ok(ps) = Command::new("ps").args(["-A", "-o", "pid=,ppid="]).output() {
+        let table = parse_ps(&String::from_utf8_lossy(&ps.stdout));
+        let mut i = 0;
+        while i < pids.len() {
+            let parent = pids[i];
+            pids.extend(table.iter().filter(|(_, p)| *p == parent).map(|(c, _)| *c));
+            i += 1;
+        }
+    }
+    Command::new("kill")
+        .arg("-KILL")
+        .args(pids.iter().map(u32::to_string))
+        .output()
+        .ok();
+}
+
+#[cfg(windows)]
+pub fn kill_tree(pid: u32) {
+    Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output()
+        .ok();
+}
+
+// The pairs of process and parent IDs listed by `ps -o pid=,ppid=`
+#[cfg(unix)]
+fn parse_ps(ps: &str) -> Vec<(u32, u32)> {
+    ps.lines()
+        .filter_map(|line| {
+            let mut ids = line.split_whitespace().map(str::parse);
+            match (ids.next(), ids.next()) {
+                (Some(Ok(pid)), Some(Ok(ppid))) => Some((pid, ppid)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limits(time: Duration, output: Option<usize>) -> Limits {
+        Limits {
+            time,
+            output,
+            memory: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_ps() {
+        assert_eq!(
+            parse_ps("    1     0\n  812     1\nheader\n"),
+            vec![(1, 0), (812, 1)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_times_out() {
+        let start = Instant::now();
+        let result = output(
+            Command::new("sh").args(["-c", "sleep 30; echo late"]),
+            &limits(Duration::from_millis(200), None),
+        );
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let result = output(
+            &mut Command::new("true"),
+            &limits(Duration::from_secs(5), None),
+        );
+        assert!(result.unwrap().status.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_is_cut_off() {
+        let result = output(
+            Command::new("sh").args(["-c", "while true; do echo spam; done"]),
+            &limits(Duration::from_secs(30), Some(100)),
+        )
+        .unwrap();
+        assert!(!result.status.success());
+        let stdout = String::from_utf8_lossy(&result.stdout);
+        assert!(stdout.starts_with("spam\nspam\n"));
+        assert!(stdout.contains("The output was cut off after 100 of the "));
+        assert!(result.stderr.is_empty());
+    }
+}
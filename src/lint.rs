@@ -0,0 +1,177 @@
+// `rustlings lint` runs clippy on exercises whatever their mode, and sums up
+// the warnings of each one. Learners get into the habit of heeding lints, and
+// authors keep the exercises and their solutions free of warnings.
+use crate::diagnostics;
+use crate::exercise::Exercise;
+use crate::output;
+use crate::theme;
+
+// What clippy had to say about an exercise
+#[derive(PartialEq, Debug)]
+enum Outcome {
+    Clean,
+    // The lints that warned, like `clippy::needless_range_loop`
+    Warnings(Vec<String>),
+    // The exercise doesn't compile, or a lint that's denied fired
+    Errors { errors: usize, warnings: usize },
+    // Clippy can't check the exercise on its own
+    Skipped,
+}
+
+// Lint the exercises one after another, printing what clippy found unless
+// only the summary is wanted. Returns whether all of them are free of
+// warnings and errors.
+pub fn lint(exercises: &[&Exercise], summary_only: bool) -> bool {
+    let mut outcomes = Vec::new();
+    for exercise in exercises {
+        let spinner = output::spinner(format!("Linting {}...", exercise.name));
+        let result = exercise.lint();
+        spinner.finish_and_clear();
+        let outcome = match result {
+            None => Outcome::Skipped,
+            Some(Ok(output)) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let outcome = outcome_of(&stderr, output.status.success());
+                if !summary_only && outcome != Outcome::Clean {
+                    print!("{}", diagnostics::render(&stderr));
+                }
+                outcome
+            }
+            // Like when it took too long
+            Some(Err(output)) => {
+                if !summary_only {
+                    println!("{}", output.stderr.trim_end());
+                }
+                Outcome::Errors {
+                    errors: 1,
+                    warnings: 0,
+                }
+            }
+        };
+        outcomes.push((exercise.name.as_str(), outcome));
+    }
+    print_summary(&outcomes);
+    outcomes
+        .iter()
+        .all(|(_, outcome)| matches!(outcome, Outcome::Clean | Outcome::Skipped))
+}
+
+fn outcome_of(stderr: &str, success: bool) -> Outcome {
+    let levels = diagnostics::levels(stderr);
+    let count = |level: &str| levels.iter().filter(|(l, _)| l == level).count();
+    let errors = count("error");
+    if !success || errors > 0 {
+        return Outcome::Errors {
+            // Cargo failed without a diagnostic, like for a missing crate
+            errors: errors.max(1),
+            warnings: count("warning"),
+        };
+    }
+    let mut lints: Vec<String> = levels
+        .into_iter()
+        .filter(|(level, _)| level == "warning")
+        .map(|(_, code)| code.unwrap_or_else(|| "warning".to_string()))
+        .collect();
+    if lints.is_empty() {
+        return Outcome::Clean;
+    }
+    lints.sort();
+    lints.dedup();
+    Outcome::Warnings(lints)
+}
+
+// A line for every exercise clippy complained about, and the totals
+fn print_summary(outcomes: &[(&str, Outcome)]) {
+    println!();
+    let mut clean = 0;
+    let mut skipped = 0;
+    for (name, outcome) in outcomes {
+        match outcome {
+            Outcome::Clean => clean += 1,
+            Outcome::Skipped => skipped += 1,
+            Outcome::Warnings(lints) => println!(
+                "{} {name}: {}",
+                theme::warning().apply_to(output::symbol("⚠️ ", "!")),
+                lints.join(", ")
+            ),
+            Outcome::Errors { errors, warnings } => {
+                let mut counts = plural(*errors, "error");
+                if *warnings > 0 {
+                    counts.push_str(&format!(", {}", plural(*warnings, "warning")));
+                }
+                println!(
+                    "{} {name}: {counts}",
+                    theme::failure().apply_to(output::symbol("❌", "x"))
+                );
+            }
+        }
+    }
+    let linted = outcomes.len() - skipped;
+    let mut totals = format!("{clean} of {} free of warnings", plural(linted, "exercise"));
+    if skipped > 0 {
+        // compile_fail and property exercises
+        totals.push_str(&format!(
+            ", and {skipped} skipped that clippy can't check on their own"
+        ));
+    }
+    if clean == linted {
+        success!("{}", totals);
+    } else {
+        println!("{totals}");
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        _ => format!("{count} {noun}s"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diagnostic(level: &str, code: &str) -> String {
+        format!(
+            r#"{{"message":"m","code":{{"code":"{code}","explanation":null}},"level":"{level}","spans":[{{"file_name":"x.rs","line_start":1,"column_start":1,"is_primary":true,"label":null,"text":[],"suggested_replacement":null}}],"children":[]}}"#
+        )
+    }
+
+    #[test]
+    fn test_outcome_of() {
+        assert_eq!(outcome_of("", true), Outcome::Clean);
+        let stderr = [
+            diagnostic("warning", "unused_variables"),
+            diagnostic("warning", "clippy::needless_range_loop"),
+            diagnostic("warning", "unused_variables"),
+        ]
+        .join("\n");
+        assert_eq!(
+            outcome_of(&stderr, true),
+            Outcome::Warnings(vec![
+                "clippy::needless_range_loop".to_string(),
+                "unused_variables".to_string()
+            ])
+        );
+        let stderr = [
+            diagnostic("error", "E0308"),
+            diagnostic("warning", "unused_variables"),
+        ]
+        .join("\n");
+        assert_eq!(
+            outcome_of(&stderr, false),
+            Outcome::Errors {
+                errors: 1,
+                warnings: 1
+            }
+        );
+        assert_eq!(
+            outcome_of("error: no matching package named `nope` found", false),
+            Outcome::Errors {
+                errors: 1,
+                warnings: 0
+            }
+        );
+    }
+}
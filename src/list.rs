@@ -0,0 +1,215 @@
+use crate::config::CONFIG;
+use crate::exercise::Exercise;
+use crate::output::symbol;
+use crate::theme;
+use console::{style, Key, Term};
+use std::env;
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+// What the user chose to do in the interactive list
+pub enum Selection<'a> {
+    // Start watch mode from the given exercise
+    Watch(&'a Exercise),
+    // Leave the list without doing anything
+    Quit,
+}
+
+// An exercise as displayed in the interactive list
+struct Entry<'a> {
+    exercise: &'a Exercise,
+    topic: String,
+    done: bool,
+}
+
+// Show an interactive list of the exercises which can be filtered by typing.
+// Enter starts watch mode from the selected exercise, and Tab opens it in
+// the editor set in $VISUAL or $EDITOR.
+pub fn interactive(exercises: &[Exercise]) -> io::Result<Selection<'_>> {
+    let term = Term::stdout();
+    let entries: Vec<Entry> = exercises
+        .iter()
+        .map(|exercise| Entry {
+            exercise,
+            topic: exercise.topic(),
+            done: exercise.looks_done(),
+        })
+        .collect();
+
+    let mut filter = String::new();
+    let mut selected = 0;
+    term.hide_cursor()?;
+    let selection = loop {
+        let matching: Vec<&Entry> = entries
+            .iter()
+            .filter(|e| fuzzy_match(&filter, &e.exercise.name) || fuzzy_match(&filter, &e.topic))
+            .collect();
+        selected = selected.min(matching.len().saturating_sub(1));
+        draw(&term, &matching, selected, &filter)?;
+
+        let key = match term.read_key() {
+            Ok(key) => key,
+            // Ctrl-C, which the terminal doesn't turn into a signal while a
+            // key is read
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => break Selection::Quit,
+            Err(e) => {
+                let _ignored = term.show_cursor();
+                return Err(e);
+            }
+        };
+        match key {
+            Key::ArrowUp => selected = selected.saturating_sub(1),
+            Key::ArrowDown => selected += 1,
+            Key::PageUp => selected = selected.saturating_sub(page_size(&term)),
+            Key::PageDown => selected += page_size(&term),
+            Key::Home => selected = 0,
+            Key::End => selected = matching.len(),
+            Key::Backspace => {
+                filter.pop();
+            }
+            Key::Char(c) if !c.is_control() => filter.push(c),
+            Key::Enter => {
+                if let Some(entry) = matching.get(selected) {
+                    break Selection::Watch(entry.exercise);
+                }
+            }
+            Key::Tab => {
+                if let Some(entry) = matching.get(selected) {
+                    term.show_cursor()?;
+                    open_in_editor(entry.exercise);
+                    term.hide_cursor()?;
+                }
+            }
+            Key::Escape => break Selection::Quit,
+            _ => {}
+        }
+    };
+    term.clear_screen()?;
+    term.show_cursor()?;
+    Ok(selection)
+}
+
+// Number of exercises that fit on the screen next to the header and footer
+fn page_size(term: &Term) -> usize {
+    (term.size().0 as usize).saturating_sub(5).max(1)
+}
+
+fn draw(term: &Term, matching: &[&Entry], selected: usize, filter: &str) -> io::Result<()> {
+    term.clear_screen()?;
+    term.write_line(&format!("Filter: {filter}"))?;
+    term.write_line(&format!(
+        "{}",
+        style(format!(
+            "  {:<17}\t{:<17}\t{:<7}",
+            "Name", "Topic", "Status"
+        ))
+        .bold()
+    ))?;
+
+    // Scroll so that the selected exercise is always visible
+    let page_size = page_size(term);
+    let first = selected.saturating_sub(page_size - 1);
+    for (i, entry) in matching.iter().enumerate().skip(first).take(page_size) {
+        let status = if entry.done { "Done" } else { "Pending" };
+        let line = format!(
+            "  {:<17}\t{:<17}\t{status:<7}",
+            entry.exercise.name, entry.topic
+        );
+        if i == selected {
+            term.write_line(&format!("{}", style(line).reverse()))?;
+        } else if entry.done {
+            term.write_line(&format!("{}", theme::success().apply_to(line)))?;
+        } else {
+            term.write_line(&line)?;
+        }
+    }
+
+    term.write_line("")?;
+    term.write_line(&format!(
+        "{}",
+        style(format!(
+            "{}: select  Enter: watch from exercise  Tab: open in editor  Esc: quit",
+            symbol("↑/↓", "Up/Down")
+        ))
+        .dim()
+    ))
+}
+
+// Whether all characters of the pattern appear in the text in the same order
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| text.any(|t| t == p))
+}
+
+pub const NO_EDITOR: &str =
+    "Set $VISUAL or $EDITOR, or `editor` in rustlings.toml, to open exercises in your editor";
+
+// Open the exercise in the configured editor, or the one set in $VISUAL or $EDITOR,
+// returning whether one is set. Editors that are known to support it are
+// pointed at the `I AM NOT DONE` marker.
+pub fn open_in_editor(exercise: &Exercise) -> bool {
+    let editor = CONFIG
+        .editor
+        .clone()
+        .or_else(|| env::var("VISUAL").ok())
+        .or_else(|| env::var("EDITOR").ok());
+    let Some(editor) = editor else {
+        return false;
+    };
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+    let _ignored = Command::new(program)
+        .args(parts)
+        .args(editor_args(program, exercise.marker()))
+        .status();
+    true
+}
+
+// The arguments that open the file at the given line and column in the editor
+fn editor_args(program: &str, (path, line, column): (&Path, usize, usize)) -> Vec<OsString> {
+    let name = Path::new(program)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut at = path.as_os_str().to_owned();
+    match name.as_str() {
+        "vi" | "vim" | "nvim" | "gvim" | "nano" | "emacs" | "emacsclient" | "micro" | "kak" => {
+            vec![format!("+{line}").into(), at]
+        }
+        "code" | "codium" | "code-insiders" => {
+            at.push(format!(":{line}:{column}"));
+            vec!["--goto".into(), at]
+        }
+        "subl" | "hx" | "helix" | "zed" => {
+            at.push(format!(":{line}:{column}"));
+            vec![at]
+        }
+        _ => vec![at],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_editor_args() {
+        let marker = (Path::new("exercises/intro/intro1.rs"), 12, 5);
+        assert_eq!(
+            editor_args("/usr/bin/vim", marker),
+            vec!["+12", "exercises/intro/intro1.rs"]
+        );
+        assert_eq!(
+            editor_args("code", marker),
+            vec!["--goto", "exercises/intro/intro1.rs:12:5"]
+        );
+        assert_eq!(editor_args("ed", marker), vec!["exercises/intro/intro1.rs"]);
+    }
+}
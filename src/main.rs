@@ -1,29 +1,96 @@
-use crate::exercise::{Exercise, ExerciseList};
-use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::completions::{parse_shell, Shell};
+use crate::config::CONFIG;
+use crate::editor::{parse_editor, Editor};
+use crate::exercise::{
+    exercises_dir, parse_difficulty, parse_mode, Difficulty, Exercise, ExerciseList, Mode,
+};
+use crate::grade::Rubric;
+use crate::hooks::Event;
+use crate::i18n::parse_language;
+use crate::list::Selection;
+use crate::output::{parse_color, symbol, ColorMode, Verbosity};
+use crate::progress::{record_completion, record_current, record_regression, Progress};
+use crate::project::{CargoWorkspace, Destination, RustAnalyzerProject, CARGO_WORKSPACE_DIR};
+use crate::report::{parse_format, Format, Report, Status};
+use crate::run::{reset, restore_marker, run, save_originals};
+use crate::scaffold::NewExercise;
+use crate::state::DoneStrategy;
+use crate::summary::{parse_summary_format, SummaryFormat};
+use crate::theme::{parse_theme, Theme};
+use crate::verify::{verify, Stopped};
+use crate::watch::Action;
 use argh::FromArgs;
-use console::Emoji;
 use notify::DebouncedEvent;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io::prelude::*;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
 
 #[macro_use]
 mod ui;
 
+mod backend;
+mod check_metadata;
+mod completions;
+mod config;
+mod deps;
+mod diagnostics;
+mod diff;
+mod doctor;
+mod editor;
+mod embedded;
+mod exam;
 mod exercise;
+mod grade;
+mod graph;
+mod hint;
+mod hooks;
+mod i18n;
+mod language_server;
+mod limits;
+mod lint;
+mod list;
+mod markdown;
+mod metadata;
+mod notification;
+mod output;
+mod panics;
+mod plugin;
+mod progress;
 mod project;
+mod property;
+mod registry;
+mod report;
+mod review;
 mod run;
+mod sandbox;
+mod scaffold;
+mod search;
+mod serve;
+mod session;
+mod signals;
+mod solution;
+mod state;
+mod stats;
+mod streak;
+mod submit;
+mod summary;
+mod sync;
+mod theme;
+mod topics;
+mod transfer;
+mod update;
 mod verify;
+mod wasm;
+mod watch;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -37,6 +104,39 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// only show the outcome, like which exercise failed, for scripts
+    #[argh(switch, short = 'q')]
+    quiet: bool,
+    /// also show the commands that are run, how long they take and the files they use
+    #[argh(switch)]
+    verbose: bool,
+    /// output format of `verify`, `run` and `list`, either `text` or `json`
+    #[argh(option, from_str_fn(parse_format), default = "Format::Text")]
+    format: Format,
+    /// the info.toml of the exercises to use, for example from a third-party exercise pack
+    #[argh(option, default = "PathBuf::from(\"info.toml\")")]
+    info_file: PathBuf,
+    /// run the exercises in a sandbox, without network access, for exercise packs that aren't trusted
+    #[argh(switch)]
+    untrusted: bool,
+    /// whether to color the output: auto, always or never
+    #[argh(option, from_str_fn(parse_color))]
+    color: Option<ColorMode>,
+    /// don't color the output, like `--color never`
+    #[argh(switch)]
+    no_color: bool,
+    /// the colors of what passed and failed: default, colorblind or monochrome
+    #[argh(option, from_str_fn(parse_theme))]
+    theme: Option<Theme>,
+    /// only print ASCII characters, without emoji or an animated spinner
+    #[argh(switch)]
+    ascii: bool,
+    /// print for screen readers: plain lines instead of spinners, progress bars and emoji
+    #[argh(switch)]
+    a11y: bool,
+    /// the language of messages and hints, like `en` or `es`
+    #[argh(option, from_str_fn(parse_language))]
+    lang: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -44,19 +144,108 @@ struct Args {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand)]
 enum Subcommands {
+    Init(InitArgs),
     Verify(VerifyArgs),
     Watch(WatchArgs),
     Run(RunArgs),
     Reset(ResetArgs),
     Hint(HintArgs),
+    NextHint(NextHintArgs),
+    About(AboutArgs),
+    Search(SearchArgs),
     List(ListArgs),
     Lsp(LspArgs),
+    LanguageServer(LanguageServerArgs),
+    Progress(ProgressArgs),
+    ExportProgress(ExportProgressArgs),
+    ImportProgress(ImportProgressArgs),
+    Sync(SyncArgs),
+    Stats(StatsArgs),
+    Report(ReportArgs),
+    Graph(GraphArgs),
+    Update(UpdateArgs),
+    Completions(CompletionsArgs),
+    Doctor(DoctorArgs),
+    Grade(GradeArgs),
+    Solution(SolutionArgs),
+    Streak(StreakArgs),
+    Open(OpenArgs),
+    Serve(ServeArgs),
+    Review(ReviewArgs),
+    Exam(ExamArgs),
+    Submit(SubmitArgs),
+    Done(DoneArgs),
+    NewExercise(NewExerciseArgs),
+    CheckMetadata(CheckMetadataArgs),
+    Prepare(PrepareArgs),
+    Lint(LintArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "init")]
+/// Sets up the exercises in a new directory
+struct InitArgs {
+    #[argh(positional, default = "PathBuf::from(\"rustlings\")")]
+    /// the directory to create, `rustlings` by default
+    dir: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "completions")]
+/// Prints a shell completion script, like `rustlings completions bash >> ~/.bashrc`
+struct CompletionsArgs {
+    #[argh(positional, from_str_fn(parse_shell))]
+    /// the shell to complete in: bash, zsh, fish or powershell
+    shell: Shell,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "doctor")]
+/// Checks for problems with the environment rustlings runs in
+struct DoctorArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "grade")]
+/// Verifies all exercises without stopping and scores them, for instructors
+struct GradeArgs {
+    /// a grading.toml with the points of exercises and topics,
+    /// by default the one next to info.toml if it exists
+    #[argh(option)]
+    rubric: Option<PathBuf>,
+    /// print the scores as CSV
+    #[argh(switch)]
+    csv: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    /// number of exercises to compile concurrently, 1 by default
+    #[argh(option, short = 'j')]
+    jobs: Option<usize>,
+    /// start verifying from the exercise with the given name
+    #[argh(option)]
+    from: Option<String>,
+    /// only verify the exercises of the given topic, like `move_semantics`
+    #[argh(option)]
+    only: Option<String>,
+    /// only verify the exercises whose name or path matches the glob pattern
+    #[argh(option, from_str_fn(parse_glob))]
+    glob: Option<glob::Pattern>,
+    /// only verify the exercises with the given tag, can be given multiple times
+    #[argh(option)]
+    tag: Vec<String>,
+    /// only verify the exercises of the given difficulty: easy, medium or hard
+    #[argh(option, from_str_fn(parse_difficulty))]
+    difficulty: Option<Difficulty>,
+    /// verify all exercises without stopping, and write a JUnit XML report to the given file
+    #[argh(option)]
+    report: Option<PathBuf>,
+    /// verify all exercises without stopping, and print a summary per topic
+    #[argh(switch)]
+    keep_going: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -65,39 +254,346 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// show a desktop notification when an exercise passes or fails
+    #[argh(switch)]
+    notify: bool,
+    /// ring the terminal bell when an exercise starts passing
+    #[argh(switch)]
+    bell: bool,
+    /// verify completed exercises again when their files change, and mark the
+    /// ones that broke as not completed anymore
+    #[argh(switch)]
+    recheck: bool,
+    /// show what changed in an exercise since it was last verified, above the
+    /// compiler output
+    #[argh(switch)]
+    diff: bool,
+    /// only watch the exercise with the given name, without moving on once it's done
+    #[argh(option, short = 'e')]
+    exercise: Option<String>,
+    /// milliseconds to wait for further changes before verifying, 1000 by default
+    #[argh(option)]
+    debounce: Option<u64>,
+    /// ignore changes to files whose name matches the glob pattern, in addition
+    /// to the temporary files of common editors. Can be given multiple times.
+    #[argh(option, from_str_fn(parse_glob))]
+    ignore: Vec<glob::Pattern>,
 }
 
+// Temporary files of vim, emacs and other editors
+const DEFAULT_IGNORE: &[&str] = &["*.swp", "*.swx", "*~", "~*", ".#*", "#*#"];
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
 /// Runs/Tests a single exercise
 struct RunArgs {
+    #[argh(switch)]
+    /// run the next pending exercise
+    next: bool,
     #[argh(positional)]
-    /// the name of the exercise
-    name: String,
+    /// the name of the exercise, followed by arguments to pass to it after `--`
+    args: Vec<String>,
+}
+
+impl RunArgs {
+    // The name of the exercise to run, where `next` stands for the next pending exercise
+    fn exercise_name(&self) -> &str {
+        match self.args.first() {
+            _ if self.next => "next",
+            Some(name) => name,
+            None => {
+                println!("{}", t!("run-needs-name"));
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // The arguments to pass to the exercise, which follow its name
+    fn exercise_args(&self) -> &[String] {
+        if self.next {
+            &self.args
+        } else {
+            self.args.get(1..).unwrap_or_default()
+        }
+    }
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets a single exercise to its original state
 struct ResetArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    /// only put the `I AM NOT DONE` marker back, keeping your changes
+    #[argh(switch)]
+    marker: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "hint")]
-/// Returns a hint for the given exercise
+/// Returns a hint for the given exercise, revealing one more level of hints each time
 struct HintArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    /// reveal all hints at once
+    #[argh(switch, short = 'a')]
+    all: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "next-hint")]
+/// Reveals the next hint for the exercise you're currently on
+struct NextHintArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "about")]
+/// Shows the README of a topic, along with the hints of an exercise
+struct AboutArgs {
+    #[argh(positional)]
+    /// the name of a topic, like `move_semantics`, or of an exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "search")]
+/// Searches the names, hints and comments of the exercises, and the topic READMEs
+struct SearchArgs {
+    #[argh(positional)]
+    /// what to search for, like `Rc`, matched at the start of words and ignoring case
+    query: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "open")]
+/// Opens an exercise in your editor, at its `I AM NOT DONE` comment
+struct OpenArgs {
+    #[argh(positional)]
+    /// the name of the exercise, or `next` for the next pending exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "serve")]
+/// Serves a dashboard of the exercises in your browser
+struct ServeArgs {
+    /// the address to serve the dashboard on
+    #[argh(option, short = 'a', default = "String::from(\"127.0.0.1:7878\")")]
+    address: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "streak")]
+/// Shows your streak of days working on exercises, and the badges you earned
+struct StreakArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "exam")]
+/// Solves random exercises of a topic again without hints, and scores them
+struct ExamArgs {
+    /// the topic to hold the exam on, like `move_semantics`
+    #[argh(positional)]
+    topic: String,
+    /// how many exercises to pick, 5 by default
+    #[argh(option, default = "5")]
+    count: usize,
+    /// how many minutes there are to solve them
+    #[argh(option)]
+    minutes: Option<u64>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-metadata")]
+/// Checks info.toml for missing files, duplicate names, invalid modes, empty hints and misordered exercises
+struct CheckMetadataArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "prepare")]
+/// Builds the crates exercises depend on ahead of time, so that no exercise waits for them
+struct PrepareArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "lint")]
+/// Runs clippy on exercises whatever their mode, and sums up their warnings
+struct LintArgs {
+    /// only lint the exercises of the given topic, like `move_semantics`
+    #[argh(option)]
+    only: Option<String>,
+    /// only lint the exercises whose name or path matches the glob pattern
+    #[argh(option, from_str_fn(parse_glob))]
+    glob: Option<glob::Pattern>,
+    /// only print the summary, not the warnings themselves
+    #[argh(switch)]
+    summary: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "new-exercise")]
+/// Creates a new exercise and adds it to info.toml, for exercise authors
+struct NewExerciseArgs {
+    #[argh(positional)]
+    /// the name of the exercise, like `iterators6`
+    name: String,
+    /// the folder in exercises/ it goes into, like `iterators`
+    #[argh(option)]
+    topic: String,
+    /// how the exercise is checked, `compile` by default
+    #[argh(option, from_str_fn(parse_mode), default = "Mode::Compile")]
+    mode: Mode,
+    /// the exercise it comes after, instead of the last one of its topic
+    #[argh(option)]
+    after: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "done")]
+/// Marks an exercise that passes as done, if exercises are marked as done with `done = "state"`
+struct DoneArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "export-progress")]
+/// Exports your progress to a file, to import it on another machine
+struct ExportProgressArgs {
+    #[argh(positional)]
+    /// the file to export to
+    file: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "import-progress")]
+/// Imports progress exported on another machine, replacing the progress here
+struct ImportProgressArgs {
+    #[argh(positional)]
+    /// the file to import from
+    file: PathBuf,
+    /// import even if the exercises here differ from the exported ones, or
+    /// have progress the export doesn't have
+    #[argh(switch)]
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "sync")]
+/// Syncs your progress with the backend set up in rustlings.toml, encrypted
+struct SyncArgs {
+    /// only merge the progress of the backend into yours
+    #[argh(switch)]
+    pull: bool,
+    /// only replace the progress of the backend with yours
+    #[argh(switch)]
+    push: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "submit")]
+/// Verifies all exercises and submits the results to your instructor
+struct SubmitArgs {
+    /// the URL to post the results to, instead of the one configured in `[submit]`
+    #[argh(option)]
+    endpoint: Option<String>,
+    /// include the hashes of the exercises, and sign the results with the token
+    #[argh(switch)]
+    attest: bool,
+    /// print what would be submitted instead of submitting it
+    #[argh(switch)]
+    dry_run: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "review")]
+/// Solves completed exercises again once they're due, so that they stick
+struct ReviewArgs {
+    /// list when the completed exercises are due for review instead
+    #[argh(switch)]
+    list: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "solution")]
+/// Shows the reference solution of an exercise after a few failed attempts
+struct SolutionArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+    /// compare your attempt to the solution instead of printing it
+    #[argh(switch, short = 'd')]
+    diff: bool,
+    /// show the solution without failing the exercise often enough first
+    #[argh(switch)]
+    i_give_up: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
-struct LspArgs {}
+struct LspArgs {
+    /// regenerate rust-project.json whenever exercises are added, removed or renamed
+    #[argh(switch, short = 'w')]
+    watch: bool,
+    /// generate a Cargo workspace in .rustlings-cargo instead of rust-project.json
+    #[argh(switch)]
+    cargo: bool,
+    /// where to write rust-project.json, `rust-project.json` by default
+    #[argh(option)]
+    out: Option<PathBuf>,
+    /// print rust-project.json instead of writing it
+    #[argh(switch)]
+    stdout: bool,
+    /// also write settings pointing rust-analyzer at the project for `vscode`, `helix` or `neovim`
+    #[argh(option, from_str_fn(parse_editor))]
+    editor: Option<Editor>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "language-server")]
+/// Runs a language server for editors that points out `I AM NOT DONE` and shows hints
+struct LanguageServerArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "progress")]
+/// Shows how many exercises and topics you have completed
+struct ProgressArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "stats")]
+/// Shows the time spent, attempts and failures per topic
+struct StatsArgs {
+    /// chart the exercises completed per day and the attempts per topic
+    #[argh(switch)]
+    chart: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "report")]
+/// Prints a summary of your progress per topic to share, as a Markdown table or CSV
+struct ReportArgs {
+    /// the format of the summary, either `md` or `csv`
+    #[argh(
+        option,
+        from_str_fn(parse_summary_format),
+        default = "SummaryFormat::Markdown"
+    )]
+    format: SummaryFormat,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "graph")]
+/// Prints the prerequisites of the exercises as a graph in the DOT format
+struct GraphArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "update")]
+/// Checks for a newer release, and updates the exercises you haven't worked on yet
+struct UpdateArgs {
+    /// don't check crates.io for a newer release of rustlings
+    #[argh(switch)]
+    offline: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
@@ -119,10 +615,60 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(switch, short = 'i')]
+    /// browse the exercises interactively
+    interactive: bool,
+    #[argh(option)]
+    /// display only exercises with the given tag, can be given multiple times
+    tag: Vec<String>,
+    #[argh(option, from_str_fn(parse_difficulty))]
+    /// display only exercises of the given difficulty: easy, medium or hard
+    difficulty: Option<Difficulty>,
 }
 
 fn main() {
     let args: Args = argh::from_env();
+    signals::install();
+    let color = if args.no_color {
+        Some(ColorMode::Never)
+    } else {
+        args.color.or(CONFIG.color)
+    };
+    color.unwrap_or(ColorMode::Auto).apply();
+    if let Some(theme) = args.theme.or(CONFIG.theme) {
+        theme::set(theme);
+    }
+    if args.ascii || CONFIG.ascii.unwrap_or(false) {
+        output::use_ascii();
+    }
+    if args.a11y || CONFIG.a11y.unwrap_or(false) {
+        output::use_a11y();
+    }
+    let configured_lang = CONFIG.lang.as_deref().and_then(|lang| {
+        parse_language(lang)
+            .map_err(|e| warn!("Ignoring the configured language: {}", e))
+            .ok()
+    });
+    if let Some(lang) = args.lang.clone().or(configured_lang) {
+        i18n::set_language(lang);
+    }
+
+    match (args.quiet, args.verbose) {
+        (true, true) => {
+            println!("{}", t!("quiet-verbose-conflict"));
+            std::process::exit(1);
+        }
+        (true, false) => output::set_verbosity(Verbosity::Quiet),
+        (false, true) => output::set_verbosity(Verbosity::Verbose),
+        (false, false) => {}
+    }
+
+    if args.untrusted {
+        sandbox::enable();
+        if *sandbox::ISOLATION == sandbox::Isolation::None {
+            warn!("{}", t!("sandbox-unisolated"));
+        }
+    }
 
     if args.version {
         println!("v{VERSION}");
@@ -133,55 +679,132 @@ fn main() {
         println!("\n{WELCOME}\n");
     }
 
-    if !Path::new("info.toml").exists() {
-        println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
-        );
-        println!("Try `cd rustlings/`!");
+    if let Some(Subcommands::Init(subargs)) = &args.nested {
+        if let Err(e) = embedded::init(&subargs.dir) {
+            println!("{}", t!("init-failed", error = e));
+            std::process::exit(1);
+        }
+        let dir = subargs.dir.display();
+        println!("{}", t!("init-done", dir = dir));
+        println!("{}", t!("init-next", dir = dir));
+        std::process::exit(0);
+    }
+
+    if let Some(Subcommands::Completions(subargs)) = &args.nested {
+        print!("{}", completions::script(&subargs.shell));
+        std::process::exit(0);
+    }
+
+    // This checks info.toml, so it mustn't need a valid one
+    if let Some(Subcommands::CheckMetadata(_)) = &args.nested {
+        let valid = check_metadata::run(&args.info_file);
+        std::process::exit(if valid { 0 } else { 1 });
+    }
+
+    if let Some(Subcommands::Doctor(_)) = &args.nested {
+        let works = doctor::run(&args.info_file);
+        std::process::exit(if works { 0 } else { 1 });
+    }
+
+    if !args.info_file.exists() {
+        let exe = std::env::current_exe().unwrap();
+        println!("{}", t!("wrong-dir", exe = exe.to_str().unwrap()));
+        println!("{}", t!("wrong-dir-help"));
         std::process::exit(1);
     }
 
     if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+        println!("{}", t!("no-rustc"));
+        println!("{}", t!("no-rustc-diagnose"));
+        println!("{}", t!("no-rustc-install"));
+        std::process::exit(1);
+    }
+
+    let ExerciseList {
+        exercises, topics, ..
+    } = ExerciseList::load(&args.info_file).unwrap();
+    if let Some(problem) = untrusted_problem(&exercises, &args.info_file) {
+        println!("{problem}");
         std::process::exit(1);
     }
+    let verbose = args.nocapture || output::verbose();
+    let mut watch_options = WatchOptions {
+        info_file: args.info_file.clone(),
+        exercises_dir: exercises_dir(&args.info_file),
+        verbose,
+        success_hints: false,
+        notify: false,
+        bell: false,
+        recheck: false,
+        diff: false,
+        pin: false,
+        debounce: Duration::from_secs(1),
+        ignore: Vec::new(),
+    };
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-    let verbose = args.nocapture;
+    if save_originals(&exercises, &args.info_file).is_err() {
+        println!("{}", t!("originals-failed"));
+    }
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", t!("default-out"));
         std::process::exit(0);
     });
     match command {
+        // Handled before looking for info.toml
+        Subcommands::Init(_)
+        | Subcommands::Completions(_)
+        | Subcommands::Doctor(_)
+        | Subcommands::CheckMetadata(_) => {}
+
+        Subcommands::List(subargs) if args.format == Format::Json => {
+            let reports: Vec<Report> = exercises
+                .iter()
+                .filter(|e| is_listed(e, &subargs))
+                .map(Report::state)
+                .collect();
+            report::print_json(&reports);
+        }
+
         Subcommands::List(subargs) => {
+            if subargs.interactive {
+                match list::interactive(&exercises) {
+                    Ok(Selection::Watch(exercise)) => {
+                        run_watch(&exercises, Some(exercise), &watch_options);
+                    }
+                    Ok(Selection::Quit) => {}
+                    Err(e) => {
+                        println!("{}", t!("interactive-list-failed", error = e));
+                        std::process::exit(1);
+                    }
+                }
+                std::process::exit(0);
+            }
             if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+                println!(
+                    "{:<17}\t{:<46}\t{:<7}",
+                    t!("list-name"),
+                    t!("list-path"),
+                    t!("list-status")
+                );
             }
             let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
+            // Exercises that failed when they were last checked,
+            // even though their marker was removed
+            let stats = Progress::load().stats;
+            let mut failing = Vec::new();
             exercises.iter().for_each(|e| {
                 let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
+                let status = if e.looks_done() && stats.get(&e.name).is_some_and(|s| s.failing) {
+                    failing.push(e.name.as_str());
+                    t!("status-failing")
+                } else if e.looks_done() {
                     exercises_done += 1;
-                    "Done"
+                    t!("status-done")
                 } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
+                    t!("status-pending")
                 };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
+                if is_listed(e, &subargs) {
                     let line = if subargs.paths {
                         format!("{fname}\n")
                     } else if subargs.names {
@@ -206,219 +829,1149 @@ fn main() {
             });
             let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
             println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
+                "{}",
+                t!(
+                    "list-progress",
+                    done = exercises_done,
+                    total = exercises.len(),
+                    percentage = format!("{percentage_progress:.1}"),
+                )
             );
+            if !failing.is_empty() && state::strategy() == DoneStrategy::Marker {
+                warn!("{}", t!("marked-done-failing", names = failing.join(", ")));
+                println!("{}", t!("marked-done-failing-help"));
+            }
             std::process::exit(0);
         }
 
+        Subcommands::Run(subargs) if args.format == Format::Json => {
+            let exercise = find_exercise(subargs.exercise_name(), &exercises);
+            let report = Report::run(exercise);
+            report::print_json(&report);
+            if report.status != Status::Passed {
+                std::process::exit(1);
+            }
+        }
+
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(subargs.exercise_name(), &exercises);
 
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            run(exercise, verbose, subargs.exercise_args())
+                .unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Reset(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            if subargs.marker {
+                put_marker_back(exercise).unwrap_or_else(|()| std::process::exit(1));
+            } else {
+                reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            }
         }
 
         Subcommands::Hint(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
+            check_prerequisites(exercise);
 
-            println!("{}", exercise.hint);
+            if subargs.all {
+                hint::reveal_all(&exercise.name, &exercise.hint);
+            } else {
+                hint::reveal(&exercise.name, &exercise.hint);
+            }
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::NextHint(_) => {
+            let exercise = current_exercise(&exercises);
+            check_prerequisites(exercise);
+            hint::reveal_next(&exercise.name, &exercise.hint);
         }
 
-        Subcommands::Lsp(_subargs) => {
-            let mut project = RustAnalyzerProject::new();
-            project
-                .get_sysroot_src()
-                .expect("Couldn't find toolchain path, do you have `rustc` installed?");
-            project
-                .exercises_to_json()
-                .expect("Couldn't parse rustlings exercises files");
+        Subcommands::About(subargs) => {
+            about(&subargs.name, &exercises);
+        }
 
-            if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
-            } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
-            } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+        Subcommands::Search(subargs) => {
+            if !search::run(&subargs.query, &exercises) {
+                std::process::exit(1);
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+        Subcommands::Solution(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            check_prerequisites(exercise);
+            if exam::in_progress() {
+                println!("{}", t!("exam-no-hints"));
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
+            if solution::show(exercise, subargs.diff, subargs.i_give_up).is_err() {
+                std::process::exit(1);
             }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+        }
+
+        Subcommands::Progress(_subargs) => {
+            progress::show(&exercises);
+        }
+
+        Subcommands::Stats(subargs) => {
+            if subargs.chart {
+                stats::chart(&exercises);
+            } else {
+                stats::show(&exercises);
             }
-        },
-    }
-}
+        }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
-                }
+        Subcommands::Update(subargs) => {
+            // The embedded exercises are the ones of rustlings itself, not of packs
+            if args.info_file != Path::new("info.toml") {
+                println!("{}", t!("update-pack"));
+                std::process::exit(1);
+            }
+            if let Err(e) = update::update(!subargs.offline) {
+                println!("{}", t!("update-failed", error = e));
+                std::process::exit(1);
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
-}
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
-    if name.eq("next") {
-        exercises
-            .iter()
-            .find(|e| !e.looks_done())
-            .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
-                std::process::exit(1)
-            })
-    } else {
-        exercises
-            .iter()
-            .find(|e| e.name == name)
-            .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
-                std::process::exit(1)
-            })
-    }
-}
+        Subcommands::Prepare(_subargs) => {
+            if !deps::prepare(&exercises) {
+                std::process::exit(1);
+            }
+        }
 
-enum WatchStatus {
-    Finished,
-    Unfinished,
-}
+        Subcommands::Lint(subargs) => {
+            let selected: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| subargs.only.as_ref().is_none_or(|t| e.topic() == *t))
+                .filter(|e| {
+                    subargs.glob.as_ref().is_none_or(|pattern| {
+                        pattern.matches(&e.name) || pattern.matches_path(&e.path)
+                    })
+                })
+                .collect();
+            if selected.is_empty() {
+                println!("{}", t!("no-matches"));
+                std::process::exit(1);
+            }
+            if !lint::lint(&selected, subargs.summary) {
+                std::process::exit(1);
+            }
+        }
 
-fn watch(
-    exercises: &[Exercise],
-    verbose: bool,
-    success_hints: bool,
-) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
-    }
+        Subcommands::Graph(_subargs) => {
+            print!("{}", graph::to_dot(&exercises, &Progress::load()));
+        }
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
+        Subcommands::Report(subargs) => {
+            summary::print(&exercises, &subargs.format);
+        }
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+        Subcommands::Open(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            if !list::open_in_editor(exercise) {
+                println!("{}", list::NO_EDITOR);
+                println!("{}", t!("open-location", path = exercise.path.display()));
+                std::process::exit(1);
+            }
+        }
 
-    clear_screen();
+        Subcommands::Streak(_subargs) => {
+            streak::show(&exercises);
+        }
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
+        Subcommands::Exam(subargs) => {
+            if let Err(e) = exam::run(&exercises, &subargs.topic, subargs.count, subargs.minutes) {
+                println!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::ExportProgress(subargs) => {
+            if let Err(e) = transfer::export(&exercises, &subargs.file) {
+                println!("{}", t!("export-failed", error = e));
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::ImportProgress(subargs) => {
+            match transfer::import(&exercises, &subargs.file, subargs.force) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    println!("{}", t!("import-failed", error = e));
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Sync(subargs) => {
+            let direction = match (subargs.pull, subargs.push) {
+                (true, false) => sync::Direction::Pull,
+                (false, true) => sync::Direction::Push,
+                _ => sync::Direction::Both,
+            };
+            if let Err(e) = sync::run(direction) {
+                println!("{}", t!("sync-failed", error = e));
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Submit(subargs) => {
+            submit(&exercises, &args.info_file, subargs);
+        }
+
+        Subcommands::NewExercise(subargs) => {
+            let new = NewExercise {
+                name: &subargs.name,
+                topic: &subargs.topic,
+                mode: subargs.mode,
+                after: subargs.after.as_deref(),
+            };
+            match scaffold::scaffold(&args.info_file, &exercises, &new) {
+                Ok(path) => success!("{}", t!("exercise-created", path = path.display())),
+                Err(e) => {
+                    println!("{}", t!("exercise-create-failed", error = e));
+                    std::process::exit(1);
+                }
+            }
+            generate_rust_project(
+                &args.info_file,
+                false,
+                &Destination::File(PathBuf::from("rust-project.json")),
+            );
+        }
+
+        Subcommands::Done(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            let passes = match verify::check(exercise) {
+                Ok(_) => true,
+                Err(output) => {
+                    println!("{}", output.stderr);
+                    false
+                }
+            };
+            if !mark_done(exercise, passes) {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Review(subargs) => {
+            if subargs.list {
+                review::list(&exercises);
+            } else if let Err(e) = review::run(&exercises, verbose) {
+                println!("{}", t!("review-failed", error = e));
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Serve(subargs) => {
+            // Exercises can also live right next to info.toml
+            let exercises_dir = Some(watch_options.exercises_dir)
+                .filter(|dir| dir.is_dir())
+                .or_else(|| args.info_file.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+            if let Err(e) = serve::serve(exercises, &subargs.address, &exercises_dir) {
+                println!("{}", t!("serve-failed", error = format!("{e:?}")));
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Verify(subargs) if subargs.report.is_some() => {
+            verify_with_report(&exercises, &subargs);
+        }
+
+        Subcommands::Verify(subargs) if args.format == Format::Json => {
+            // Like the text output, this stops at the first exercise that isn't done
+            let mut reports = Vec::new();
+            for exercise in selected_exercises(&exercises, &subargs) {
+                let report = Report::verify(exercise);
+                let done = report.status == Status::Done;
+                reports.push(report);
+                if !done {
+                    break;
+                }
+                record_completion(exercise);
+            }
+            report::print_json(&reports);
+            if reports.iter().any(|r| r.status != Status::Done) {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Verify(subargs) => {
+            let selected = selected_exercises(&exercises, &subargs);
+            verify(
+                selected.iter().copied(),
+                (0, selected.len()),
+                verbose,
+                false,
+                subargs.jobs.or(CONFIG.jobs).unwrap_or(1).max(1),
+                subargs.keep_going,
+            )
+            .unwrap_or_else(|stopped| {
+                if !stopped.passed
+                    && stopped.exercise.looks_done()
+                    && state::strategy() == DoneStrategy::Marker
+                {
+                    offer_marker(stopped.exercise);
+                }
+                std::process::exit(1)
+            });
+            if selected.len() == exercises.len() {
+                hooks::run(Event::AllDone);
+            }
+        }
+
+        Subcommands::Grade(subargs) => {
+            let rubric_file = subargs
+                .rubric
+                .unwrap_or_else(|| args.info_file.with_file_name("grading.toml"));
+            let rubric = if rubric_file.exists() {
+                Rubric::load(&rubric_file).unwrap_or_else(|e| {
+                    println!(
+                        "{}",
+                        t!("rubric-failed", path = rubric_file.display(), error = e)
+                    );
+                    std::process::exit(1)
+                })
+            } else {
+                Rubric::default()
+            };
+            let grade = grade::grade(&exercises, &rubric);
+            if args.format == Format::Json {
+                report::print_json(&grade);
+            } else if subargs.csv {
+                grade.print_csv();
+            } else {
+                grade.print_text();
+            }
+        }
+
+        Subcommands::Lsp(subargs) => {
+            if subargs.cargo && (subargs.out.is_some() || subargs.stdout) {
+                println!("{}", t!("lsp-cargo-conflict"));
+                std::process::exit(1);
+            }
+            if subargs.stdout
+                && (subargs.out.is_some() || subargs.watch || subargs.editor.is_some())
+            {
+                println!("{}", t!("lsp-stdout-conflict"));
+                std::process::exit(1);
+            }
+            let destination = match subargs.out {
+                _ if subargs.stdout => Destination::Stdout,
+                Some(out) if out.is_dir() => Destination::File(out.join("rust-project.json")),
+                Some(out) => Destination::File(out),
+                None => Destination::File(PathBuf::from("rust-project.json")),
+            };
+            generate_rust_project(&args.info_file, subargs.cargo, &destination);
+            if let (Some(editor), Destination::File(path)) = (&subargs.editor, &destination) {
+                let project = if subargs.cargo {
+                    Path::new(CARGO_WORKSPACE_DIR).join("Cargo.toml")
+                } else {
+                    path.clone()
+                };
+                match editor::write_settings(editor, &project) {
+                    Ok(files) => {
+                        for file in files {
+                            println!("{}", t!("editor-settings-written", path = file.display()));
+                        }
+                    }
+                    Err(e) => println!("{}", t!("editor-settings-failed", error = e)),
+                }
+            }
+            if subargs.watch {
+                if let Err(e) = watch_rust_project(&args.info_file, subargs.cargo, &destination) {
+                    println!("{}", t!("lsp-watch-failed", error = format!("{e:?}")));
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::LanguageServer(_subargs) => {
+            if let Err(e) = language_server::run(&exercises) {
+                eprintln!("{}", t!("language-server-failed", error = e));
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Watch(subargs) => {
+            let _watch_lock = match session::lock_watch() {
+                Ok(lock) => lock,
+                Err(pid) => {
+                    match pid {
+                        Some(pid) => println!("{}", t!("watch-running", pid = pid)),
+                        None => println!("{}", t!("watch-running-elsewhere")),
+                    }
+                    println!("{}", t!("watch-running-help"));
+                    std::process::exit(1);
+                }
+            };
+            // Before the progress tells where to resume from
+            sync::auto();
+            // Switches can only turn on what the configuration leaves off
+            let config = &CONFIG.watch;
+            watch_options.success_hints =
+                subargs.success_hints || config.success_hints.unwrap_or(false);
+            watch_options.notify = subargs.notify || config.notify.unwrap_or(false);
+            watch_options.bell = subargs.bell || config.bell.unwrap_or(false);
+            watch_options.recheck = subargs.recheck || config.recheck.unwrap_or(false);
+            watch_options.diff = subargs.diff || config.diff.unwrap_or(false);
+            watch_options.debounce =
+                Duration::from_millis(subargs.debounce.or(config.debounce).unwrap_or(1000));
+            let configured_ignore = config.ignore.iter().flatten().map(|pattern| {
+                parse_glob(pattern).unwrap_or_else(|e| {
+                    println!("{}", t!("invalid-ignore", error = e));
+                    std::process::exit(1)
+                })
+            });
+            watch_options.ignore = DEFAULT_IGNORE
+                .iter()
+                .map(|pattern| glob::Pattern::new(pattern).unwrap())
+                .chain(configured_ignore)
+                .chain(subargs.ignore)
+                .collect();
+            let pinned = subargs
+                .exercise
+                .as_deref()
+                .map(|name| find_exercise(name, &exercises));
+            watch_options.pin = pinned.is_some();
+            // Resume from the exercise watch mode was waiting on last time
+            let start = pinned.or_else(|| {
+                let current = Progress::load().current?;
+                exercises.iter().find(|e| e.name == current)
+            });
+            topics::watch(&exercises, &topics);
+            run_watch(&exercises, start, &watch_options);
+        }
+    }
+    // Runs that exit early leave theirs to the next run
+    session::end();
+}
+
+// Options that change how watch mode behaves
+struct WatchOptions {
+    // Read again whenever it changes
+    info_file: PathBuf,
+    // The folder that is watched for changes
+    exercises_dir: PathBuf,
+    // Show the output of test exercises
+    verbose: bool,
+    // Show hints when an exercise passes
+    success_hints: bool,
+    // Show desktop notifications when an exercise passes or fails
+    notify: bool,
+    // Ring the terminal bell when an exercise starts passing
+    bell: bool,
+    // Verify completed exercises again when their files change
+    recheck: bool,
+    // Show what changed since the last time an exercise was verified
+    diff: bool,
+    // Only watch the exercise watch mode starts from
+    pin: bool,
+    // How long to wait for further changes before verifying
+    debounce: Duration,
+    // File names that changes are ignored for
+    ignore: Vec<glob::Pattern>,
+}
+
+// Run watch mode, optionally starting from the given exercise,
+// and report how it ended
+fn run_watch(exercises: &[Exercise], start: Option<&Exercise>, options: &WatchOptions) {
+    let status = watch(exercises, start, options);
+    if status.is_ok() {
+        sync::auto();
+    }
+    match status {
+        Err(e) => {
+            println!("{}", t!("watch-failed", error = format!("{e:?}")));
+            println!("{}", t!("watch-failed-help"));
+            std::process::exit(1);
+        }
+        Ok(WatchStatus::Finished) => {
+            record_current(None);
+            hooks::run(Event::AllDone);
+            println!("{}", t!("all-done", emoji = symbol("🎉", "*")));
+            if output::ascii() {
+                info!("\n{}\n", t!("finish-line"));
+            } else {
+                info!("\n{FENISH_LINE}\n\n{}\n", t!("finish-line"));
+            }
+        }
+        Ok(WatchStatus::Unfinished) => {
+            println!("{}", t!("watch-unfinished"));
+            println!("{}", t!("watch-unfinished-help"));
+        }
+    }
+}
+
+// Generate rust-project.json for the exercises.
+// info.toml is read again every time, since it may have changed in watch mode.
+fn generate_rust_project(info_file: &Path, cargo: bool, destination: &Destination) {
+    let Ok(ExerciseList {
+        exercises,
+        excluded,
+        ..
+    }) = ExerciseList::load(info_file)
+    else {
+        println!("{}", t!("info-invalid"));
+        return;
     };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    if cargo {
+        generate_cargo_workspace(&exercises);
+        return;
+    }
+
+    // rust-project.json only has a single sysroot, so the toolchain that
+    // exercises ask for is used for all of them
+    let toolchain = exercises.iter().find_map(|e| e.toolchain.as_deref());
+    let mut project = RustAnalyzerProject::new();
+    project
+        .get_sysroot_src(toolchain)
+        .expect("Couldn't find toolchain path, do you have `rustc` installed?");
+    project
+        .exercises_to_json(&exercises, &excluded, &exercises_dir(info_file))
+        .expect("Couldn't parse rustlings exercises files");
+
+    if project.crates.is_empty() {
+        println!("{}", t!("project-no-exercises"));
+        return;
+    }
+    let path = match destination {
+        Destination::File(path) => path,
+        Destination::Stdout => {
+            // Only the JSON goes to stdout, so that it can be piped elsewhere
+            println!("{}", project.to_json());
+            for problem in project.validate() {
+                eprintln!("{problem}");
+            }
+            return;
+        }
+    };
+    match project.write_to_disk(path) {
+        Err(_) => {
+            println!("{}", t!("project-write-failed"));
+            return;
+        }
+        Ok(false) => println!("{}", t!("project-unchanged")),
+        Ok(true) => {
+            println!("{}", t!("project-generated"));
+            println!("{}", t!("project-restart"));
+        }
+    }
+    for problem in project.validate() {
+        warn!("{}", problem);
+    }
+    if !project.missing_proc_macros.is_empty() {
+        let packages: Vec<String> = project
+            .missing_proc_macros
+            .iter()
+            .map(|package| format!("-p {package}"))
+            .collect();
+        println!(
+            "{}",
+            t!("project-proc-macros", packages = packages.join(" "))
+        );
+    }
+}
+
+// Generate a Cargo workspace for the exercises, which rust-analyzer
+// has to be pointed at through its `linkedProjects` setting
+fn generate_cargo_workspace(exercises: &[Exercise]) {
+    match CargoWorkspace::new(exercises).and_then(|workspace| workspace.write_to_disk()) {
+        Ok(manifest) => {
+            println!(
+                "{}",
+                t!("workspace-generated", manifest = manifest.display())
+            );
+            println!("{}", t!("workspace-link"));
+            println!(
+                "  \"rust-analyzer.linkedProjects\": [\"{}\"]",
+                manifest.display()
+            );
+            println!("{}", t!("workspace-restart"));
+        }
+        Err(e) => println!("{}", t!("workspace-failed", error = e)),
+    }
+}
+
+// Regenerate rust-project.json whenever the set of exercises changes.
+// rust-analyzer watches rust-project.json itself, so rewriting it is
+// enough to make it reload the project.
+fn watch_rust_project(
+    info_file: &Path,
+    cargo: bool,
+    destination: &Destination,
+) -> notify::Result<()> {
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
+    watcher.watch(exercises_dir(info_file), RecursiveMode::Recursive)?;
+    watcher.watch(info_file, RecursiveMode::NonRecursive)?;
+
+    println!("{}", t!("lsp-watching"));
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
+        match rx.recv() {
             Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
+                DebouncedEvent::Create(_)
+                | DebouncedEvent::Remove(_)
+                | DebouncedEvent::Rename(_, _) => {
+                    generate_rust_project(info_file, cargo, destination);
+                }
+                DebouncedEvent::Write(b) if b.ends_with(info_file.file_name().unwrap()) => {
+                    generate_rust_project(info_file, cargo, destination);
                 }
                 _ => {}
             },
+            Err(e) => {
+                println!("{}", t!("watch-error", error = format!("{e:?}")));
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Whether the exercise matches the filters given to `list`
+fn is_listed(exercise: &Exercise, subargs: &ListArgs) -> bool {
+    let fname = format!("{}", exercise.path.display());
+    let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
+    let filter_cond = filters
+        .split(',')
+        .filter(|f| !f.trim().is_empty())
+        .any(|f| exercise.name.contains(f) || fname.contains(f));
+    let solve_cond = {
+        (exercise.looks_done() && subargs.solved)
+            || (!exercise.looks_done() && subargs.unsolved)
+            || (!subargs.solved && !subargs.unsolved)
+    };
+    solve_cond
+        && (filter_cond || subargs.filter.is_none())
+        && exercise.has_metadata(&subargs.tag, subargs.difficulty)
+}
+
+fn parse_glob(value: &str) -> Result<glob::Pattern, String> {
+    glob::Pattern::new(value).map_err(|e| format!("invalid glob pattern `{value}`: {e}"))
+}
+
+// Verify all exercises and post the results to the configured endpoint
+fn submit(exercises: &[Exercise], info_file: &Path, subargs: SubmitArgs) {
+    let endpoint = subargs.endpoint.or_else(|| CONFIG.submit.endpoint.clone());
+    let token = submit::token();
+    if subargs.attest && token.is_none() && !subargs.dry_run {
+        println!("{}", t!("submit-no-token"));
+        std::process::exit(1);
+    }
+    let submission = submit::collect(exercises, info_file, subargs.attest).unwrap_or_else(|e| {
+        println!("{}", t!("submit-collect-failed", error = e));
+        std::process::exit(1)
+    });
+    if subargs.dry_run {
+        println!("{}", submission.to_json());
+        return;
+    }
+    let Some(endpoint) = endpoint else {
+        println!("{}", t!("submit-no-endpoint"));
+        std::process::exit(1);
+    };
+    let spinner = output::spinner(t!("submitting", endpoint = endpoint));
+    let result = submission.post(&endpoint, token.as_deref());
+    spinner.finish_and_clear();
+    match result {
+        Ok(()) => success!("{}", t!("submitted", endpoint = endpoint)),
+        Err(e) => {
+            println!("{}", t!("submit-failed", endpoint = endpoint, error = e));
+            std::process::exit(1);
+        }
+    }
+}
+
+// Verify all selected exercises without stopping,
+// and write the results to the report file given to `verify`
+fn verify_with_report(exercises: &[Exercise], subargs: &VerifyArgs) {
+    let Some(path) = &subargs.report else {
+        return;
+    };
+    // Graders want to see the results of all exercises,
+    // so this doesn't stop at the first one that isn't done
+    let selected = selected_exercises(exercises, subargs);
+    let reports: Vec<Report> = selected.iter().map(|e| Report::verify(e)).collect();
+    for (exercise, report) in selected.iter().zip(&reports) {
+        if report.status == Status::Done {
+            record_completion(exercise);
+        }
+    }
+    if let Err(e) = report::write_junit(&reports, path) {
+        println!("{}", t!("junit-failed", path = path.display(), error = e));
+        std::process::exit(1);
+    }
+    let done = reports.iter().filter(|r| r.status == Status::Done).count();
+    println!(
+        "{}",
+        t!(
+            "junit-written",
+            done = done,
+            total = reports.len(),
+            path = path.display(),
+        )
+    );
+    if done < reports.len() {
+        std::process::exit(1);
+    }
+}
+
+// The exercises to verify, narrowed down by the filters of `verify`
+fn selected_exercises<'a>(exercises: &'a [Exercise], subargs: &VerifyArgs) -> Vec<&'a Exercise> {
+    let start = match &subargs.from {
+        Some(name) => {
+            let from = find_exercise(name, exercises);
+            exercises
+                .iter()
+                .position(|e| std::ptr::eq(e, from))
+                .unwrap_or(0)
+        }
+        None => 0,
+    };
+    let selected: Vec<&Exercise> = exercises[start..]
+        .iter()
+        .filter(|e| subargs.only.as_ref().is_none_or(|t| e.topic() == *t))
+        .filter(|e| e.has_metadata(&subargs.tag, subargs.difficulty))
+        .filter(|e| {
+            subargs
+                .glob
+                .as_ref()
+                .is_none_or(|pattern| pattern.matches(&e.name) || pattern.matches_path(&e.path))
+        })
+        .collect();
+    if selected.is_empty() {
+        println!("{}", t!("no-matches"));
+        std::process::exit(1);
+    }
+    selected
+}
+
+// Print the README of the topic with the given name, or of the topic of
+// the exercise with the given name followed by its hints
+fn about(name: &str, exercises: &[Exercise]) {
+    let exercise = exercises.iter().find(|e| e.name == name);
+    let Some(topic_exercise) = exercise.or_else(|| exercises.iter().find(|e| e.topic() == name))
+    else {
+        println!("{}", t!("no-topic", name = name));
+        std::process::exit(1)
+    };
+
+    match fs::read_to_string(topic_exercise.topic_dir().join("README.md")) {
+        Ok(readme) => print!("{}", markdown::render(&readme)),
+        Err(_) => println!("{}", t!("no-readme", topic = topic_exercise.topic())),
+    }
+
+    if let Some(exercise) = exercise {
+        println!();
+        hint::reveal(&exercise.name, &exercise.hint);
+    }
+}
+
+// Stop unless the exercises the given one requires are completed,
+// if the configuration asks to enforce that
+fn check_prerequisites(exercise: &Exercise) {
+    if !CONFIG.enforce_prerequisites.unwrap_or(false) {
+        return;
+    }
+    let missing = graph::missing_prerequisites(exercise, &Progress::load());
+    if !missing.is_empty() {
+        println!(
+            "{}",
+            t!(
+                "prerequisites-missing",
+                missing = missing.join(", "),
+                name = exercise.name,
+            )
+        );
+        std::process::exit(1);
+    }
+}
+
+// Mark the exercise as done, if exercises are marked as done and it passes.
+// Returns whether it was marked.
+fn mark_done(exercise: &Exercise, passes: bool) -> bool {
+    if state::strategy() != DoneStrategy::State {
+        println!("{}", t!("done-unused"));
+        return false;
+    }
+    if !passes {
+        warn!("{}", t!("done-failing", name = exercise.name));
+        return false;
+    }
+    if record_completion(exercise) {
+        hooks::run(Event::ExercisePassed(exercise));
+    }
+    success!("{}", t!("marked-done", name = exercise.name));
+    true
+}
+
+// Tell the user that the exercise fails even though its marker was removed,
+// and offer to put the marker back when there's someone to ask
+fn offer_marker(exercise: &Exercise) {
+    println!();
+    warn!("{}", t!("marker-removed", name = exercise.name));
+    if !std::io::stdin().is_terminal() {
+        println!("{}", t!("restore-marker-help", name = exercise.name));
+        return;
+    }
+    print!("{} ", t!("restore-marker-prompt"));
+    let _ignored = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_ok() && matches!(answer.trim(), "y" | "Y" | "yes")
+    {
+        let _ignored = put_marker_back(exercise);
+    }
+}
+
+fn put_marker_back(exercise: &Exercise) -> Result<(), ()> {
+    match restore_marker(exercise) {
+        Ok(path) => {
+            success!("{}", t!("marker-restored", path = path.display()));
+            Ok(())
+        }
+        Err(e) => {
+            warn!(
+                "{}",
+                t!("marker-restore-failed", name = exercise.name, error = e)
+            );
+            Err(())
+        }
+    }
+}
+
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+    if name.eq("next") {
+        exercises
+            .iter()
+            .find(|e| !e.looks_done())
+            .unwrap_or_else(|| {
+                println!("{}", t!("congratulations", emoji = symbol("🎉", "*")));
+                println!("{}", t!("no-next", emoji = symbol("🔚", "*")));
+                std::process::exit(1)
+            })
+    } else {
+        exercises
+            .iter()
+            .find(|e| e.name == name)
+            .unwrap_or_else(|| {
+                println!("{}", t!("no-exercise", name = name));
+                std::process::exit(1)
+            })
+    }
+}
+
+// The exercise watch mode was last waiting on, as long as it isn't done
+// since, or else the next pending exercise
+fn current_exercise(exercises: &[Exercise]) -> &Exercise {
+    Progress::load()
+        .current
+        .and_then(|current| exercises.iter().find(|e| e.name == current))
+        .filter(|e| !e.looks_done())
+        .unwrap_or_else(|| find_exercise("next", exercises))
+}
+
+enum WatchStatus {
+    Finished,
+    Unfinished,
+}
+
+fn watch(
+    exercises: &[Exercise],
+    start: Option<&Exercise>,
+    options: &WatchOptions,
+) -> notify::Result<WatchStatus> {
+    /* Clears the terminal with an ANSI escape code.
+    Works in UNIX and newer Windows terminals. Screen readers would lose
+    their place, so the output only scrolls for them. */
+    fn clear_screen() {
+        if output::a11y() {
+            println!();
+        } else {
+            println!("\x1Bc");
+        }
+    }
+
+    let (tx, rx) = channel();
+    let should_quit = Arc::new(AtomicBool::new(false));
+
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, options.debounce)?;
+    watcher.watch(&options.exercises_dir, RecursiveMode::Recursive)?;
+    // Its directory rather than the file itself, which editors often replace
+    let info_dir = match options.info_file.parent() {
+        Some(dir) if dir != Path::new("") => dir,
+        _ => Path::new("."),
+    };
+    watcher.watch(info_dir, RecursiveMode::NonRecursive)?;
+    let info_file = options.info_file.canonicalize().ok();
+    // Replaced by the ones of info.toml whenever it changes
+    let mut exercises: Vec<Exercise> = exercises.to_vec();
+
+    clear_screen();
+
+    // The exercise watch mode stays on, instead of moving on once it is done
+    let pinned = start.filter(|_| options.pin);
+
+    // When starting from a given exercise, it is verified first,
+    // followed by the remaining pending exercises
+    let (initial_exercises, num_done): (Vec<&Exercise>, usize) = match start {
+        Some(start) if options.pin => (vec![start], 0),
+        Some(start) => (
+            std::iter::once(start)
+                .chain(
+                    exercises
+                        .iter()
+                        .filter(|e| !e.looks_done() && e.name != start.name),
+                )
+                .collect(),
+            // The start exercise is counted once it's verified
+            exercises
+                .iter()
+                .filter(|e| e.looks_done() && e.name != start.name)
+                .count(),
+        ),
+        None => (exercises.iter().collect(), 0),
+    };
+
+    let notify_stopped = |stopped: &Stopped| {
+        if options.notify {
+            notification::exercise_stopped(stopped);
+        }
+    };
+    // Whether the current exercise passes, and only still has its `I AM NOT DONE` marker
+    let mut current_passed;
+    let total = if pinned.is_some() { 1 } else { exercises.len() };
+    let verified = verify(
+        initial_exercises,
+        (num_done, total),
+        options.verbose,
+        options.success_hints,
+        1,
+        false,
+    );
+    streak::celebrate(&exercises);
+    let current = match verified {
+        Ok(_) => match pinned {
+            Some(pinned) => {
+                announce_pinned_done(pinned);
+                current_passed = true;
+                Arc::new(Mutex::new(Some(pinned.clone())))
+            }
+            None => return Ok(WatchStatus::Finished),
+        },
+        Err(stopped) => {
+            announce_current(stopped.exercise);
+            current_passed = stopped.passed;
+            Arc::new(Mutex::new(Some(stopped.exercise.clone())))
+        }
+    };
+    // The sources of the current exercise as they were last verified
+    let mut attempts: HashMap<PathBuf, String> = HashMap::new();
+    if options.diff {
+        if let Some(exercise) = &*current.lock().unwrap() {
+            remember_attempt(exercise, &mut attempts);
+        }
+    }
+    let (actions_tx, actions) = channel();
+    let shared = Arc::new(Mutex::new(exercises.clone()));
+    watch::spawn_shell(&shared, &current, actions_tx, Arc::clone(&should_quit));
+    // Exercises skipped in the watch shell, in the order they were skipped
+    let mut skipped: Vec<String> = Vec::new();
+    loop {
+        // Set when the exercises have to be verified again,
+        // possibly starting from a given exercise
+        let mut recheck: Option<Option<&Exercise>> = None;
+        // The source files that changed since the last check
+        let mut changed: Vec<PathBuf> = Vec::new();
+        let mut info_changed = false;
+        match rx.recv_timeout(Duration::from_secs(1)) {
+            Ok(event) => {
+                // Editors often save a file in several steps, so all changes that
+                // were reported together only lead to verifying once
+                for event in std::iter::once(event).chain(rx.try_iter()) {
+                    if info_file.is_some() && changed_path(&event) == info_file {
+                        info_changed = true;
+                        continue;
+                    }
+                    let Some(filepath) = changed_file(event, &options.ignore) else {
+                        continue;
+                    };
+                    output::log(format!("{} changed", filepath.display()));
+                    state::invalidate(&filepath);
+                    changed.push(filepath);
+                }
+            }
             Err(RecvTimeoutError::Timeout) => {
                 // the timeout expired, just check the `should_quit` variable below then loop again
             }
-            Err(e) => println!("watch error: {e:?}"),
+            Err(e) => println!("{}", t!("watch-error", error = format!("{e:?}"))),
+        }
+        // What changed in info.toml, told once the screen was cleared
+        let mut reload_notice = None;
+        if info_changed {
+            if let Some((reloaded, notice)) = reload_exercises(&options.info_file, &exercises) {
+                reload_notice = Some(notice);
+                let names = |exercises: &[Exercise]| -> Vec<String> {
+                    exercises.iter().map(|e| e.name.clone()).collect()
+                };
+                let same_exercises = names(&exercises) == names(&reloaded);
+                exercises = reloaded;
+                *shared.lock().unwrap() = exercises.clone();
+                // Like its hint, which may have been fixed
+                let mut current = current.lock().unwrap();
+                let updated = current
+                    .as_ref()
+                    .and_then(|c| exercises.iter().find(|e| e.name == c.name));
+                match updated {
+                    Some(updated) => *current = Some(updated.clone()),
+                    // Watch mode moves on to the next one
+                    None => recheck = Some(None),
+                }
+                // New exercises may come before the current one
+                if !same_exercises && pinned.is_none() {
+                    recheck = Some(None);
+                }
+            }
+        }
+        for filepath in &changed {
+            match pinned {
+                Some(pinned) if pinned.contains(filepath) => recheck = Some(Some(pinned)),
+                Some(_) => {}
+                None => recheck = Some(exercises.iter().find(|e| e.contains(filepath))),
+            }
+        }
+        while let Ok(action) = actions.try_recv() {
+            if matches!(action, Action::Done) {
+                let current = current.lock().unwrap().clone();
+                if let Some(exercise) = current {
+                    if mark_done(&exercise, current_passed) {
+                        recheck = Some(None);
+                    }
+                }
+                continue;
+            }
+            if let Some(pinned) = pinned {
+                println!("{}", t!("pinned-only", name = pinned.name));
+                continue;
+            }
+            match action {
+                Action::Skip => {
+                    if let Some(exercise) = &*current.lock().unwrap() {
+                        println!("{}", t!("skipping", name = exercise.name));
+                        skipped.retain(|name| *name != exercise.name);
+                        skipped.push(exercise.name.clone());
+                    }
+                    recheck = Some(None);
+                }
+                Action::Run(name) => {
+                    skipped.retain(|skipped| *skipped != name);
+                    recheck = Some(exercises.iter().find(|e| e.name == name));
+                }
+                Action::Done => {}
+            }
+        }
+
+        if let Some(first) = recheck {
+            let mut to_verify = match pinned {
+                Some(pinned) => vec![pinned],
+                None => pending_order(&exercises, first, &skipped),
+            };
+            // Completed exercises that changed are verified right after the
+            // changed exercise, so that breaking them doesn't go unnoticed
+            let rechecked = if options.recheck && pinned.is_none() {
+                changed_completed(&exercises, &changed, &to_verify)
+            } else {
+                Vec::new()
+            };
+            let at = usize::from(first.is_some());
+            to_verify.splice(at..at, rechecked.iter().copied());
+            let (num_done, total) = match pinned {
+                Some(_) => (0, 1),
+                // The exercises that are verified are counted once they pass
+                None => (
+                    exercises
+                        .iter()
+                        .filter(|e| e.looks_done() && !to_verify.iter().any(|v| v.name == e.name))
+                        .count(),
+                    exercises.len(),
+                ),
+            };
+            clear_screen();
+            if let Some(notice) = reload_notice.take() {
+                print!("{notice}");
+            }
+            if let (true, Some(first)) = (options.diff, first) {
+                show_changes(first, &attempts);
+            }
+            let verified = verify(
+                to_verify,
+                (num_done, total),
+                options.verbose,
+                options.success_hints,
+                1,
+                false,
+            );
+            streak::celebrate(&exercises);
+            match verified {
+                Ok(_) => {
+                    if options.bell && !(pinned.is_some() && current_passed) {
+                        notification::bell();
+                    }
+                    let Some(pinned) = pinned else {
+                        if options.notify {
+                            notification::all_done();
+                        }
+                        return Ok(WatchStatus::Finished);
+                    };
+                    current_passed = true;
+                    announce_pinned_done(pinned);
+                }
+                Err(stopped) => {
+                    notify_stopped(&stopped);
+                    // Exercises that got their marker back aren't broken, just not done
+                    if options.recheck
+                        && !stopped.passed
+                        && stopped.exercise.looks_done()
+                        && record_regression(stopped.exercise)
+                    {
+                        warn!("{}", t!("regression", name = stopped.exercise.name));
+                    } else if !stopped.passed
+                        && stopped.exercise.looks_done()
+                        && state::strategy() == DoneStrategy::Marker
+                    {
+                        // Watch mode would move on from it once another file changes
+                        warn!("{}", t!("marker-removed", name = stopped.exercise.name));
+                        println!(
+                            "{}",
+                            t!("restore-marker-help", name = stopped.exercise.name)
+                        );
+                    }
+                    announce_current(stopped.exercise);
+                    let previous = current.lock().unwrap().replace(stopped.exercise.clone());
+                    // Either the current exercise passes now, or it was completed
+                    // and watch mode moved on to another one
+                    let started_passing = match previous {
+                        Some(previous) if previous.name == stopped.exercise.name => {
+                            stopped.passed && !current_passed
+                        }
+                        Some(previous) => previous.looks_done(),
+                        None => false,
+                    };
+                    if started_passing && options.bell {
+                        notification::bell();
+                    }
+                    current_passed = stopped.passed;
+                }
+            }
+            if options.diff {
+                if let Some(exercise) = &*current.lock().unwrap() {
+                    remember_attempt(exercise, &mut attempts);
+                }
+            }
+        }
+        if let Some(notice) = reload_notice {
+            print!("{notice}");
         }
         // Check if we need to exit
         if should_quit.load(Ordering::SeqCst) {
@@ -427,9 +1980,216 @@ fn watch(
     }
 }
 
+// Remember the sources of the exercise as they are now, which is
+// how they were verified last
+fn remember_attempt(exercise: &Exercise, attempts: &mut HashMap<PathBuf, String>) {
+    for source in exercise.sources() {
+        if let Ok(contents) = fs::read_to_string(source) {
+            attempts.insert(source.to_path_buf(), contents);
+        }
+    }
+}
+
+// Print how the sources of the exercise changed since they were last verified
+fn show_changes(exercise: &Exercise, attempts: &HashMap<PathBuf, String>) {
+    for source in exercise.sources() {
+        let (Some(last), Ok(now)) = (attempts.get(source), fs::read_to_string(source)) else {
+            continue;
+        };
+        if *last != now {
+            let path = source.display();
+            diff::print_unified(
+                &t!("diff-last-attempt", path = path),
+                last,
+                &t!("diff-now", path = path),
+                &now,
+            );
+            println!();
+        }
+    }
+}
+
+// Tell the user that the only exercise watch mode watches is done
+fn announce_pinned_done(exercise: &Exercise) {
+    println!();
+    success!("{}", t!("pinned-done", name = exercise.name));
+}
+
+// The order in which watch mode verifies the exercises that aren't done yet:
+// the given exercise first, and the skipped ones last
+fn pending_order<'a>(
+    exercises: &'a [Exercise],
+    first: Option<&'a Exercise>,
+    skipped: &[String],
+) -> Vec<&'a Exercise> {
+    let is_first = |e: &Exercise| first.is_some_and(|first| first.name == e.name);
+    let pending = exercises
+        .iter()
+        .filter(|e| !e.looks_done() && !is_first(e) && !skipped.contains(&e.name));
+    let skipped = skipped
+        .iter()
+        .filter_map(|name| exercises.iter().find(|e| e.name == *name))
+        .filter(|e| !e.looks_done() && !is_first(e));
+    first.into_iter().chain(pending).chain(skipped).collect()
+}
+
+// The completed exercises that the changed files are sources of, and that
+// aren't going to be verified already
+fn changed_completed<'a>(
+    exercises: &'a [Exercise],
+    changed: &[PathBuf],
+    to_verify: &[&Exercise],
+) -> Vec<&'a Exercise> {
+    let progress = Progress::load();
+    exercises
+        .iter()
+        .filter(|e| progress.is_completed(e) && e.looks_done())
+        .filter(|e| changed.iter().any(|file| e.contains(file)))
+        .filter(|e| !to_verify.iter().any(|v| v.name == e.name))
+        .collect()
+}
+
+// The file an event is about, if it was created or changed
+fn changed_path(event: &DebouncedEvent) -> Option<PathBuf> {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Chmod(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Rename(_, path) => path.canonicalize().ok(),
+        _ => None,
+    }
+}
+
+// Load info.toml again after it changed, along with a notice of which
+// exercises were added and removed. Returns `None` if it can't be used, and
+// watch mode keeps the exercises it had.
+fn reload_exercises(info_file: &Path, exercises: &[Exercise]) -> Option<(Vec<Exercise>, String)> {
+    let (reloaded, topics) = match ExerciseList::load(info_file) {
+        Ok(list) => (list.exercises, list.topics),
+        Err(e) => {
+            warn!(
+                "{}",
+                t!("info-reload-failed", path = info_file.display(), error = e)
+            );
+            return None;
+        }
+    };
+    if let Some(problem) = untrusted_problem(&reloaded, info_file) {
+        warn!("{}", problem);
+        return None;
+    }
+    let names_not_in = |these: &[Exercise], those: &[Exercise]| -> Vec<String> {
+        these
+            .iter()
+            .filter(|e| !those.iter().any(|other| other.name == e.name))
+            .map(|e| e.name.clone())
+            .collect()
+    };
+    let added = names_not_in(&reloaded, exercises);
+    let removed = names_not_in(exercises, &reloaded);
+    let mut notice = format!("\n{}\n", t!("info-reloaded", path = info_file.display()));
+    if !added.is_empty() {
+        notice.push_str(&t!("info-reloaded-added", names = added.join(", ")));
+        notice.push('\n');
+    }
+    if !removed.is_empty() {
+        notice.push_str(&t!("info-reloaded-removed", names = removed.join(", ")));
+        notice.push('\n');
+    }
+    // So that the new exercises can be reset too
+    if save_originals(&reloaded, info_file).is_err() {
+        println!("{}", t!("originals-failed"));
+    }
+    topics::watch(&reloaded, &topics);
+    Some((reloaded, notice))
+}
+
+// The source file that was changed by the event, unless it is ignored,
+// like the temporary files editors write
+fn changed_file(event: DebouncedEvent, ignore: &[glob::Pattern]) -> Option<PathBuf> {
+    let (DebouncedEvent::Create(path) | DebouncedEvent::Chmod(path) | DebouncedEvent::Write(path)) =
+        event
+    else {
+        return None;
+    };
+    let file_name = path.file_name()?.to_string_lossy();
+    if path.extension() != Some(OsStr::new("rs"))
+        || !path.exists()
+        || ignore.iter().any(|pattern| pattern.matches(&file_name))
+    {
+        return None;
+    }
+    path.canonicalize().ok()
+}
+
+// Print the path of the exercise watch mode is waiting on,
+// so that it's easy to open in an editor once watch mode advances to it
+fn announce_current(exercise: &Exercise) {
+    record_current(Some(&exercise.name));
+    println!();
+    println!("{}", t!("current-exercise", path = exercise.path.display()));
+    let footer = stats::footer(exercise);
+    if !footer.is_empty() {
+        info!("{}", console::style(footer).dim());
+    }
+}
+
+// Why the exercises can't be run with `--untrusted`, if they can't
+fn untrusted_problem(exercises: &[Exercise], info_file: &Path) -> Option<String> {
+    if !sandbox::enabled() {
+        return None;
+    }
+    // cargo and rustup would run what they're configured with outside of the
+    // sandbox, like when linting or with a toolchain given by path
+    let pack_dir = info_file.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let exercise_dirs = exercises.iter().filter_map(|e| e.path.parent());
+    if let Some(config) = std::iter::once(pack_dir.unwrap_or(Path::new(".")))
+        .chain(exercise_dirs)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find_map(sandbox::tool_config)
+    {
+        return Some(t!("sandbox-tool-config", path = config.display()));
+    }
+    for exercise in exercises {
+        // rustup would run the compiler of the pack
+        if let Some(toolchain) = exercise
+            .toolchain
+            .as_deref()
+            .filter(|t| sandbox::is_path_toolchain(t))
+        {
+            return Some(t!(
+                "sandbox-path-toolchain",
+                name = exercise.name,
+                toolchain = toolchain
+            ));
+        }
+        // Building crates runs their build scripts and proc macros
+        if !exercise.deps.is_empty() {
+            return Some(t!(
+                "sandbox-deps",
+                name = exercise.name,
+                deps = exercise.deps.join(", ")
+            ));
+        }
+        // rustdoc runs the doc tests itself, out of reach of the sandbox
+        if exercise.mode == Mode::Doctest {
+            return Some(t!("sandbox-doctest", name = exercise.name));
+        }
+        // Cargo builds the package however its Cargo.toml says
+        if exercise.mode == Mode::Project {
+            return Some(t!("sandbox-project", name = exercise.name));
+        }
+        // Plugins can do anything, and run outside of the sandbox
+        if exercise.mode == Mode::Plugin {
+            return Some(t!("sandbox-plugin", name = exercise.name));
+        }
+    }
+    None
+}
+
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
@@ -437,34 +2197,6 @@ fn rustc_exists() -> bool {
         .unwrap_or(false)
 }
 
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like
-   autocompletion, run the command `rustlings lsp`.
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
-
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
 +--------------------------  ------------------------+
@@ -483,14 +2215,7 @@ const FENISH_LINE: &str = r#"+--------------------------------------------------
          ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
        ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
        ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
-           ▒▒  ▒▒                      ▒▒  ▒▒
-
-We hope you enjoyed learning about the various aspects of Rust!
-If you noticed any issues, please don't hesitate to report them to our repo.
-You can also contribute your own exercises to help the greater community!
-
-Before reporting an issue or contributing, please read our guidelines:
-https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
+           ▒▒  ▒▒                      ▒▒  ▒▒"#;
 
 const WELCOME: &str = r#"       welcome to...
                  _   _ _
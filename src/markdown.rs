@@ -0,0 +1,96 @@
+use crate::output;
+use console::style;
+
+// Render the markdown of the topic READMEs for the terminal.
+// This only covers what the READMEs use: headings, lists, code and links.
+pub fn render(markdown: &str) -> String {
+    let mut rendered = String::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        let line = if in_code_block {
+            format!("    {}", style(line).yellow())
+        } else if let Some(heading) = line.strip_prefix("# ") {
+            style(plain(heading)).bold().underlined().to_string()
+        } else if let Some(heading) = line.strip_prefix("## ").or(line.strip_prefix("### ")) {
+            style(plain(heading)).bold().to_string()
+        } else if let Some(item) = line.strip_prefix("- ").or(line.strip_prefix("* ")) {
+            let bullet = if output::ascii() { "*" } else { "•" };
+            format!("  {bullet} {}", inline(item))
+        } else {
+            inline(line)
+        };
+        rendered.push_str(&line);
+        rendered.push('\n');
+    }
+    rendered
+}
+
+// Render code, bold text and links within a line
+fn inline(text: &str) -> String {
+    let mut rendered = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['`', '*', '[']) {
+        rendered.push_str(&rest[..start]);
+        let from = &rest[start..];
+        if let Some((code, after)) = delimited(from, "`", "`") {
+            rendered.push_str(&style(code).yellow().to_string());
+            rest = after;
+        } else if let Some((bold, after)) = delimited(from, "**", "**") {
+            rendered.push_str(&style(bold).bold().to_string());
+            rest = after;
+        } else if let Some((text, url, after)) = link(from) {
+            rendered.push_str(&format!("{text} ({})", style(url).underlined()));
+            rest = after;
+        } else {
+            rendered.push_str(&from[..1]);
+            rest = &from[1..];
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+// Like `inline`, but without any styling, for text that is styled as a whole
+fn plain(text: &str) -> String {
+    console::strip_ansi_codes(&inline(text)).to_string()
+}
+
+// Split `open...close` off the start of the text, returning what's in between
+// and what comes after
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, &'a str)> {
+    let inner = text.strip_prefix(open)?;
+    let end = inner.find(close)?;
+    Some((&inner[..end], &inner[end + close.len()..]))
+}
+
+// Split a `[text](url)` link off the start of the text
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, after) = delimited(text, "[", "]")?;
+    let (url, after) = delimited(after, "(", ")")?;
+    Some((label, url, after))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        console::set_colors_enabled(false);
+        let markdown = "# Move Semantics\n\
+                        \n\
+                        See [the book](https://doc.rust-lang.org/book) and `Vec`.\n\
+                        - [Ownership](https://example.com)";
+        assert_eq!(
+            render(markdown),
+            "Move Semantics\n\
+             \n\
+             See the book (https://doc.rust-lang.org/book) and Vec.\n  \
+             • Ownership (https://example.com)\n"
+        );
+    }
+}
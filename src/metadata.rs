@@ -0,0 +1,167 @@
+use crate::output::LoggedCommand;
+use serde::Deserialize;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The package and dependency graph of a Cargo workspace,
+/// as reported by `cargo metadata --format-version 1`
+#[derive(Deserialize)]
+pub struct Metadata {
+    pub packages: Vec<Package>,
+    /// Missing when cargo was asked not to resolve dependencies
+    pub resolve: Option<Resolve>,
+    /// Where cargo puts what it compiles
+    pub target_directory: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+pub struct Package {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub manifest_path: PathBuf,
+    #[serde(default)]
+    pub targets: Vec<Target>,
+}
+
+#[derive(Deserialize)]
+pub struct Target {
+    /// Like `lib`, `bin`, `test` or `proc-macro`
+    pub kind: Vec<String>,
+    pub src_path: PathBuf,
+    pub edition: String,
+}
+
+#[derive(Deserialize)]
+pub struct Resolve {
+    pub nodes: Vec<Node>,
+    /// The ID of the package of the manifest, unless it's a virtual workspace
+    pub root: Option<String>,
+}
+
+/// A package in the dependency graph, with the features it's built with
+#[derive(Deserialize)]
+pub struct Node {
+    pub id: String,
+    #[serde(default)]
+    pub deps: Vec<NodeDep>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NodeDep {
+    /// The name the dependency is used by in code, with `-` replaced by `_`
+    pub name: String,
+    pub pkg: String,
+    #[serde(default)]
+    pub dep_kinds: Vec<DepKind>,
+}
+
+#[derive(Deserialize)]
+pub struct DepKind {
+    /// `dev` or `build`, or missing for normal dependencies
+    pub kind: Option<String>,
+}
+
+impl Metadata {
+    /// Run `cargo metadata` on the given manifest. When offline,
+    /// cargo only uses crates it already downloaded.
+    pub fn load(manifest: &Path, offline: bool) -> Result<Metadata, Box<dyn Error>> {
+        let mut cargo = Command::new("cargo");
+        cargo
+            .args(["metadata", "--format-version", "1"])
+            .arg("--manifest-path")
+            .arg(manifest);
+        if offline {
+            cargo.arg("--offline");
+        }
+        let output = cargo.logged_output()?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+        }
+        Metadata::parse(&output.stdout)
+    }
+
+    pub fn parse(json: &[u8]) -> Result<Metadata, Box<dyn Error>> {
+        Ok(serde_json::from_slice(json)?)
+    }
+
+    pub fn package(&self, id: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| p.id == id)
+    }
+
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.resolve.as_ref()?.nodes.iter().find(|n| n.id == id)
+    }
+}
+
+impl Package {
+    /// The library target of the package, which other crates depend on
+    pub fn lib(&self) -> Option<&Target> {
+        self.targets.iter().find(|t| {
+            t.kind
+                .iter()
+                .any(|k| matches!(k.as_str(), "lib" | "rlib" | "proc-macro"))
+        })
+    }
+}
+
+impl Target {
+    pub fn is_proc_macro(&self) -> bool {
+        self.kind.iter().any(|k| k == "proc-macro")
+    }
+}
+
+impl NodeDep {
+    /// Whether the dependency is needed by the code of the package itself,
+    /// rather than only by its tests or build script
+    pub fn is_normal(&self) -> bool {
+        self.dep_kinds.is_empty() || self.dep_kinds.iter().any(|k| k.kind.is_none())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let metadata = Metadata::parse(
+            br#"{
+                "packages": [
+                    {
+                        "id": "rand 0.8.5",
+                        "name": "rand",
+                        "version": "0.8.5",
+                        "manifest_path": "/registry/rand-0.8.5/Cargo.toml",
+                        "targets": [
+                            {"name": "rand", "kind": ["lib"], "src_path": "/registry/rand-0.8.5/src/lib.rs", "edition": "2018"}
+                        ]
+                    }
+                ],
+                "resolve": {
+                    "nodes": [
+                        {
+                            "id": "rand 0.8.5",
+                            "deps": [
+                                {"name": "rand_core", "pkg": "rand_core 0.6.4", "dep_kinds": [{"kind": null}]},
+                                {"name": "bincode", "pkg": "bincode 1.3.3", "dep_kinds": [{"kind": "dev"}]}
+                            ],
+                            "features": ["std"]
+                        }
+                    ]
+                },
+                "version": 1
+            }"#,
+        )
+        .unwrap();
+        let rand = metadata.package("rand 0.8.5").unwrap();
+        assert_eq!(rand.lib().unwrap().edition, "2018");
+        let node = metadata.node(&rand.id).unwrap();
+        assert_eq!(node.features, ["std"]);
+        assert!(node.deps[0].is_normal());
+        assert!(!node.deps[1].is_normal());
+    }
+}
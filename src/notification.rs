@@ -0,0 +1,73 @@
+use crate::verify::Stopped;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+// Tell the user about the exercise watch mode stopped at
+pub fn exercise_stopped(stopped: &Stopped) {
+    if stopped.passed {
+        send(
+            "Rustlings: exercise passed",
+            &format!(
+                "{} passes! Remove the `I AM NOT DONE` comment to move on.",
+                stopped.exercise.name
+            ),
+        );
+    } else {
+        send(
+            "Rustlings: exercise failed",
+            &format!("{} doesn't pass yet, keep going!", stopped.exercise.name),
+        );
+    }
+}
+
+pub fn all_done() {
+    send("Rustlings", "All exercises completed!");
+}
+
+// Ring the terminal bell, which most terminals turn into a sound
+// or a visual cue even while they are in the background
+pub fn bell() {
+    print!("\x07");
+    let _ignored = io::stdout().flush();
+}
+
+// Show a desktop notification using the tools of the platform.
+// Notifications are a nicety, so failing to show one is silently ignored.
+fn send(summary: &str, body: &str) {
+    let _ignored = notification_command(summary, body)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(summary: &str, body: &str) -> Command {
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "display notification {body:?} with title {summary:?}"
+    ));
+    command
+}
+
+#[cfg(windows)]
+fn notification_command(summary: &str, body: &str) -> Command {
+    let script = format!(
+        "[reflection.assembly]::loadwithpartialname('System.Windows.Forms') | Out-Null; \
+        $n = New-Object System.Windows.Forms.NotifyIcon; \
+        $n.Icon = [System.Drawing.SystemIcons]::Information; \
+        $n.Visible = $true; \
+        $n.ShowBalloonTip(5000, '{}', '{}', 'Info')",
+        summary.replace('\'', "''"),
+        body.replace('\'', "''")
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command", &script]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn notification_command(summary: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args([summary, body]);
+    command
+}
@@ -0,0 +1,194 @@
+// How output looks: whether it's colored, whether it sticks to ASCII
+// for dumb terminals, logs and screen readers, which don't get along
+// with emoji and the animated spinner, and how much of it there is.
+
+use crate::limits::{self, Limits};
+use console::{style, Term};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::env;
+use std::io;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::Instant;
+
+// The frames of the spinner shown while compiling, when sticking to ASCII
+const ASCII_SPINNER: &[&str] = &["|", "/", "-", "\\", " "];
+
+// When to color the output, `auto` only doing so for terminals that support it
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+pub fn parse_color(value: &str) -> Result<ColorMode, String> {
+    match value {
+        "auto" => Ok(ColorMode::Auto),
+        "always" => Ok(ColorMode::Always),
+        "never" => Ok(ColorMode::Never),
+        _ => Err(format!(
+            "unknown color mode `{value}`, expected `auto`, `always` or `never`"
+        )),
+    }
+}
+
+impl ColorMode {
+    // Color the output accordingly from now on.
+    // See https://no-color.org for `NO_COLOR`.
+    pub fn apply(self) {
+        let enabled = match self {
+            ColorMode::Auto if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => false,
+            ColorMode::Auto => return,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        };
+        console::set_colors_enabled(enabled);
+        console::set_colors_enabled_stderr(enabled);
+    }
+}
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+static A11Y: AtomicBool = AtomicBool::new(false);
+
+// Only print ASCII characters from now on
+pub fn use_ascii() {
+    ASCII.store(true, Ordering::SeqCst);
+}
+
+// Print for screen readers from now on: in ASCII, with lines that are only
+// ever added to instead of spinners and progress bars that update in place
+pub fn use_a11y() {
+    A11Y.store(true, Ordering::SeqCst);
+    use_ascii();
+}
+
+pub fn a11y() -> bool {
+    A11Y.load(Ordering::SeqCst)
+}
+
+// Whether output sticks to ASCII, because it was asked to, emoji were turned
+// off with `NO_EMOJI`, or the terminal is a dumb one
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::SeqCst)
+        || env::var_os("NO_EMOJI").is_some()
+        || env::var("TERM").is_ok_and(|term| term == "dumb")
+}
+
+// The symbol, or its ASCII fallback where it can't be shown
+pub fn symbol(unicode: &'static str, fallback: &'static str) -> &'static str {
+    if ascii() || !Term::stdout().features().wants_emoji() {
+        fallback
+    } else {
+        unicode
+    }
+}
+
+// How much rustlings tells about what it's doing
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Verbosity {
+    // Only the outcome, for scripts that mostly care about the exit code
+    Quiet,
+    Normal,
+    // Also the commands that are run, how long they take and the files they use
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+// Tell as much as the verbosity asks for from now on
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::SeqCst);
+}
+
+pub fn quiet() -> bool {
+    VERBOSITY.load(Ordering::SeqCst) == Verbosity::Quiet as u8
+}
+
+pub fn verbose() -> bool {
+    VERBOSITY.load(Ordering::SeqCst) == Verbosity::Verbose as u8
+}
+
+// Print the message on stderr, if the output is verbose
+pub fn log(message: impl AsRef<str>) {
+    if verbose() {
+        eprintln!("{} {}", style("[rustlings]").dim(), message.as_ref());
+    }
+}
+
+// Running commands, logging what is run and how long it took
+pub trait LoggedCommand {
+    fn logged_output(&mut self) -> io::Result<Output>;
+    // Like `logged_output`, within the limits. Fails with
+    // `io::ErrorKind::TimedOut` if the command takes too long.
+    fn logged_output_within(&mut self, limits: &Limits) -> io::Result<Output>;
+    // Like `logged_output_within`, with the input written to stdin
+    fn logged_output_with_input(&mut self, limits: &Limits, input: &[u8]) -> io::Result<Output>;
+}
+
+impl LoggedCommand for Command {
+    fn logged_output(&mut self) -> io::Result<Output> {
+        log(format!("Running {self:?}"));
+        let start = Instant::now();
+        let output = self.output();
+        log_finished(start, &output);
+        output
+    }
+
+    fn logged_output_within(&mut self, limits: &Limits) -> io::Result<Output> {
+        log(format!("Running {self:?} for at most {:?}", limits.time));
+        let start = Instant::now();
+        let output = limits::output(self, limits);
+        log_finished(start, &output);
+        output
+    }
+
+    fn logged_output_with_input(&mut self, limits: &Limits, input: &[u8]) -> io::Result<Output> {
+        log(format!(
+            "Running {self:?} with {} bytes of input for at most {:?}",
+            input.len(),
+            limits.time
+        ));
+        let start = Instant::now();
+        let output = limits::output_with_input(self, limits, Some(input));
+        log_finished(start, &output);
+        output
+    }
+}
+
+fn log_finished(start: Instant, output: &io::Result<Output>) {
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    match output {
+        Ok(output) => log(format!(
+            "Finished in {elapsed:.1} ms with {}",
+            output.status
+        )),
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+            log(format!("Killed after {elapsed:.1} ms, since it {e}"))
+        }
+        Err(_) => {}
+    }
+}
+
+// A spinner with the given message, which keeps spinning until it's finished.
+// It's hidden when the output is quiet, and when it's verbose, so that it
+// doesn't get in the way of the log. For screen readers, the message is
+// printed as a line instead.
+pub fn spinner(message: String) -> ProgressBar {
+    if quiet() || verbose() {
+        return ProgressBar::hidden();
+    }
+    if a11y() {
+        println!("{message}");
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner();
+    if ascii() {
+        spinner.set_style(ProgressStyle::default_spinner().tick_strings(ASCII_SPINNER));
+    }
+    spinner.set_message(message);
+    spinner.enable_steady_tick(100);
+    spinner
+}
@@ -0,0 +1,285 @@
+// Render the output of failing tests for beginners, like the diagnostics of
+// rustc: for every failed test, its panic message and the line it panicked
+// at, the functions of the exercise that led there, and what it printed,
+// instead of the whole output of libtest with its backtraces.
+use crate::output;
+use crate::theme;
+use console::style;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+// Like `thread 'tests::doubles' (19993) panicked at exercises/x.rs:17:9:`
+static PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^thread '([^']*)'(?: \(\d+\))? panicked at (.+):(\d+):(\d+):$").unwrap()
+});
+// Like `   4: x::tests::doubles`
+static FRAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\d+: (.+)$").unwrap());
+// Like `             at ./exercises/x.rs:17:9`
+static FRAME_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+at (.+):(\d+):(\d+)$").unwrap());
+
+// A place in the source code
+#[derive(PartialEq, Debug)]
+struct Location {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+// A failed test that panicked
+#[derive(Default, Debug)]
+struct Failure {
+    test: String,
+    // What the test printed before panicking
+    printed: Vec<String>,
+    location: Option<Location>,
+    message: Vec<String>,
+    // The functions of the exercise the panic came through, innermost first
+    frames: Vec<(String, Location)>,
+}
+
+// Render the output of a test binary that failed. `files` are the source
+// files of the exercise, the only ones whose code is shown, which leaves out
+// hidden tests. Returns `None` if no panics could be found in the output.
+pub fn render(stdout: &str, files: &[&Path]) -> Option<String> {
+    let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+    let mut summary = None;
+    for line in stdout.lines() {
+        if let Some(test) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            sections.push((test.to_string(), Vec::new()));
+        } else if line == "failures:" {
+            // The list of the failed tests after their output
+            sections.push((String::new(), Vec::new()));
+        } else if line.starts_with("test result:") {
+            summary = Some(line);
+        } else if let Some((test, lines)) = sections.last_mut() {
+            if !test.is_empty() {
+                lines.push(line);
+            }
+        }
+    }
+
+    let mut rendered = String::new();
+    let mut rendered_any = false;
+    for (test, lines) in sections.iter().filter(|(test, _)| !test.is_empty()) {
+        match parse(test, lines) {
+            Some(failure) => {
+                render_failure(&failure, files, &mut rendered);
+                rendered_any = true;
+            }
+            // Like tests that returned an error, or didn't panic as they should
+            None => {
+                rendered.push_str(&format!("{}\n", style(format!("---- {test} ----")).bold()));
+                for line in lines.iter().filter(|line| !line.is_empty()) {
+                    rendered.push_str(&format!("{line}\n"));
+                }
+            }
+        }
+        rendered.push('\n');
+    }
+    if !rendered_any {
+        return None;
+    }
+    if let Some(summary) = summary {
+        rendered.push_str(summary);
+        rendered.push('\n');
+    }
+    Some(rendered)
+}
+
+fn parse(test: &str, lines: &[&str]) -> Option<Failure> {
+    let mut failure = Failure {
+        test: test.to_string(),
+        ..Failure::default()
+    };
+    let mut lines = lines.iter();
+    for line in lines.by_ref() {
+        if let Some(caps) = PANIC.captures(line) {
+            failure.location = Some(Location {
+                file: caps[2].to_string(),
+                line: caps[3].parse().ok()?,
+                column: caps[4].parse().ok()?,
+            });
+            break;
+        }
+        if !line.is_empty() {
+            failure.printed.push(line.to_string());
+        }
+    }
+    failure.location.as_ref()?;
+
+    let mut in_backtrace = false;
+    let mut function = None;
+    for line in lines {
+        if *line == "stack backtrace:" {
+            in_backtrace = true;
+        } else if line.starts_with("note: ") {
+            in_backtrace = false;
+        } else if !in_backtrace {
+            failure.message.push(line.to_string());
+        } else if let Some(caps) = FRAME_LOCATION.captures(line) {
+            if let (Some(function), Ok(line), Ok(column)) =
+                (function.take(), caps[2].parse(), caps[3].parse())
+            {
+                let file = caps[1].trim_start_matches("./").to_string();
+                failure
+                    .frames
+                    .push((function, Location { file, line, column }));
+            }
+        } else if let Some(caps) = FRAME.captures(line) {
+            function = Some(caps[1].to_string());
+        }
+    }
+    while failure.message.last().is_some_and(|line| line.is_empty()) {
+        failure.message.pop();
+    }
+    Some(failure)
+}
+
+fn render_failure(failure: &Failure, files: &[&Path], rendered: &mut String) {
+    let location = failure.location.as_ref().unwrap();
+    rendered.push_str(&format!(
+        "{} {} {}\n",
+        theme::failure().bold().apply_to(output::symbol("✗", "x")),
+        style(&failure.test).bold(),
+        style(format!(
+            "panicked at {}:{}:{}",
+            location.file, location.line, location.column
+        ))
+        .dim()
+    ));
+    for line in &failure.message {
+        rendered.push_str(&format!("  {}\n", theme::failure().apply_to(line)));
+    }
+    if let Some(source) = source_line(location, files) {
+        rendered.push_str(&format!(
+            "  {} {}\n",
+            style(format!("{} |", location.line)).blue().bold(),
+            source
+        ));
+    }
+
+    let frames: Vec<_> = failure
+        .frames
+        .iter()
+        .filter(|(function, frame)| {
+            !function.ends_with("{{closure}}")
+                && frame != location
+                && files.iter().any(|file| same_file(&frame.file, file))
+        })
+        .collect();
+    if !frames.is_empty() {
+        rendered.push_str("  called from:\n");
+        for (function, frame) in frames {
+            rendered.push_str(&format!(
+                "    {} {}\n",
+                short_name(function),
+                style(format!("at {}:{}", frame.file, frame.line)).dim()
+            ));
+        }
+    }
+
+    if !failure.printed.is_empty() {
+        rendered.push_str("  it printed:\n");
+        for line in &failure.printed {
+            rendered.push_str(&format!("    {line}\n"));
+        }
+    }
+}
+
+// The line of the exercise the location is at, if it's in one of its files
+fn source_line(location: &Location, files: &[&Path]) -> Option<String> {
+    let file = files.iter().find(|file| same_file(&location.file, file))?;
+    let source = fs::read_to_string(file).ok()?;
+    let line = source.lines().nth(location.line.checked_sub(1)?)?;
+    Some(line.trim_end().to_string())
+}
+
+fn same_file(printed: &str, file: &Path) -> bool {
+    Path::new(printed.trim_start_matches("./")) == file
+}
+
+// The function without the name of the crate, which is the temporary binary
+fn short_name(function: &str) -> &str {
+    function.split_once("::").map_or(function, |(_, rest)| rest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = "
+running 3 tests
+test tests::big ... FAILED
+test tests::doubles ... FAILED
+test tests::fine ... ok
+
+failures:
+
+---- tests::big stdout ----
+
+thread 'tests::big' (19992) panicked at exercises/fail1.rs:7:18:
+too big: 1000
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/library/std/src/panicking.rs:689:5
+   2: fail1::check
+             at ./exercises/fail1.rs:7:18
+   3: fail1::double
+             at ./exercises/fail1.rs:2:5
+   4: fail1::tests::big
+             at ./hidden_tests/fail1.rs:22:9
+   5: fail1::tests::big::{{closure}}
+             at ./hidden_tests/fail1.rs:21:13
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+---- tests::doubles stdout ----
+checking
+
+thread 'tests::doubles' (19993) panicked at exercises/fail1.rs:17:9:
+assertion `left == right` failed
+  left: 6
+ right: 4
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::big
+    tests::doubles
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+";
+
+    #[test]
+    fn test_render() {
+        console::set_colors_enabled(false);
+        let rendered = render(OUTPUT, &[Path::new("exercises/fail1.rs")]).unwrap();
+        assert_eq!(
+            rendered,
+            "x tests::big panicked at exercises/fail1.rs:7:18
+  too big: 1000
+  called from:
+    double at exercises/fail1.rs:2
+
+x tests::doubles panicked at exercises/fail1.rs:17:9
+  assertion `left == right` failed
+    left: 6
+   right: 4
+  it printed:
+    checking
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+"
+        );
+    }
+
+    #[test]
+    fn test_render_without_panics() {
+        assert_eq!(render("running 0 tests\n", &[]), None);
+    }
+}
@@ -0,0 +1,180 @@
+// Exercises with `mode = "plugin"` are verified by a plugin instead of by
+// rustlings itself, which lets courses add modes of their own, like checking
+// an exercise with miri or running it against a web service, without
+// changing rustlings. A plugin is an executable called `rustlings-mode-<name>`
+// in the `plugins` folder next to info.toml, or else on the PATH, which is
+// asked to verify an exercise like this:
+//
+// - The request is written to its stdin as a JSON object, with `protocol`,
+//   the version of this protocol, `action`, which is `verify` for now,
+//   `exercise`, with the `name`, `path` and other source `files` of the
+//   exercise, `args`, which `rustlings run` was given, `options`, the
+//   `plugin_options` table of the exercise in info.toml, and `verbose`.
+// - The response is read from its stdout as a JSON object, with `passed`,
+//   whether the exercise passes, and `stdout` and `stderr`, what to show the
+//   learner, which can be left out.
+//
+// Whatever the plugin prints to stderr is shown when it doesn't respond.
+use crate::exercise::Exercise;
+use crate::limits::Limits;
+use crate::output::LoggedCommand;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The version of the protocol, which changes when plugins written for
+// another one wouldn't understand it anymore
+pub const PROTOCOL: u32 = 1;
+
+// What plugin executables are called, followed by the name of the plugin
+const PREFIX: &str = "rustlings-mode-";
+
+// What a plugin is asked to do
+#[derive(Serialize, Debug)]
+pub struct Request<'a> {
+    pub protocol: u32,
+    pub action: &'static str,
+    pub exercise: RequestedExercise<'a>,
+    pub args: &'a [String],
+    pub options: Option<&'a toml::Value>,
+    pub verbose: bool,
+}
+
+// The exercise a plugin is asked about
+#[derive(Serialize, Debug)]
+pub struct RequestedExercise<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    pub files: &'a [PathBuf],
+}
+
+impl Request<'_> {
+    // Ask to verify the exercise, passing the arguments on to it
+    pub fn verify<'a>(exercise: &'a Exercise, args: &'a [String], verbose: bool) -> Request<'a> {
+        Request {
+            protocol: PROTOCOL,
+            action: "verify",
+            exercise: RequestedExercise {
+                name: &exercise.name,
+                path: &exercise.path,
+                files: &exercise.files,
+            },
+            args,
+            options: exercise.plugin_options.as_ref(),
+            verbose,
+        }
+    }
+}
+
+// What a plugin responds with
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct Response {
+    pub passed: bool,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+}
+
+// Something that verifies exercises of a mode rustlings doesn't know itself
+pub trait ModeRunner {
+    // Answer the request within the limits. Fails with
+    // `io::ErrorKind::TimedOut` if that takes too long.
+    fn verify(&self, request: &Request, limits: &Limits) -> io::Result<Response>;
+}
+
+// A plugin that is an executable speaking the protocol over stdin and stdout
+pub struct Executable {
+    pub path: PathBuf,
+}
+
+impl ModeRunner for Executable {
+    fn verify(&self, request: &Request, limits: &Limits) -> io::Result<Response> {
+        let input = serde_json::to_vec(request)?;
+        let output = Command::new(&self.path).logged_output_with_input(limits, &input)?;
+        serde_json::from_slice(&output.stdout).map_err(|e| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            io::Error::other(format!(
+                "{} exited with {} without a valid response ({e}){}{}",
+                self.path.display(),
+                output.status,
+                if stderr.trim().is_empty() { "" } else { ":\n" },
+                stderr.trim_end()
+            ))
+        })
+    }
+}
+
+// What the executable of the plugin with the given name is called
+pub fn file_name(name: &str) -> String {
+    format!("{PREFIX}{name}{}", env::consts::EXE_SUFFIX)
+}
+
+// The executable of the plugin with the given name, from the plugins folder
+// or else from the PATH
+pub fn find(name: &str, plugins_dir: &Path) -> Option<Executable> {
+    let file = file_name(name);
+    let path_dirs: Vec<PathBuf> = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect())
+        .unwrap_or_default();
+    std::iter::once(plugins_dir.to_path_buf())
+        .chain(path_dirs)
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+        .map(|path| Executable { path })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    #[test]
+    fn test_request() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+[[exercises]]
+name = "miri1"
+path = "exercises/unsafe/miri1.rs"
+mode = "plugin"
+plugin = "miri"
+plugin_options = { flags = ["-Zmiri-strict-provenance"] }
+hint = ""
+"#,
+        )
+        .unwrap();
+        let args = ["--quiet".to_string()];
+        let request = Request::verify(&list.exercises[0], &args, false);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "protocol": PROTOCOL,
+                "action": "verify",
+                "exercise": {
+                    "name": "miri1",
+                    "path": "exercises/unsafe/miri1.rs",
+                    "files": [],
+                },
+                "args": ["--quiet"],
+                "options": { "flags": ["-Zmiri-strict-provenance"] },
+                "verbose": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_response() {
+        let response: Response = serde_json::from_str(r#"{"passed": true}"#).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                passed: true,
+                stdout: String::new(),
+                stderr: String::new(),
+            }
+        );
+        assert!(serde_json::from_str::<Response>(r#"{"stdout": "hi"}"#).is_err());
+    }
+}
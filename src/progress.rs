@@ -0,0 +1,362 @@
+use crate::exercise::Exercise;
+use crate::topics::by_topic;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PROGRESS_FILE_PATH: &str = ".rustlings-state.toml";
+
+// Held while the progress is saved
+static SAVING: Mutex<()> = Mutex::new(());
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// The persisted progress of the user.
+// This is stored next to info.toml so that completed exercises are
+// remembered even after an exercise has been reset.
+// Tables have to come after plain values to be serialized to TOML,
+// so maps are kept at the end.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Progress {
+    // Names of the exercises that have been verified successfully
+    #[serde(default)]
+    pub completed: BTreeSet<String>,
+    // The exercise watch mode was waiting on, to resume from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    // The days exercises were worked on, counted from the Unix epoch
+    #[serde(default)]
+    pub active_days: BTreeSet<u64>,
+    // The badges that have been awarded, by their ID
+    #[serde(default)]
+    pub badges: BTreeSet<String>,
+    // How many hint levels have been revealed for each exercise
+    #[serde(default)]
+    pub hints_seen: BTreeMap<String, usize>,
+    // How working on each exercise went
+    #[serde(default)]
+    pub stats: BTreeMap<String, ExerciseStats>,
+    // When each exercise was reviewed with `rustlings review`,
+    // in seconds since the Unix epoch
+    #[serde(default)]
+    pub reviews: BTreeMap<String, Vec<u64>>,
+}
+
+// Statistics about working on a single exercise
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ExerciseStats {
+    // When the exercise was first checked, in seconds since the Unix epoch
+    pub started_at: u64,
+    // When the exercise was completed, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    // How many times the exercise was checked
+    #[serde(default)]
+    pub attempts: u32,
+    // How many of those checks failed to compile or pass the tests
+    #[serde(default)]
+    pub failures: u32,
+    // Whether the last check failed
+    #[serde(default)]
+    pub failing: bool,
+    // How many hint levels had been revealed when the exercise was completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<usize>,
+}
+
+impl ExerciseStats {
+    // The seconds spent from the first check until the exercise was completed,
+    // or until now if it isn't completed yet
+    pub fn time_spent(&self) -> u64 {
+        self.finished_at
+            .unwrap_or_else(now)
+            .saturating_sub(self.started_at)
+    }
+}
+
+impl Progress {
+    // Load the progress from disk, starting from scratch if there is none yet
+    pub fn load() -> Progress {
+        if !Path::new(PROGRESS_FILE_PATH).exists() {
+            return Progress::default();
+        }
+        fs::read_to_string(PROGRESS_FILE_PATH)
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    // Write the progress back to disk. It's written to a temporary file
+    // first, so that it's never left half written.
+    pub fn save(&self) -> io::Result<()> {
+        let contents = toml::to_string(self).expect("Failed to serialize the progress to TOML");
+        let _saving = lock();
+        let temporary = format!("{PROGRESS_FILE_PATH}.tmp");
+        fs::write(&temporary, contents)?;
+        fs::rename(temporary, PROGRESS_FILE_PATH)
+    }
+
+    pub fn is_completed(&self, exercise: &Exercise) -> bool {
+        self.completed.contains(&exercise.name)
+    }
+
+    // Mark the exercise as completed, returning whether it wasn't already
+    pub fn complete(&mut self, exercise: &Exercise) -> bool {
+        let hint_level = self.hint_level(&exercise.name);
+        if let Some(stats) = self.stats.get_mut(&exercise.name) {
+            if stats.finished_at.is_none() {
+                stats.finished_at = Some(now());
+                stats.hints = Some(hint_level);
+            }
+        }
+        self.active_days.insert(today());
+        self.completed.insert(exercise.name.clone())
+    }
+
+    // Forget that the exercise was completed, returning whether it was
+    pub fn uncomplete(&mut self, exercise: &Exercise) -> bool {
+        self.completed.remove(&exercise.name)
+    }
+
+    // Count a check of an exercise that hasn't been completed yet
+    pub fn attempt(&mut self, exercise: &Exercise, failed: bool) {
+        let stats = self
+            .stats
+            .entry(exercise.name.clone())
+            .or_insert_with(|| ExerciseStats {
+                started_at: now(),
+                ..ExerciseStats::default()
+            });
+        stats.attempts += 1;
+        if failed {
+            stats.failures += 1;
+        }
+        stats.failing = failed;
+        self.active_days.insert(today());
+    }
+
+    // The number of hint levels that have been revealed for the exercise
+    pub fn hint_level(&self, name: &str) -> usize {
+        self.hints_seen.get(name).copied().unwrap_or(0)
+    }
+
+    // Remember that the hint levels up to `level` have been revealed,
+    // returning whether that wasn't the case already
+    pub fn see_hint_level(&mut self, name: &str, level: usize) -> bool {
+        if level <= self.hint_level(name) {
+            return false;
+        }
+        self.hints_seen.insert(name.to_string(), level);
+        true
+    }
+
+    // The number of hint levels that were used to complete the exercise,
+    // leaving out those revealed afterwards, or all that were revealed so far
+    // if it isn't completed yet, or was completed before that was recorded
+    pub fn hints_used(&self, name: &str) -> usize {
+        self.stats
+            .get(name)
+            .and_then(|stats| stats.hints)
+            .unwrap_or_else(|| self.hint_level(name))
+    }
+
+    // Add the progress made elsewhere, like on another machine. Nothing is
+    // lost either way: exercises completed in either are completed, and the
+    // statistics of an exercise are the ones of the more attempts.
+    pub fn merge(&mut self, other: Progress) {
+        self.completed.extend(other.completed);
+        if self.current.is_none() {
+            self.current = other.current;
+        }
+        self.active_days.extend(other.active_days);
+        self.badges.extend(other.badges);
+        for (name, level) in other.hints_seen {
+            let seen = self.hints_seen.entry(name).or_insert(0);
+            *seen = (*seen).max(level);
+        }
+        for (name, stats) in other.stats {
+            match self.stats.get(&name) {
+                Some(ours) if ours.attempts >= stats.attempts => {}
+                _ => {
+                    self.stats.insert(name, stats);
+                }
+            }
+        }
+        for (name, reviews) in other.reviews {
+            let ours = self.reviews.entry(name).or_default();
+            ours.extend(reviews);
+            ours.sort_unstable();
+            ours.dedup();
+        }
+    }
+
+    // Whether the exercise was completed without revealing any hints
+    pub fn completed_without_hints(&self, exercise: &Exercise) -> bool {
+        self.is_completed(exercise) && self.hints_used(&exercise.name) == 0
+    }
+}
+
+// Remember that the given exercise has been completed,
+// returning whether it wasn't already
+pub fn record_completion(exercise: &Exercise) -> bool {
+    let mut progress = Progress::load();
+    let newly_completed = progress.complete(exercise);
+    if newly_completed && progress.save().is_err() {
+        warn!("Failed to save your progress to {}", PROGRESS_FILE_PATH);
+    }
+    newly_completed
+}
+
+// Remember that the given exercise doesn't pass anymore,
+// returning whether it was completed before
+pub fn record_regression(exercise: &Exercise) -> bool {
+    let mut progress = Progress::load();
+    let was_completed = progress.uncomplete(exercise);
+    if was_completed && progress.save().is_err() {
+        warn!("Failed to save your progress to {}", PROGRESS_FILE_PATH);
+    }
+    was_completed
+}
+
+// Remember the exercise watch mode is waiting on, if any
+pub fn record_current(name: Option<&str>) {
+    let mut progress = Progress::load();
+    if progress.current.as_deref() == name {
+        return;
+    }
+    progress.current = name.map(String::from);
+    if progress.save().is_err() {
+        warn!("Failed to save your progress to {}", PROGRESS_FILE_PATH);
+    }
+}
+
+// Remember that the given exercise was checked, unless it was completed before
+pub fn record_attempt(exercise: &Exercise, failed: bool) {
+    let mut progress = Progress::load();
+    if progress.is_completed(exercise) {
+        return;
+    }
+    progress.attempt(exercise, failed);
+    if progress.save().is_err() {
+        warn!("Failed to save your progress to {}", PROGRESS_FILE_PATH);
+    }
+}
+
+// Remember that the given exercise was reviewed just now
+pub fn record_review(exercise: &Exercise) {
+    let mut progress = Progress::load();
+    progress
+        .reviews
+        .entry(exercise.name.clone())
+        .or_default()
+        .push(now());
+    if progress.save().is_err() {
+        warn!("Failed to save your progress to {}", PROGRESS_FILE_PATH);
+    }
+}
+
+// Keep the progress from being saved until the guard is dropped, waiting
+// until it's saved if it's being saved right now
+pub fn lock() -> MutexGuard<'static, ()> {
+    SAVING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// The current time in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// The current day, counted from the Unix epoch. Days start at midnight UTC.
+pub fn today() -> u64 {
+    now() / SECONDS_PER_DAY
+}
+
+// Print how many exercises have been completed, overall and per topic
+pub fn show(exercises: &[Exercise]) {
+    let progress = Progress::load();
+
+    let topics = by_topic(exercises);
+    println!("{:<17}\t{:>9}\t{:<7}", "Topic", "Completed", "Status");
+    for (topic, in_topic) in &topics {
+        let done = in_topic.iter().filter(|e| progress.is_completed(e)).count();
+        let total = in_topic.len();
+        let status = if done == total { "Done" } else { "Pending" };
+        println!("{topic:<17}\t{:>9}\t{status:<7}", format!("{done}/{total}"));
+    }
+
+    let exercises_done = exercises
+        .iter()
+        .filter(|e| progress.is_completed(e))
+        .count();
+    let topics_done = topics
+        .iter()
+        .filter(|(_, in_topic)| in_topic.iter().all(|e| progress.is_completed(e)))
+        .count();
+    let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+    println!();
+    println!(
+        "Progress: You completed {} / {} exercises ({:.1} %).",
+        exercises_done,
+        exercises.len(),
+        percentage_progress
+    );
+    println!(
+        "Topics: You completed {} / {} topics.",
+        topics_done,
+        topics.len()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut ours = Progress {
+            completed: BTreeSet::from(["intro1".to_string()]),
+            hints_seen: BTreeMap::from([("intro2".to_string(), 2)]),
+            stats: BTreeMap::from([(
+                "intro2".to_string(),
+                ExerciseStats {
+                    attempts: 3,
+                    ..ExerciseStats::default()
+                },
+            )]),
+            reviews: BTreeMap::from([("intro1".to_string(), vec![10, 20])]),
+            ..Progress::default()
+        };
+        let theirs = Progress {
+            completed: BTreeSet::from(["intro2".to_string()]),
+            current: Some("intro3".to_string()),
+            hints_seen: BTreeMap::from([("intro2".to_string(), 1), ("intro3".to_string(), 1)]),
+            stats: BTreeMap::from([(
+                "intro2".to_string(),
+                ExerciseStats {
+                    attempts: 5,
+                    finished_at: Some(100),
+                    ..ExerciseStats::default()
+                },
+            )]),
+            reviews: BTreeMap::from([("intro1".to_string(), vec![20, 30])]),
+            ..Progress::default()
+        };
+        ours.merge(theirs);
+        assert_eq!(ours.completed.len(), 2);
+        assert_eq!(ours.current.as_deref(), Some("intro3"));
+        assert_eq!(ours.hint_level("intro2"), 2);
+        assert_eq!(ours.hint_level("intro3"), 1);
+        assert_eq!(ours.stats["intro2"].attempts, 5);
+        assert_eq!(ours.stats["intro2"].finished_at, Some(100));
+        assert_eq!(ours.reviews["intro1"], [10, 20, 30]);
+    }
+}
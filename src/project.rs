@@ -1,24 +1,54 @@
+use crate::exercise::{Exercise, Mode};
+use crate::metadata::Metadata;
+use crate::output;
+use crate::registry::Registry;
 use glob::glob;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
 use std::error::Error;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
+/// Where `rustlings lsp` writes rust-project.json
+pub enum Destination {
+    File(PathBuf),
+    Stdout,
+}
+
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
 pub struct RustAnalyzerProject {
     sysroot_src: String,
     pub crates: Vec<Crate>,
+    /// The proc macro crates that weren't compiled yet, so that
+    /// rust-analyzer can't expand their macros
+    #[serde(skip)]
+    pub missing_proc_macros: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
     root_module: String,
     edition: String,
-    deps: Vec<String>,
+    deps: Vec<Dep>,
     cfg: Vec<String>,
+    #[serde(default)]
+    is_proc_macro: bool,
+    /// The compiled proc macro crate, which rust-analyzer loads to expand its macros
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proc_macro_dylib_path: Option<String>,
+}
+
+/// A dependency of a crate, referring to another entry of `crates` by index
+#[derive(Serialize, Deserialize)]
+pub struct Dep {
+    #[serde(rename = "crate")]
+    krate: usize,
+    name: String,
 }
 
 impl RustAnalyzerProject {
@@ -26,28 +56,101 @@ impl RustAnalyzerProject {
         RustAnalyzerProject {
             sysroot_src: String::new(),
             crates: Vec::new(),
+            missing_proc_macros: Vec::new(),
         }
     }
 
-    /// Write rust-project.json to disk
-    pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
-        std::fs::write(
-            "./rust-project.json",
-            serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
-        )?;
-        Ok(())
+    /// The contents of rust-project.json, pretty-printed
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self).expect("Failed to serialize to JSON")
+    }
+
+    /// Write rust-project.json to the given path, unless it's already there.
+    /// Returns whether it was written, since rewriting it unchanged would
+    /// still make rust-analyzer reload the project.
+    pub fn write_to_disk(&self, path: &Path) -> Result<bool, std::io::Error> {
+        let json = self.to_json() + "\n";
+        if fs::read_to_string(path).is_ok_and(|existing| existing == json) {
+            return Ok(false);
+        }
+        fs::write(path, json)?;
+        Ok(true)
+    }
+
+    /// Sort the crates by their root module, and the dependencies of every
+    /// crate by name, so that the file is the same on every filesystem
+    fn sort_crates(&mut self) {
+        let mut order: Vec<usize> = (0..self.crates.len()).collect();
+        order.sort_by(|&a, &b| self.crates[a].root_module.cmp(&self.crates[b].root_module));
+        let mut new_index = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
+
+        let mut crates: Vec<Option<Crate>> = self.crates.drain(..).map(Some).collect();
+        for old in order {
+            let mut krate = crates[old].take().expect("every crate is moved once");
+            for dep in &mut krate.deps {
+                dep.krate = new_index[dep.krate];
+            }
+            krate.deps.sort_by(|a, b| a.name.cmp(&b.name));
+            self.crates.push(krate);
+        }
+    }
+
+    /// Check that rust-analyzer can load the project, returning what's wrong
+    /// with it and how to fix it
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !Path::new(&self.sysroot_src).is_dir() {
+            problems.push(format!(
+                "The sources of the standard library aren't in {}, \
+                 install them with `rustup component add rust-src`",
+                self.sysroot_src
+            ));
+        }
+        for (index, krate) in self.crates.iter().enumerate() {
+            if !Path::new(&krate.root_module).is_file() {
+                problems.push(format!("{} doesn't exist", krate.root_module));
+            }
+            for dep in &krate.deps {
+                if dep.krate >= self.crates.len() || dep.krate == index {
+                    problems.push(format!(
+                        "{} depends on `{}` through crate {}, which doesn't exist",
+                        krate.root_module, dep.name, dep.krate
+                    ));
+                }
+            }
+            if let Some(dylib) = &krate.proc_macro_dylib_path {
+                if !Path::new(dylib).is_file() {
+                    problems.push(format!("The proc macro dylib {dylib} doesn't exist"));
+                }
+            }
+        }
+        problems
     }
 
     /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
+    /// depending on the given dependencies
+    fn path_to_json(
+        &mut self,
+        path: PathBuf,
+        edition: String,
+        deps: Vec<Dep>,
+    ) -> Result<(), Box<dyn Error>> {
         if let Some(ext) = path.extension() {
             if ext == "rs" {
+                // Absolute, since rust-analyzer resolves relative paths
+                // against the folder of rust-project.json, wherever that is
+                let path = fs::canonicalize(&path).unwrap_or(path);
                 self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
+                    root_module: json_path(&path),
+                    edition,
+                    deps,
                     // This allows rust_analyzer to work inside #[test] blocks
                     cfg: vec!["test".to_string()],
+                    is_proc_macro: false,
+                    proc_macro_dylib_path: None,
                 })
             }
         }
@@ -55,45 +158,589 @@ impl RustAnalyzerProject {
         Ok(())
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
+    /// Parse the given exercises folder for .rs files, any matches will create
     /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
+    /// treat it like a normal binary.
+    /// Directory exercises only get a crate for their entry file, and
+    /// excluded exercises and project exercises don't get one at all.
+    /// The `deps` declared for an exercise in info.toml are added as crates
+    /// as well, so that rust-analyzer can resolve them.
+    /// The crates, their editions, features and dependencies are taken from
+    /// `cargo metadata` on the Cargo workspace of `rustlings lsp --cargo`,
+    /// which runs offline, so that generating the file doesn't hang without
+    /// a network. If cargo can't resolve the dependencies with the crates it
+    /// downloaded, their versions and dependencies are taken from a
+    /// Cargo.lock instead, if there is one.
+    pub fn exercises_to_json(
+        &mut self,
+        exercises: &[Exercise],
+        excluded: &[Exercise],
+        exercises_dir: &Path,
+    ) -> Result<(), Box<dyn Error>> {
+        let metadata = CargoWorkspace::new(exercises)
+            .and_then(|workspace| workspace.write_to_disk())
+            .and_then(|manifest| Metadata::load(&manifest, true));
+        let metadata = match metadata {
+            Ok(metadata) => Some(metadata),
+            Err(e) => {
+                output::log(format!("cargo metadata failed, using the registry: {e}"));
+                None
+            }
+        };
+        let base = exercises_dir.parent().unwrap_or_else(|| Path::new(""));
+        let lockfile = Lockfile::find(base);
+        let registry = Registry::discover(cargo_manifests(base))?;
+        let mut dep_crates: HashMap<String, Option<usize>> = HashMap::new();
+        for path in glob(&glob_in(exercises_dir, "**/*"))? {
+            let path = path?;
+            if excluded.iter().any(|e| e.contains(&path)) {
+                continue;
+            }
+            let mut deps = Vec::new();
+            let exercise = exercises.iter().find(|e| e.contains(&path));
+            // The other files of directory exercises are modules of their entry
+            // file, and project exercises are packages that rust-analyzer can
+            // open with their own Cargo.toml
+            if exercise.is_some_and(|e| !path.ends_with(&e.path) || e.mode == Mode::Project) {
+                continue;
+            }
+            for name in exercise.iter().flat_map(|e| &e.deps) {
+                let crate_name = name.replace('-', "_");
+                let index = match &metadata {
+                    Some(metadata) => {
+                        self.exercise_dep_to_json(&crate_name, metadata, &mut dep_crates)
+                    }
+                    None => self.dep_to_json(name, None, &lockfile, &registry, &mut dep_crates)?,
+                };
+                if let Some(index) = index {
+                    deps.push(Dep {
+                        krate: index,
+                        name: crate_name,
+                    });
+                }
+            }
+            let edition = metadata
+                .as_ref()
+                .and_then(|metadata| exercise_edition(metadata, &path))
+                .unwrap_or_else(|| "2021".to_string());
+            self.path_to_json(path, edition, deps)?;
         }
+        self.sort_crates();
         Ok(())
     }
 
-    /// Use `rustc` to determine the default toolchain
-    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
+    /// Add a crate for the dependency of the exercises with the given name,
+    /// as it was resolved by cargo, along with crates for its own dependencies.
+    /// Returns the index of the crate, if cargo resolved it.
+    /// `added` holds the indices of the crates added so far, by package ID.
+    fn exercise_dep_to_json(
+        &mut self,
+        name: &str,
+        metadata: &Metadata,
+        added: &mut HashMap<String, Option<usize>>,
+    ) -> Option<usize> {
+        let root = metadata.node(metadata.resolve.as_ref()?.root.as_deref()?)?;
+        let Some(dep) = root.deps.iter().find(|dep| dep.name == name) else {
+            eprintln!("Couldn't find the sources of `{name}`, try running `cargo fetch`");
+            return None;
+        };
+        self.package_to_json(&dep.pkg, metadata, added)
+    }
+
+    /// Add a crate for the library of the package with the given ID,
+    /// along with crates for its own dependencies
+    fn package_to_json(
+        &mut self,
+        id: &str,
+        metadata: &Metadata,
+        added: &mut HashMap<String, Option<usize>>,
+    ) -> Option<usize> {
+        if let Some(index) = added.get(id) {
+            return *index;
+        }
+        // Guards against cycles, which cargo doesn't resolve anyway
+        added.insert(id.to_string(), None);
+
+        let package = metadata.package(id)?;
+        let lib = package.lib()?;
+        let node = metadata.node(id)?;
+        let mut deps = Vec::new();
+        for dep in node.deps.iter().filter(|dep| dep.is_normal()) {
+            if let Some(index) = self.package_to_json(&dep.pkg, metadata, added) {
+                deps.push(Dep {
+                    krate: index,
+                    name: dep.name.clone(),
+                });
+            }
+        }
+
+        let proc_macro_dylib_path = match &metadata.target_directory {
+            Some(target_dir) if lib.is_proc_macro() => {
+                self.proc_macro_dylib(target_dir, &package.name)
+            }
+            _ => None,
+        };
+        self.crates.push(Crate {
+            root_module: json_path(&lib.src_path),
+            edition: lib.edition.clone(),
+            deps,
+            cfg: node
+                .features
+                .iter()
+                .map(|feature| format!("feature=\"{feature}\""))
+                .collect(),
+            is_proc_macro: lib.is_proc_macro(),
+            proc_macro_dylib_path,
+        });
+        let index = self.crates.len() - 1;
+        added.insert(id.to_string(), Some(index));
+        Some(index)
+    }
+
+    /// Add a crate for the dependency with the given name, using its sources
+    /// from the cargo registry, along with crates for its own dependencies.
+    /// The version is the one in the Cargo.lock, or the newest one there is.
+    /// Returns the index of the crate, if the sources could be found.
+    /// `added` holds the indices of the crates added so far, by name and version.
+    fn dep_to_json(
+        &mut self,
+        name: &str,
+        version: Option<&str>,
+        lockfile: &Lockfile,
+        registry: &Registry,
+        added: &mut HashMap<String, Option<usize>>,
+    ) -> Result<Option<usize>, Box<dyn Error>> {
+        let key = format!("{name} {}", version.unwrap_or("*"));
+        if let Some(index) = added.get(&key) {
+            return Ok(*index);
+        }
+        // Guards against cycles, which a valid Cargo.lock doesn't have anyway
+        added.insert(key.clone(), None);
+
+        let locked = lockfile.package(name, version);
+        let source = registry.find(name, locked.map(|locked| locked.version.as_str()));
+        let Some(source) = source else {
+            eprintln!("Couldn't find the sources of `{name}`, try running `cargo fetch`");
+            return Ok(None);
+        };
+
+        let mut deps = Vec::new();
+        for dependency in locked.iter().flat_map(|locked| &locked.dependencies) {
+            let (dep_name, dep_version) = parse_lock_dependency(dependency);
+            if let Some(index) =
+                self.dep_to_json(dep_name, dep_version, lockfile, registry, added)?
+            {
+                deps.push(Dep {
+                    krate: index,
+                    name: dep_name.replace('-', "_"),
+                });
+            }
+        }
+
+        let manifest = crate_manifest(&source);
+        let is_proc_macro = manifest_is_proc_macro(manifest.as_ref());
+        let proc_macro_dylib_path = if is_proc_macro {
+            let target_dir = Path::new(CARGO_WORKSPACE_DIR).join("target");
+            self.proc_macro_dylib(&target_dir, name)
+        } else {
+            None
+        };
+        self.crates.push(Crate {
+            root_module: json_path(&source.join(manifest_lib_path(manifest.as_ref()))),
+            edition: manifest_edition(manifest.as_ref()),
+            deps,
+            cfg: Vec::new(),
+            is_proc_macro,
+            proc_macro_dylib_path,
+        });
+        let index = self.crates.len() - 1;
+        added.insert(key, Some(index));
+        Ok(Some(index))
+    }
+
+    /// The path of the newest dylib cargo compiled for the proc macro package
+    /// with the given name into the given target directory, if there is one
+    fn proc_macro_dylib(&mut self, target_dir: &Path, package: &str) -> Option<String> {
+        let name = package.replace('-', "_");
+        let deps = target_dir.join("debug").join("deps");
+        let newest = fs::read_dir(deps)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|file_name| is_proc_macro_dylib(file_name, &name))
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .max_by_key(|(modified, _)| *modified);
+        match newest {
+            Some((_, path)) => Some(json_path(&fs::canonicalize(&path).unwrap_or(path))),
+            None => {
+                self.missing_proc_macros.push(package.to_string());
+                None
+            }
+        }
+    }
+
+    /// Use `rustc` to determine the sysroot of the given rustup toolchain,
+    /// or of the one rustup picks by default, which respects `RUSTUP_TOOLCHAIN`
+    pub fn get_sysroot_src(&mut self, toolchain: Option<&str>) -> Result<(), Box<dyn Error>> {
         // check if RUST_SRC_PATH is set
         if let Ok(path) = env::var("RUST_SRC_PATH") {
             self.sysroot_src = path;
             return Ok(());
         }
 
-        let toolchain = Command::new("rustc")
-            .arg("--print")
-            .arg("sysroot")
-            .output()?
-            .stdout;
+        let mut rustc = Command::new("rustc");
+        if let Some(toolchain) = toolchain {
+            rustc.env("RUSTUP_TOOLCHAIN", toolchain);
+        }
+        let toolchain = rustc.arg("--print").arg("sysroot").output()?.stdout;
 
+        // Only trim the output, since the path may contain spaces,
+        // like in Windows user directories
         let toolchain = String::from_utf8_lossy(&toolchain);
-        let mut whitespace_iter = toolchain.split_whitespace();
+        let toolchain = toolchain.trim();
 
-        let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
+        // Not on stdout, which `rustlings lsp --stdout` prints the JSON to
+        eprintln!("Determined toolchain: {}\n", &toolchain);
 
-        println!("Determined toolchain: {}\n", &toolchain);
-
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
-            .join("lib")
-            .join("rustlib")
-            .join("src")
-            .join("rust")
-            .join("library")
-            .to_string_lossy())
-        .to_string();
+        self.sysroot_src = json_path(
+            &Path::new(toolchain)
+                .join("lib")
+                .join("rustlib")
+                .join("src")
+                .join("rust")
+                .join("library"),
+        );
         Ok(())
     }
 }
+
+/// The folder of the synthetic Cargo workspace generated by `rustlings lsp --cargo`
+pub const CARGO_WORKSPACE_DIR: &str = ".rustlings-cargo";
+
+/// A Cargo manifest with a target for every exercise, as an alternative to
+/// rust-project.json. rust-analyzer then gets the dependencies from cargo
+/// itself, which resolves their features and proc macros properly.
+#[derive(Serialize)]
+pub struct CargoWorkspace {
+    package: Package,
+    // Keeps the manifest out of any workspace in a parent folder
+    workspace: BTreeMap<String, String>,
+    dependencies: BTreeMap<String, String>,
+    // An empty list would be a value after tables, which TOML can't have
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    bin: Vec<Target>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    test: Vec<Target>,
+}
+
+#[derive(Serialize)]
+struct Package {
+    name: String,
+    version: String,
+    edition: String,
+    publish: bool,
+}
+
+/// A target compiling a single exercise
+#[derive(Serialize)]
+struct Target {
+    name: String,
+    path: String,
+}
+
+impl CargoWorkspace {
+    /// Test and doctest exercises become test targets, since they don't need
+    /// a `main` function, and all other exercises become binaries, except
+    /// for project exercises, which are packages of their own.
+    /// The `deps` of all exercises are added to the dependencies, using the
+    /// newest version in the cargo registry if there is one.
+    pub fn new(exercises: &[Exercise]) -> Result<CargoWorkspace, Box<dyn Error>> {
+        let mut workspace = CargoWorkspace {
+            package: Package {
+                name: "rustlings-exercises".to_string(),
+                version: "0.0.0".to_string(),
+                edition: "2021".to_string(),
+                publish: false,
+            },
+            workspace: BTreeMap::new(),
+            dependencies: BTreeMap::new(),
+            bin: Vec::new(),
+            test: Vec::new(),
+        };
+        // Asking `cargo metadata` about this workspace would be going in circles
+        let registry = Registry::discover(Vec::new())?;
+        for exercise in exercises {
+            let target = Target {
+                name: exercise.name.clone(),
+                path: json_path(&fs::canonicalize(&exercise.path)?),
+            };
+            match exercise.mode {
+                Mode::Test | Mode::Property | Mode::Doctest => workspace.test.push(target),
+                Mode::Compile | Mode::Clippy | Mode::Bench | Mode::CompileFail | Mode::Fmt => {
+                    workspace.bin.push(target)
+                }
+                Mode::Project | Mode::Plugin => continue,
+            }
+            for name in &exercise.deps {
+                if workspace.dependencies.contains_key(name) {
+                    continue;
+                }
+                let version = registry
+                    .find(name, None)
+                    .and_then(|source| {
+                        let file_name = source.file_name()?.to_str()?;
+                        Some(file_name.strip_prefix(name)?.strip_prefix('-')?.to_string())
+                    })
+                    .unwrap_or_else(|| "*".to_string());
+                workspace.dependencies.insert(name.clone(), version);
+            }
+        }
+        Ok(workspace)
+    }
+
+    /// Write the manifest into `CARGO_WORKSPACE_DIR`, returning its path
+    pub fn write_to_disk(&self) -> Result<PathBuf, Box<dyn Error>> {
+        let dir = Path::new(CARGO_WORKSPACE_DIR);
+        fs::create_dir_all(dir)?;
+        let manifest = dir.join("Cargo.toml");
+        fs::write(&manifest, toml::to_string(self)?)?;
+        Ok(manifest)
+    }
+}
+
+/// The packages of a Cargo.lock
+#[derive(Deserialize, Default)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    /// Written as `name`, or as `name version` when several versions are locked
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    /// The packages locked by the Cargo workspace generated by `rustlings lsp --cargo`,
+    /// which locks the dependencies of exercises, followed by the ones locked by
+    /// rustlings itself. Packages in neither use the newest version in the registry.
+    fn find(base: &Path) -> Lockfile {
+        let package = cargo_manifests(base)
+            .iter()
+            .map(|manifest| manifest.with_file_name("Cargo.lock"))
+            .filter_map(|path| toml::from_str::<Lockfile>(&fs::read_to_string(path).ok()?).ok())
+            .flat_map(|lockfile| lockfile.package)
+            .collect();
+        Lockfile { package }
+    }
+
+    /// The locked package with the given name, and version if given
+    fn package(&self, name: &str, version: Option<&str>) -> Option<&LockedPackage> {
+        self.package
+            .iter()
+            .find(|p| p.name == name && version.is_none_or(|v| p.version == v))
+    }
+}
+
+/// Split a dependency of a locked package into its name and version, if any
+fn parse_lock_dependency(dependency: &str) -> (&str, Option<&str>) {
+    let mut parts = dependency.split_whitespace();
+    let name = parts.next().unwrap_or(dependency);
+    (name, parts.next())
+}
+
+/// The manifests of the Cargo workspace generated by `rustlings lsp --cargo`,
+/// and of rustlings itself, which dependencies are resolved against
+pub fn cargo_manifests(base: &Path) -> Vec<PathBuf> {
+    vec![
+        Path::new(CARGO_WORKSPACE_DIR).join("Cargo.toml"),
+        base.join("Cargo.toml"),
+    ]
+}
+
+/// A glob pattern matching `pattern` inside of `dir`.
+/// The directory is escaped, since it may contain characters that have a
+/// special meaning in patterns. `/` separates paths on every platform.
+fn glob_in(dir: &Path, pattern: &str) -> String {
+    format!(
+        "{}/{pattern}",
+        glob::Pattern::escape(&dir.to_string_lossy())
+    )
+}
+
+/// Convert a path to a string rust-analyzer understands on every platform,
+/// without `.` components or the `\\?\` prefix of canonicalized Windows paths
+fn json_path(path: &Path) -> String {
+    let path: PathBuf = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    let path = path.display().to_string();
+    match path.strip_prefix(r"\\?\") {
+        Some(path) => path.to_string(),
+        None => path,
+    }
+}
+
+/// Read the Cargo.toml of a crate
+fn crate_manifest(source: &Path) -> Option<toml::Value> {
+    fs::read_to_string(source.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// The edition of the target cargo compiles the exercise at the given path with
+fn exercise_edition(metadata: &Metadata, path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    metadata
+        .packages
+        .iter()
+        .flat_map(|package| &package.targets)
+        .find(|target| fs::canonicalize(&target.src_path).is_ok_and(|src| src == path))
+        .map(|target| target.edition.clone())
+}
+
+/// The edition of a crate, defaulting to 2015 like cargo
+fn manifest_edition(manifest: Option<&toml::Value>) -> String {
+    manifest
+        .and_then(|manifest| manifest.get("package")?.get("edition")?.as_str())
+        .unwrap_or("2015")
+        .to_string()
+}
+
+/// Whether a crate's library is a proc macro crate
+fn manifest_is_proc_macro(manifest: Option<&toml::Value>) -> bool {
+    let lib = manifest.and_then(|manifest| manifest.get("lib"));
+    ["proc-macro", "proc_macro"]
+        .iter()
+        .any(|key| lib.and_then(|lib| lib.get(key)?.as_bool()) == Some(true))
+}
+
+/// Whether the file is a dylib cargo compiled for the proc macro crate
+/// with the given name, like `libserde_derive-0123456789abcdef.so`
+fn is_proc_macro_dylib(file_name: &str, name: &str) -> bool {
+    file_name
+        .strip_prefix(DLL_PREFIX)
+        .and_then(|f| f.strip_prefix(name))
+        .and_then(|f| f.strip_prefix('-'))
+        .and_then(|f| f.strip_suffix(DLL_SUFFIX))
+        .is_some_and(|hash| hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// The root module of a crate's library, `src/lib.rs` unless configured otherwise
+fn manifest_lib_path(manifest: Option<&toml::Value>) -> PathBuf {
+    manifest
+        .and_then(|manifest| manifest.get("lib")?.get("path")?.as_str())
+        .map_or_else(|| Path::new("src").join("lib.rs"), PathBuf::from)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json_path() {
+        assert_eq!(
+            json_path(Path::new("./exercises/intro/intro1.rs")),
+            Path::new("exercises/intro/intro1.rs").display().to_string()
+        );
+        assert_eq!(json_path(Path::new(r"\\?\C:\rustlings")), r"C:\rustlings");
+    }
+
+    #[test]
+    fn test_sort_crates() {
+        let mut project = RustAnalyzerProject::new();
+        for (path, deps) in [("b.rs", vec![2, 1]), ("c.rs", vec![]), ("a.rs", vec![1])] {
+            let deps = deps
+                .into_iter()
+                .map(|krate| Dep {
+                    krate,
+                    name: format!("dep{krate}"),
+                })
+                .collect();
+            project
+                .path_to_json(PathBuf::from(path), "2021".to_string(), deps)
+                .unwrap();
+        }
+        project.sort_crates();
+        let roots: Vec<&str> = project
+            .crates
+            .iter()
+            .map(|krate| krate.root_module.as_str())
+            .collect();
+        assert_eq!(roots, ["a.rs", "b.rs", "c.rs"]);
+        // The dependencies still refer to the same crates
+        let deps: Vec<usize> = project.crates[1].deps.iter().map(|d| d.krate).collect();
+        assert_eq!(deps, [2, 0]);
+        assert_eq!(project.crates[0].deps[0].krate, 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut project = RustAnalyzerProject::new();
+        project.sysroot_src = "does/not/exist".to_string();
+        project
+            .path_to_json(PathBuf::from("src/main.rs"), "2021".to_string(), Vec::new())
+            .unwrap();
+        project
+            .path_to_json(
+                PathBuf::from("src/missing.rs"),
+                "2021".to_string(),
+                vec![Dep {
+                    krate: 2,
+                    name: "rand".to_string(),
+                }],
+            )
+            .unwrap();
+        let problems = project.validate();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("rustup component add rust-src"));
+        assert!(problems[1].contains("src/missing.rs"));
+        assert!(problems[2].contains("`rand` through crate 2"));
+    }
+
+    #[test]
+    fn test_is_proc_macro_dylib() {
+        let dylib = format!("{DLL_PREFIX}serde_derive-0123456789abcdef{DLL_SUFFIX}");
+        assert!(is_proc_macro_dylib(&dylib, "serde_derive"));
+        assert!(!is_proc_macro_dylib(&dylib, "serde"));
+        let rlib = "libserde_derive-0123456789abcdef.rlib";
+        assert!(!is_proc_macro_dylib(rlib, "serde_derive"));
+    }
+
+    #[test]
+    fn test_locked_dependencies() {
+        let lockfile: Lockfile = toml::from_str(
+            r#"
+            [[package]]
+            name = "rand"
+            version = "0.8.5"
+            dependencies = ["libc", "rand_core 0.6.4"]
+
+            [[package]]
+            name = "rand_core"
+            version = "0.6.4"
+
+            [[package]]
+            name = "rand_core"
+            version = "0.9.0"
+            "#,
+        )
+        .unwrap();
+        let rand = lockfile.package("rand", None).unwrap();
+        assert_eq!(parse_lock_dependency(&rand.dependencies[0]), ("libc", None));
+        let (name, version) = parse_lock_dependency(&rand.dependencies[1]);
+        assert_eq!((name, version), ("rand_core", Some("0.6.4")));
+        assert_eq!(lockfile.package(name, version).unwrap().version, "0.6.4");
+    }
+}
@@ -0,0 +1,49 @@
+use crate::exercise::{COMPILE_CACHE_DIR, RUSTC_EDITION_ARGS};
+use crate::output::LoggedCommand;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The property testing harness property exercises are linked against
+pub const HARNESS: &str = include_str!("../harness/property.rs");
+// The name exercises use the harness by, as in `use rustlings_property::check;`
+pub const CRATE_NAME: &str = "rustlings_property";
+
+// The path of the harness compiled as a library by the given rustc command,
+// which is compiled first unless it was already. Returns the errors of rustc
+// if that failed.
+pub fn harness(mut rustc: Command) -> Result<PathBuf, String> {
+    // The library only works with the toolchain it was compiled by
+    let mut hasher = DefaultHasher::new();
+    HARNESS.hash(&mut hasher);
+    rustc
+        .get_envs()
+        .for_each(|(key, value)| (key, value).hash(&mut hasher));
+    let dir = Path::new(COMPILE_CACHE_DIR).join("property");
+    let library = dir.join(format!("lib{CRATE_NAME}-{:016x}.rlib", hasher.finish()));
+    if library.exists() {
+        return Ok(library);
+    }
+
+    let source = dir.join("property.rs");
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::write(&source, HARNESS))
+        .map_err(|e| format!("Failed to write the property testing harness: {e}"))?;
+    let output = rustc
+        .args(["--crate-type=rlib", "--crate-name", CRATE_NAME])
+        .args(RUSTC_EDITION_ARGS)
+        .arg(&source)
+        .arg("-o")
+        .arg(&library)
+        .logged_output()
+        .map_err(|e| format!("Failed to compile the property testing harness: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to compile the property testing harness:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(library)
+}
@@ -0,0 +1,167 @@
+use crate::metadata::{Metadata, Package};
+use std::cell::OnceCell;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Finds the sources of crates that cargo downloaded, so that rust-analyzer
+/// can be pointed at them.
+/// Cargo unpacks crates into `$CARGO_HOME/registry/src/<registry>-<hash>`,
+/// where the registry is `index.crates.io` since the sparse protocol became
+/// the default, and `github.com` before that. Crates found in neither, like
+/// vendored or git dependencies, are looked up with `cargo metadata`.
+pub struct Registry {
+    /// The source directories of all registries, crates.io ones first
+    source_dirs: Vec<PathBuf>,
+    /// The manifests `cargo metadata` is asked about, in order
+    manifests: Vec<PathBuf>,
+    metadata: OnceCell<Vec<Package>>,
+}
+
+impl Registry {
+    /// Find the registries in the cargo home, which respects `CARGO_HOME`.
+    /// The manifests that exist are used for `cargo metadata`.
+    pub fn discover(manifests: Vec<PathBuf>) -> Result<Registry, Box<dyn Error>> {
+        // The home crate reads CARGO_HOME, and finds the home directory on Windows too
+        let src = home::cargo_home()?.join("registry").join("src");
+        let mut source_dirs: Vec<PathBuf> = match fs::read_dir(&src) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect(),
+            // Nothing was downloaded yet
+            Err(_) => Vec::new(),
+        };
+        source_dirs.sort_by_key(|dir| (registry_rank(dir), dir.clone()));
+        Ok(Registry {
+            source_dirs,
+            manifests: manifests.into_iter().filter(|m| m.exists()).collect(),
+            metadata: OnceCell::new(),
+        })
+    }
+
+    /// The sources of the crate with the given name, in the given version,
+    /// or in the newest version there is
+    pub fn find(&self, name: &str, version: Option<&str>) -> Option<PathBuf> {
+        self.find_in_registries(name, version)
+            .or_else(|| self.find_in_metadata(name, version))
+    }
+
+    fn find_in_registries(&self, name: &str, version: Option<&str>) -> Option<PathBuf> {
+        let mut newest: Option<(Vec<u64>, PathBuf)> = None;
+        for dir in &self.source_dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+                let Some(found) = path
+                    .file_name()
+                    .and_then(|f| f.to_str())
+                    .and_then(|f| f.strip_prefix(name))
+                    .and_then(|f| f.strip_prefix('-'))
+                else {
+                    continue;
+                };
+                if version == Some(found) {
+                    return Some(path);
+                }
+                // Another crate sharing the same prefix, like `tokio-macros`,
+                // doesn't have a version after it
+                let Some(found) = parse_version(found) else {
+                    continue;
+                };
+                // Earlier registries win if they have the same version
+                if version.is_none() && newest.as_ref().is_none_or(|(newest, _)| found > *newest) {
+                    newest = Some((found, path));
+                }
+            }
+        }
+        newest.map(|(_, path)| path)
+    }
+
+    fn find_in_metadata(&self, name: &str, version: Option<&str>) -> Option<PathBuf> {
+        let packages = self.metadata.get_or_init(|| {
+            self.manifests
+                .iter()
+                // Offline, so that only crates cargo already downloaded are found
+                .filter_map(|manifest| Metadata::load(manifest, true).ok())
+                .flat_map(|metadata| metadata.packages)
+                .collect()
+        });
+        packages
+            .iter()
+            .filter(|p| p.name == name && version.is_none_or(|v| p.version == v))
+            .filter_map(|p| Some((parse_version(&p.version)?, p)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .and_then(|(_, p)| Some(p.manifest_path.parent()?.to_path_buf()))
+    }
+}
+
+/// The order registries are searched in: crates.io with the sparse protocol,
+/// then with the git index, then any other registry
+fn registry_rank(dir: &Path) -> u8 {
+    let name = dir.file_name().and_then(|f| f.to_str()).unwrap_or("");
+    if name.starts_with("index.crates.io-") {
+        0
+    } else if name.starts_with("github.com-") {
+        1
+    } else {
+        2
+    }
+}
+
+/// Parse the numeric parts of a version like `1.28.1`
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let release = version.split(['-', '+']).next()?;
+    release.split('.').map(|part| part.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.28.1"), Some(vec![1, 28, 1]));
+        assert_eq!(parse_version("1.0.0-alpha.1"), Some(vec![1, 0, 0]));
+        assert_eq!(parse_version("macros-2.1.0"), None);
+    }
+
+    #[test]
+    fn test_registry_rank() {
+        let mut dirs = [
+            PathBuf::from("src/my-registry-0123456789abcdef"),
+            PathBuf::from("src/github.com-1ecc6299db9ec823"),
+            PathBuf::from("src/index.crates.io-6f17d22bba15001f"),
+        ];
+        dirs.sort_by_key(|dir| registry_rank(dir));
+        assert!(dirs[0].ends_with("index.crates.io-6f17d22bba15001f"));
+        assert!(dirs[1].ends_with("github.com-1ecc6299db9ec823"));
+    }
+
+    #[test]
+    fn test_metadata_fallback() {
+        let json = br#"{
+            "packages": [
+                {"id": "rand 0.8.5", "name": "rand", "version": "0.8.5", "manifest_path": "/vendor/rand-0.8.5/Cargo.toml", "source": null},
+                {"id": "rand 0.9.0", "name": "rand", "version": "0.9.0", "manifest_path": "/vendor/rand/Cargo.toml", "source": null}
+            ],
+            "workspace_members": []
+        }"#;
+        let registry = Registry {
+            source_dirs: Vec::new(),
+            manifests: Vec::new(),
+            metadata: OnceCell::from(Metadata::parse(json).unwrap().packages),
+        };
+        assert_eq!(
+            registry.find("rand", None),
+            Some(PathBuf::from("/vendor/rand"))
+        );
+        assert_eq!(
+            registry.find("rand", Some("0.8.5")),
+            Some(PathBuf::from("/vendor/rand-0.8.5"))
+        );
+        assert_eq!(registry.find("rand_core", None), None);
+    }
+}
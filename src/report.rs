@@ -0,0 +1,205 @@
+use crate::exercise::{Difficulty, Exercise, ExerciseOutput, Mode};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// The format in which results are printed
+#[derive(PartialEq, Debug)]
+pub enum Format {
+    // Human readable, colored text
+    Text,
+    // Machine readable JSON, for editors, grading scripts and CI pipelines
+    Json,
+}
+
+pub fn parse_format(value: &str) -> Result<Format, String> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(format!(
+            "unknown format `{value}`, expected `text` or `json`"
+        )),
+    }
+}
+
+// The status of an exercise in a report
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    // The exercise passes and its `I AM NOT DONE` marker was removed
+    Done,
+    // The exercise still has its `I AM NOT DONE` marker
+    Pending,
+    // The exercise compiled and ran successfully
+    Passed,
+    // The exercise failed to compile, run or pass its tests
+    Failed,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let status = match self {
+            Status::Done => "done",
+            Status::Pending => "pending",
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+        };
+        write!(f, "{status}")
+    }
+}
+
+// A machine readable report about a single exercise
+#[derive(Serialize, Debug)]
+pub struct Report<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    pub topic: String,
+    pub mode: Mode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    pub tags: &'a [String],
+    pub status: Status,
+    // The output of the compiler or of the exercise, without colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+impl<'a> Report<'a> {
+    // A report that only contains whether the exercise looks done
+    pub fn state(exercise: &'a Exercise) -> Report<'a> {
+        let status = if exercise.looks_done() {
+            Status::Done
+        } else {
+            Status::Pending
+        };
+        Report::new(exercise, status)
+    }
+
+    // Compile and run the exercise, reporting whether it passed along with its output.
+    // Interactive exercises would wait for input, so they are only compiled.
+    pub fn run(exercise: &'a Exercise) -> Report<'a> {
+        let (status, output) = match exercise.compile() {
+            Ok(_) if exercise.interactive => (
+                Status::Passed,
+                ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                },
+            ),
+            Ok(compilation) => match compilation.run() {
+                Ok(output) => (Status::Passed, output),
+                Err(output) => (Status::Failed, output),
+            },
+            Err(output) => (Status::Failed, output),
+        };
+        Report {
+            stdout: Some(console::strip_ansi_codes(&output.stdout).to_string()),
+            stderr: Some(console::strip_ansi_codes(&output.stderr).to_string()),
+            ..Report::new(exercise, status)
+        }
+    }
+
+    // Like `run`, but an exercise that passes is only done once its marker was
+    // removed, or however else exercises are told to be done
+    pub fn verify(exercise: &'a Exercise) -> Report<'a> {
+        let mut report = Report::run(exercise);
+        if report.status == Status::Passed {
+            report.status = if exercise.done_if_passing() {
+                Status::Done
+            } else {
+                Status::Pending
+            };
+        }
+        report
+    }
+
+    fn new(exercise: &'a Exercise, status: Status) -> Report<'a> {
+        Report {
+            name: &exercise.name,
+            path: &exercise.path,
+            topic: exercise.topic(),
+            mode: exercise.mode,
+            difficulty: exercise.difficulty,
+            tags: &exercise.tags,
+            status,
+            stdout: None,
+            stderr: None,
+        }
+    }
+}
+
+// Print the given reports as JSON
+pub fn print_json<T: Serialize>(reports: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(reports).expect("Failed to serialize the report to JSON")
+    );
+}
+
+// Write the given reports as a JUnit XML file, which CI services can display.
+// Every exercise is a test case, grouped into classes by topic.
+pub fn write_junit(reports: &[Report], path: &Path) -> io::Result<()> {
+    let failures = reports.iter().filter(|r| r.status != Status::Done).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"rustlings\" tests=\"{}\" failures=\"{failures}\">\n",
+        reports.len()
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"rustlings\" tests=\"{}\" failures=\"{failures}\">\n",
+        reports.len()
+    ));
+    for report in reports {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\"",
+            escape_xml(report.name),
+            escape_xml(&report.topic),
+            escape_xml(&report.path.display().to_string())
+        ));
+        let message = match report.status {
+            Status::Done | Status::Passed => {
+                xml.push_str("/>\n");
+                continue;
+            }
+            Status::Pending => "The exercise still contains the `I AM NOT DONE` comment",
+            Status::Failed => "The exercise failed to compile, run or pass its tests",
+        };
+        let output = [&report.stdout, &report.stderr]
+            .into_iter()
+            .flatten()
+            .map(|output| output.trim())
+            .filter(|output| !output.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        xml.push_str(&format!(
+            ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+            escape_xml(message),
+            escape_xml(&output)
+        ));
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    fs::write(path, xml)
+}
+
+// Escape text for XML, dropping the control characters it doesn't allow
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
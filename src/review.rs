@@ -0,0 +1,178 @@
+// `rustlings review` brings back completed exercises in their original,
+// unsolved form, so that what they teach sticks. Every exercise is reviewed
+// at increasing intervals: the better it's known, the longer it rests.
+// Reviews happen on copies in REVIEW_DIR, so the solutions stay untouched.
+use crate::exercise::Exercise;
+use crate::output::symbol;
+use crate::progress::{now, record_review, Progress, SECONDS_PER_DAY};
+use crate::run::copy_original;
+use crate::theme;
+use crate::verify::verify;
+use console::style;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+// Where the copies of the exercises under review are kept
+pub const REVIEW_DIR: &str = ".rustlings-review";
+
+// How many days an exercise rests after it was completed, and after every
+// review since. Exercises reviewed more often than this rest for the longest.
+const INTERVALS: &[u64] = &[1, 3, 7, 14, 30, 60, 120];
+
+// When the exercise is due for review next, in seconds since the Unix epoch,
+// or `None` if it hasn't been completed yet
+pub fn due_at(exercise: &Exercise, progress: &Progress) -> Option<u64> {
+    if !progress.is_completed(exercise) {
+        return None;
+    }
+    let reviews = progress.reviews.get(&exercise.name);
+    let last = reviews
+        .and_then(|reviews| reviews.last().copied())
+        .or_else(|| progress.stats.get(&exercise.name)?.finished_at)
+        // Exercises completed before their completion time was tracked
+        .unwrap_or(0);
+    let count = reviews.map_or(0, Vec::len);
+    let interval = INTERVALS[count.min(INTERVALS.len() - 1)];
+    Some(last + interval * SECONDS_PER_DAY)
+}
+
+// The exercises that are due for review, the longest overdue first
+fn due<'a>(exercises: &'a [Exercise], progress: &Progress) -> Vec<&'a Exercise> {
+    let now = now();
+    let mut due: Vec<(u64, &Exercise)> = exercises
+        .iter()
+        .filter_map(|e| Some((due_at(e, progress)?, e)))
+        .filter(|(due_at, _)| *due_at <= now)
+        .collect();
+    due.sort_by_key(|(due_at, _)| *due_at);
+    due.into_iter().map(|(_, e)| e).collect()
+}
+
+// Print when every completed exercise is due for review
+pub fn list(exercises: &[Exercise]) {
+    let progress = Progress::load();
+    let now = now();
+    let mut scheduled: Vec<(u64, &Exercise)> = exercises
+        .iter()
+        .filter_map(|e| Some((due_at(e, &progress)?, e)))
+        .collect();
+    if scheduled.is_empty() {
+        println!("Complete some exercises first, then come back to review them.");
+        return;
+    }
+    scheduled.sort_by_key(|(due_at, _)| *due_at);
+    for (due_at, exercise) in scheduled {
+        let reviews = progress.reviews.get(&exercise.name).map_or(0, Vec::len);
+        let when = if due_at <= now {
+            theme::warning().apply_to("due now".to_string())
+        } else {
+            let days = (due_at - now).div_ceil(SECONDS_PER_DAY);
+            style(format!("in {days} day{}", if days == 1 { "" } else { "s" })).dim()
+        };
+        println!("{:<24} {when:<12} reviewed {reviews} times", exercise.name);
+    }
+}
+
+// Review the exercises that are due, one after the other in random order.
+// Returns once all of them are reviewed.
+pub fn run(exercises: &[Exercise], verbose: bool) -> Result<(), Box<dyn Error>> {
+    let mut due = due(exercises, &Progress::load());
+    if due.is_empty() {
+        println!("Nothing to review right now. Come back later, or see when with `rustlings review --list`.");
+        return Ok(());
+    }
+    // Reviewing shouldn't follow the order of the course
+    shuffle(&mut due);
+
+    let total = due.len();
+    for (i, exercise) in due.into_iter().enumerate() {
+        let copy = copy_original(exercise, Path::new(REVIEW_DIR))?;
+        println!();
+        println!(
+            "{} Review {} of {total}: solve {} again in {}",
+            symbol("🔁", ">"),
+            i + 1,
+            style(&exercise.name).bold(),
+            style(copy.path.display()).bold()
+        );
+        review(&copy, verbose)?;
+        record_review(exercise);
+        fs::remove_dir_all(Path::new(REVIEW_DIR)).ok();
+        success!("Reviewed {}", exercise);
+    }
+    println!();
+    println!("All reviews are done, see you next time!");
+    Ok(())
+}
+
+// Wait until the copy of the exercise passes, checking it whenever it changes
+fn review(copy: &Exercise, verbose: bool) -> Result<(), Box<dyn Error>> {
+    if verify([copy], (0, 1), verbose, false, 1, false).is_ok() {
+        return Ok(());
+    }
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
+    watcher.watch(REVIEW_DIR, RecursiveMode::Recursive)?;
+    loop {
+        let changed = match rx.recv()? {
+            DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => copy.contains(&path),
+            _ => false,
+        };
+        if changed && verify([copy], (0, 1), verbose, false, 1, false).is_ok() {
+            return Ok(());
+        }
+    }
+}
+
+// Put the items in random order, random enough for reviews and exams
+pub fn shuffle<T>(items: &mut [T]) {
+    let mut state = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+        | 1;
+    for i in (1..items.len()).rev() {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        items.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::progress::ExerciseStats;
+
+    #[test]
+    fn test_due_at() {
+        let exercise: Exercise = toml::from_str(
+            "name = \"intro1\"\npath = \"intro1.rs\"\nmode = \"compile\"\nhint = \"\"",
+        )
+        .unwrap();
+        let mut progress = Progress::default();
+        assert_eq!(due_at(&exercise, &progress), None);
+
+        progress.complete(&exercise);
+        progress.stats.insert(
+            "intro1".to_string(),
+            ExerciseStats {
+                finished_at: Some(1000),
+                ..ExerciseStats::default()
+            },
+        );
+        assert_eq!(due_at(&exercise, &progress), Some(1000 + SECONDS_PER_DAY));
+
+        progress
+            .reviews
+            .insert("intro1".to_string(), vec![5000, 9000]);
+        assert_eq!(
+            due_at(&exercise, &progress),
+            Some(9000 + 7 * SECONDS_PER_DAY)
+        );
+    }
+}
@@ -1,27 +1,60 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
+use crate::embedded;
 use crate::exercise::{Exercise, Mode};
+use crate::output;
+use crate::state::{self, MARKER};
 use crate::verify::test;
-use indicatif::ProgressBar;
+
+// Where pristine copies of the exercises are kept for `rustlings reset`,
+// next to info.toml
+const ORIGINALS_DIR: &str = ".rustlings-originals";
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
-// the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+// the output from the test harnesses (if the mode of the exercise is test).
+// The arguments are passed to the binary of exercises that aren't tests.
+pub fn run(exercise: &Exercise, verbose: bool, args: &[String]) -> Result<(), ()> {
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
+        Mode::Test | Mode::Property | Mode::Doctest | Mode::Project => test(exercise, verbose)?,
+        Mode::Compile | Mode::Fmt => compile_and_run(exercise, args)?,
+        Mode::Clippy | Mode::Bench | Mode::CompileFail | Mode::Plugin => {
+            compile_and_run(exercise, args)?
+        }
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
+// Resets the exercise by restoring the copy that was saved when rustlings
+// first saw it, or the copy embedded into rustlings itself.
+// If there is no such copy, the changes are stashed instead.
+// All files of directory exercises are reset.
 pub fn reset(exercise: &Exercise) -> Result<(), ()> {
+    let restored: Option<io::Result<()>> = exercise
+        .sources()
+        .map(|path| restore(exercise.info_dir(), path))
+        .collect();
+    if let Some(restored) = restored {
+        return match restored {
+            Ok(()) => {
+                success!("Successfully reset {}", exercise);
+                Ok(())
+            }
+            Err(_) => {
+                warn!("Failed to restore the original version of {}", exercise);
+                Err(())
+            }
+        };
+    }
+
     let command = Command::new("git")
         .args(["stash", "--"])
-        .arg(&exercise.path)
+        .args(exercise.sources())
         .spawn();
 
     match command {
@@ -30,13 +63,151 @@ pub fn reset(exercise: &Exercise) -> Result<(), ()> {
     }
 }
 
+// Restore the original version of a single file, if there is one
+fn restore(info_dir: &Path, path: &Path) -> Option<io::Result<()>> {
+    let original = original_path(info_dir, path);
+    if same_file(&original, path) {
+        // Copying it onto itself would empty it
+        Some(Err(io::Error::other(format!(
+            "{} is its own original",
+            path.display()
+        ))))
+    } else if original.exists() {
+        Some(fs::copy(&original, path).map(|_| ()))
+    } else {
+        embedded::original(path).map(|contents| fs::write(path, contents))
+    }
+}
+
+// Put the `I AM NOT DONE` marker back into the exercise, at the line its
+// original version has it, keeping all other changes. Exercises whose
+// original version has no marker get one at the top. Returns the file
+// the marker was put into.
+pub fn restore_marker(exercise: &Exercise) -> io::Result<PathBuf> {
+    let original = exercise.sources().find_map(|path| {
+        let contents = String::from_utf8(original_contents(exercise.info_dir(), path)?).ok()?;
+        MARKER.is_match(&contents).then_some((path, contents))
+    });
+    let (path, original) = match &original {
+        Some((path, contents)) => (*path, Some(contents.as_str())),
+        None => (exercise.path.as_path(), None),
+    };
+    let source = fs::read_to_string(path)?;
+    fs::write(path, insert_marker(&source, original))?;
+    state::invalidate(path);
+    Ok(path.to_path_buf())
+}
+
+// The source with the marker line of the original inserted at the same line
+fn insert_marker(source: &str, original: Option<&str>) -> String {
+    let (index, marker) = original
+        .and_then(|original| {
+            original
+                .lines()
+                .enumerate()
+                .find(|(_, line)| MARKER.is_match(line))
+        })
+        .unwrap_or((0, "// I AM NOT DONE"));
+    let newline = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<&str> = source.lines().collect();
+    lines.insert(index.min(lines.len()), marker);
+    let mut restored = lines.join(newline);
+    if source.is_empty() || source.ends_with('\n') {
+        restored.push_str(newline);
+    }
+    restored
+}
+
+// The original version of a single file, from the copy that was saved
+// when rustlings first saw it, or the copy embedded into rustlings itself
+fn original_contents(info_dir: &Path, path: &Path) -> Option<Vec<u8>> {
+    let original = original_path(info_dir, path);
+    if original.exists() {
+        fs::read(original).ok()
+    } else {
+        embedded::original(path).map(<[u8]>::to_vec)
+    }
+}
+
+// Copy the original versions of the files of the exercise into the given
+// directory, returning the exercise with its paths pointing at the copies.
+// This lets exercises be solved again without touching the solutions.
+pub fn copy_original(exercise: &Exercise, dir: &Path) -> Result<Exercise, Box<dyn Error>> {
+    let mut copy = exercise.clone();
+    copy.path = copy_path(&exercise.path, dir);
+    copy.files = exercise.files.iter().map(|f| copy_path(f, dir)).collect();
+    for (original, path) in exercise.sources().zip(copy.sources()) {
+        let contents = original_contents(exercise.info_dir(), original)
+            .ok_or_else(|| format!("The original version of {} is gone", original.display()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+    Ok(copy)
+}
+
+// Where the copy of the file at the given path goes in the given directory
+fn copy_path(path: &Path, dir: &Path) -> PathBuf {
+    let relative: PathBuf = path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    dir.join(relative)
+}
+
+// Save a pristine copy of every exercise that doesn't have one yet,
+// so that it can be restored by `reset` later on, and of info.toml,
+// so that `update` can tell whether it was changed.
+pub fn save_originals(exercises: &[Exercise], info_file: &Path) -> io::Result<()> {
+    let info_dir = info_file.parent().unwrap_or_else(|| Path::new(""));
+    save_original(info_dir, info_file)?;
+    for exercise in exercises {
+        for path in exercise.sources() {
+            save_original(exercise.info_dir(), path)?;
+        }
+    }
+    Ok(())
+}
+
+fn save_original(info_dir: &Path, path: &Path) -> io::Result<()> {
+    let original = original_path(info_dir, path);
+    if original.exists() || !path.exists() || same_file(&original, path) {
+        return Ok(());
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(path, &original)?;
+    Ok(())
+}
+
+// Where the pristine copy of the file at the given path is kept, by its path
+// relative to the folder of info.toml, which can't lead to the file itself
+pub fn original_path(info_dir: &Path, path: &Path) -> PathBuf {
+    let relative = path.strip_prefix(info_dir).unwrap_or(path);
+    copy_path(relative, &info_dir.join(ORIGINALS_DIR))
+}
+
+// Whether both paths lead to the same existing file
+fn same_file(a: &Path, b: &Path) -> bool {
+    matches!(
+        (fs::canonicalize(a), fs::canonicalize(b)),
+        (Ok(a), Ok(b)) if a == b
+    )
+}
+
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
-// This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+// This is strictly for non-test binaries, so output is displayed.
+// Interactive exercises are connected to the terminal instead,
+// so that they can prompt for input.
+fn compile_and_run(exercise: &Exercise, args: &[String]) -> Result<(), ()> {
+    let progress_bar = output::spinner(format!("Compiling {exercise}..."));
 
     let compilation_result = exercise.compile();
     let compilation = match compilation_result {
@@ -52,13 +223,23 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
         }
     };
 
+    if exercise.interactive {
+        progress_bar.finish_and_clear();
+        if compilation.run_interactively(args) {
+            success!("Successfully ran {}", exercise);
+            return Ok(());
+        }
+        warn!("Ran {} with errors", exercise);
+        return Err(());
+    }
+
     progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
+    let result = compilation.run_with_args(args);
     progress_bar.finish_and_clear();
 
     match result {
         Ok(output) => {
-            println!("{}", output.stdout);
+            info!("{}", output.stdout);
             success!("Successfully ran {}", exercise);
             Ok(())
         }
@@ -71,3 +252,52 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_copy_path() {
+        assert_eq!(
+            copy_path(
+                Path::new("./exercises/intro/intro1.rs"),
+                Path::new("copies")
+            ),
+            Path::new("copies").join("exercises/intro/intro1.rs")
+        );
+    }
+
+    #[test]
+    fn test_original_path() {
+        let originals = Path::new("pack").join(ORIGINALS_DIR);
+        assert_eq!(
+            original_path(Path::new("pack"), Path::new("pack/exercises/a1.rs")),
+            originals.join("exercises/a1.rs")
+        );
+        assert_eq!(
+            original_path(Path::new(""), Path::new("exercises/a1.rs")),
+            Path::new(ORIGINALS_DIR).join("exercises/a1.rs")
+        );
+        // Paths elsewhere are kept apart from the exercise itself
+        assert_eq!(
+            original_path(Path::new("pack"), Path::new("/elsewhere/a1.rs")),
+            originals.join("elsewhere/a1.rs")
+        );
+    }
+
+    #[test]
+    fn test_insert_marker() {
+        let original = "// intro1.rs\n\n// I AM NOT DONE\n\nfn main() {}\n";
+        assert_eq!(
+            insert_marker("// intro1.rs\n\nfn main() {\n}\n", Some(original)),
+            "// intro1.rs\n\n// I AM NOT DONE\nfn main() {\n}\n"
+        );
+        assert_eq!(
+            insert_marker("fn main() {}\r\n", None),
+            "// I AM NOT DONE\r\nfn main() {}\r\n"
+        );
+        // Shorter than the original
+        assert_eq!(insert_marker("", Some(original)), "// I AM NOT DONE\n");
+    }
+}
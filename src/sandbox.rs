@@ -0,0 +1,274 @@
+// Running the exercises of untrusted exercise packs, with `--untrusted`.
+// Their binaries run with a cleared environment in a temporary directory of
+// their own, which is removed afterwards, and where the platform allows it,
+// without network access and without being able to write anywhere else:
+// with bubblewrap on Linux if it's installed, or else in a network namespace
+// of their own with unshare, and with sandbox-exec on macOS.
+use crate::limits;
+use crate::output;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::LazyLock;
+
+// The variables exercises get from the environment, all others are cleared
+const KEPT_VARS: &[&str] = &[
+    "PATH",
+    "LANG",
+    "LC_ALL",
+    "TERM",
+    "RUST_BACKTRACE",
+    "RUST_TEST_THREADS",
+    "SYSTEMROOT",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+// Tells the directories of sandboxes created at the same time apart
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// How binaries are kept from the network and the filesystem
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Isolation {
+    // A read-only view of the filesystem where only the sandbox directory is
+    // writable, and no network
+    Bubblewrap,
+    // No network
+    Unshare,
+    // No network, and only the sandbox directory is writable
+    SandboxExec,
+    // Only the environment and the working directory are restricted
+    None,
+}
+
+// The best isolation available, found out once
+pub static ISOLATION: LazyLock<Isolation> = LazyLock::new(|| {
+    let works = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    if cfg!(target_os = "linux") {
+        if works("bwrap", &["--ro-bind", "/", "/", "--unshare-all", "true"]) {
+            return Isolation::Bubblewrap;
+        }
+        if works("unshare", &["--user", "--map-root-user", "--net", "true"]) {
+            return Isolation::Unshare;
+        }
+    } else if cfg!(target_os = "macos") && works("sandbox-exec", &["-n", "no-network", "true"]) {
+        return Isolation::SandboxExec;
+    }
+    Isolation::None
+});
+
+// A temporary directory a binary runs in, removed once it's dropped
+pub struct Sandbox {
+    dir: PathBuf,
+}
+
+impl Sandbox {
+    pub fn new() -> io::Result<Sandbox> {
+        let dir = env::temp_dir().join(format!(
+            "rustlings-sandbox-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir)?;
+        // sandbox-exec only knows the real path, without symlinks like /tmp
+        let dir = fs::canonicalize(&dir)?;
+        Ok(Sandbox { dir })
+    }
+
+    // A command running the program in the sandbox
+    pub fn command(&self, program: &Path) -> Command {
+        // The program is usually given relative to the current directory,
+        // which the sandbox isn't in
+        let program = fs::canonicalize(program).unwrap_or_else(|_| program.to_path_buf());
+        self.command_line(vec![program.into()])
+    }
+
+    // A command running the command line in the sandbox, whose paths have
+    // to be absolute
+    pub fn command_line(&self, command_line: Vec<OsString>) -> Command {
+        let argv = limits::argv(command_line);
+        let dir = self.dir.as_os_str();
+        let mut command = match *ISOLATION {
+            Isolation::Bubblewrap => {
+                let mut command = Command::new("bwrap");
+                command
+                    .args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"])
+                    .args(["--tmpfs", "/tmp", "--bind"])
+                    .args([dir, dir])
+                    .args(["--unshare-all", "--die-with-parent", "--new-session"])
+                    .arg("--")
+                    .args(&argv);
+                command
+            }
+            Isolation::Unshare => {
+                let mut command = Command::new("unshare");
+                command
+                    .args(["--user", "--map-root-user", "--net", "--"])
+                    .args(&argv);
+                command
+            }
+            Isolation::SandboxExec => {
+                let mut command = Command::new("sandbox-exec");
+                command.arg("-p").arg(profile(&self.dir)).args(&argv);
+                command
+            }
+            Isolation::None => {
+                let mut command = Command::new(&argv[0]);
+                command.args(&argv[1..]);
+                command
+            }
+        };
+        command.env_clear();
+        for var in KEPT_VARS {
+            if let Some(value) = env::var_os(var) {
+                command.env(var, value);
+            }
+        }
+        for var in ["HOME", "TMPDIR", "TEMP", "TMP"] {
+            command.env(var, &self.dir);
+        }
+        command.current_dir(&self.dir);
+        command
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        if fs::remove_dir_all(&self.dir).is_ok() {
+            output::log(format!("Removed the sandbox {}", self.dir.display()));
+        }
+    }
+}
+
+// Remove the sandboxes of this run that are still there, like when it's
+// interrupted while an exercise runs
+pub fn remove_all() {
+    let pattern = env::temp_dir().join(format!("rustlings-sandbox-{}-*", std::process::id()));
+    let Ok(sandboxes) = glob::glob(&pattern.to_string_lossy()) else {
+        return;
+    };
+    for dir in sandboxes.flatten() {
+        let _ignored = fs::remove_dir_all(dir);
+    }
+}
+
+// A command running the program within the memory limit, and in a sandbox
+// if exercises aren't trusted. The sandbox stays until what's returned along
+// with the command is dropped.
+pub fn command(program: &Path) -> (Command, Option<Sandbox>) {
+    if !enabled() {
+        return (unsandboxed(vec![program.into()]), None);
+    }
+    let sandbox = Sandbox::new().expect("Failed to create a sandbox for the exercise");
+    (sandbox.command(program), Some(sandbox))
+}
+
+// Like `command`, for a whole command line, whose paths have to be absolute
+pub fn command_line(command_line: Vec<OsString>) -> (Command, Option<Sandbox>) {
+    if !enabled() {
+        return (unsandboxed(command_line), None);
+    }
+    let sandbox = Sandbox::new().expect("Failed to create a sandbox for the exercise");
+    (sandbox.command_line(command_line), Some(sandbox))
+}
+
+fn unsandboxed(command_line: Vec<OsString>) -> Command {
+    let argv = limits::argv(command_line);
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..]);
+    command
+}
+
+// The sandbox-exec profile denying the network, and writes outside the directory
+fn profile(dir: &Path) -> String {
+    let dir = dir
+        .to_string_lossy()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    format!(
+        "(version 1)(allow default)(deny network*)(deny file-write*)\
+         (allow file-write* (subpath \"{dir}\") (literal \"/dev/null\") (literal \"/dev/tty\"))"
+    )
+}
+
+// The files that make cargo or rustup run what the pack wants, like the
+// `rustc-wrapper` of a `.cargo/config.toml`, or a toolchain given by path
+const TOOL_CONFIGS: &[&str] = &[".cargo", "rust-toolchain", "rust-toolchain.toml"];
+
+// The first file configuring cargo or rustup in the directory, or in one
+// below it
+pub fn tool_config(dir: &Path) -> Option<PathBuf> {
+    let mut subdirs = Vec::new();
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let name = entry.file_name();
+        if TOOL_CONFIGS.iter().any(|config| name == *config) {
+            return Some(entry.path());
+        }
+        // What rustlings and cargo put there, which is left alone
+        let generated =
+            name == "target" || name == ".git" || name.to_string_lossy().starts_with(".rustlings-");
+        // Symlinks aren't followed, so that they can't lead in circles
+        if !generated && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            subdirs.push(entry.path());
+        }
+    }
+    subdirs.iter().find_map(|subdir| tool_config(subdir))
+}
+
+// Whether the toolchain is a path, which makes rustup run the rustc of the
+// pack instead of an installed one
+pub fn is_path_toolchain(toolchain: &str) -> bool {
+    toolchain.contains('/') || toolchain.contains('\\')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_is_removed() {
+        let sandbox = Sandbox::new().unwrap();
+        let dir = sandbox.dir.clone();
+        assert!(dir.is_dir());
+        drop(sandbox);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_tool_config() {
+        let dir = env::temp_dir().join(format!("rustlings_tool_config_{}", std::process::id()));
+        fs::create_dir_all(dir.join("exercises/intro/.cargo")).unwrap();
+        fs::create_dir_all(dir.join("target/.cargo")).unwrap();
+        assert_eq!(tool_config(&dir), Some(dir.join("exercises/intro/.cargo")));
+        fs::remove_dir_all(dir.join("exercises")).unwrap();
+        assert_eq!(tool_config(&dir), None);
+        fs::write(dir.join("rust-toolchain.toml"), "").unwrap();
+        assert_eq!(tool_config(&dir), Some(dir.join("rust-toolchain.toml")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_path_toolchain() {
+        assert!(!is_path_toolchain("nightly-2024-01-01"));
+        assert!(is_path_toolchain("./toolchain"));
+        assert!(is_path_toolchain("C:\\toolchain"));
+    }
+}
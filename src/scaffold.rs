@@ -0,0 +1,209 @@
+// `rustlings new-exercise` scaffolds an exercise for exercise authors: its
+// file is created from a template for its mode, or a whole cargo package for
+// project exercises, and its entry is added to
+// info.toml next to the other exercises of its topic. info.toml is edited
+// as text, so that its comments and formatting stay as they are.
+use crate::check_metadata::entry_spans;
+use crate::exercise::{exercises_dir, Exercise, ExerciseList, Mode};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// What to scaffold
+pub struct NewExercise<'a> {
+    pub name: &'a str,
+    // The directory in the exercises folder the exercise goes into
+    pub topic: &'a str,
+    pub mode: Mode,
+    // The exercise the new one comes after, instead of the last one of its topic
+    pub after: Option<&'a str>,
+}
+
+// Create the exercise and add it to info.toml. Returns the path of the exercise.
+pub fn scaffold(
+    info_file: &Path,
+    exercises: &[Exercise],
+    new: &NewExercise,
+) -> Result<PathBuf, Box<dyn Error>> {
+    for (what, value) in [("name", new.name), ("topic", new.topic)] {
+        if value.is_empty() || !value.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "The {what} `{value}` may only contain letters, digits and underscores"
+            )
+            .into());
+        }
+    }
+    if exercises.iter().any(|e| e.name == new.name) {
+        return Err(format!("There already is an exercise called `{}`", new.name).into());
+    }
+    let file = match new.mode {
+        Mode::Project => new.name.to_string(),
+        _ => format!("{}.rs", new.name),
+    };
+    let path = exercises_dir(info_file).join(new.topic).join(&file);
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()).into());
+    }
+
+    let info = fs::read_to_string(info_file)?;
+    let relative = Path::new("exercises").join(new.topic).join(&file);
+    let entry = entry(new, &relative.to_string_lossy().replace('\\', "/"));
+    let updated = match insertion_point(&info, exercises, new)? {
+        Some(at) => format!("{}\n\n{}{}", &info[..at], entry.trim_end(), &info[at..]),
+        None => format!(
+            "{}\n\n# {}\n\n{entry}",
+            info.trim_end(),
+            new.topic.to_uppercase()
+        ),
+    };
+
+    fs::create_dir_all(path.parent().unwrap())?;
+    match new.mode {
+        Mode::Project => write_project(&path, new.name)?,
+        _ => fs::write(&path, template(new.name, new.mode))?,
+    }
+    fs::write(info_file, &updated)?;
+    // Don't leave a broken info.toml behind
+    if let Err(e) = ExerciseList::load(info_file) {
+        fs::write(info_file, info)?;
+        match new.mode {
+            Mode::Project => fs::remove_dir_all(&path)?,
+            _ => fs::remove_file(&path)?,
+        }
+        return Err(format!("Adding the exercise would break info.toml: {e}").into());
+    }
+    Ok(path)
+}
+
+// Where in info.toml the entry goes: right after the exercise it comes
+// after, or else the last exercise of its topic. `None` if the topic is new.
+fn insertion_point(
+    info: &str,
+    exercises: &[Exercise],
+    new: &NewExercise,
+) -> Result<Option<usize>, Box<dyn Error>> {
+    let previous = match new.after {
+        Some(after) => match exercises.iter().find(|e| e.name == after) {
+            Some(exercise) => exercise,
+            None => return Err(format!("There is no exercise called `{after}`").into()),
+        },
+        None => match exercises.iter().rev().find(|e| e.topic() == new.topic) {
+            Some(exercise) => exercise,
+            None => return Ok(None),
+        },
+    };
+    entry_spans(info)
+        .iter()
+        .find(|span| span.name.as_deref() == Some(previous.name.as_str()))
+        .map(|span| Some(span.end))
+        .ok_or_else(|| {
+            format!(
+                "`{}` isn't laid out as expected in info.toml",
+                previous.name
+            )
+            .into()
+        })
+}
+
+// The entry of the exercise in info.toml
+fn entry(new: &NewExercise, path: &str) -> String {
+    let mut entry = format!(
+        "[[exercises]]\nname = \"{}\"\npath = \"{path}\"\nmode = \"{}\"\n",
+        new.name,
+        mode_name(new.mode)
+    );
+    match new.mode {
+        Mode::Bench => entry.push_str("max_time_ms = 1000\n"),
+        Mode::CompileFail => entry.push_str("expected_error = \"E0382\"\n"),
+        Mode::Plugin => entry.push_str("plugin = \"TODO\"\n"),
+        _ => {}
+    }
+    entry.push_str(&format!(
+        "hint = \"\"\"\nWrite a hint for {} here.\"\"\"\n",
+        new.name
+    ));
+    entry
+}
+
+fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Compile => "compile",
+        Mode::Test => "test",
+        Mode::Clippy => "clippy",
+        Mode::Bench => "bench",
+        Mode::CompileFail => "compile_fail",
+        Mode::Property => "property",
+        Mode::Fmt => "fmt",
+        Mode::Doctest => "doctest",
+        Mode::Project => "project",
+        Mode::Plugin => "plugin",
+    }
+}
+
+// A cargo package with a library to write and a test of it in `tests`. It's a
+// workspace of its own, so that cargo doesn't take it for part of a package
+// the exercises are in.
+fn write_project(dir: &Path, name: &str) -> std::io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("tests"))?;
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             publish = false\n\n[workspace]\n\n[dependencies]\n"
+        ),
+    )?;
+    fs::write(dir.join("src/lib.rs"), template(name, Mode::Project))?;
+    fs::write(
+        dir.join("tests").join(format!("{name}.rs")),
+        format!("use {name}::*;\n\n#[test]\nfn works() {{\n    todo!()\n}}\n"),
+    )
+}
+
+// The contents of a new exercise, with the header and marker all exercises have
+fn template(name: &str, mode: Mode) -> String {
+    let body = match mode {
+        Mode::Compile | Mode::Clippy | Mode::Bench | Mode::Fmt | Mode::Plugin => {
+            "fn main() {\n    // TODO\n}\n"
+        }
+        Mode::CompileFail => {
+            "// This should fail to compile with the error in `expected_error`.\n\
+             fn main() {\n    // TODO\n}\n"
+        }
+        Mode::Test => {
+            "fn main() {\n    // You can optionally experiment here.\n}\n\n\
+             #[cfg(test)]\n\
+             mod tests {\n    use super::*;\n\n    \
+             #[test]\n    fn works() {\n        todo!()\n    }\n}\n"
+        }
+        Mode::Property => {
+            "fn main() {\n    // You can optionally experiment here.\n}\n\n\
+             #[cfg(test)]\n\
+             mod tests {\n    use super::*;\n    use rustlings_property::check;\n\n    \
+             #[test]\n    fn holds() {\n        check(|_v: Vec<u8>| todo!());\n    }\n}\n"
+        }
+        Mode::Project => {
+            "// TODO: Build the project here, in modules of its own,\n\
+             // and test it in the `tests` folder.\n\
+             pub fn todo() {}\n"
+        }
+        Mode::Doctest => {
+            "/// TODO: Show how to use this function.\n\
+             ///\n\
+             /// ```\n\
+             /// todo!();\n\
+             /// ```\n\
+             pub fn todo() {}\n"
+        }
+    };
+    let file = match mode {
+        Mode::Project => format!("{name}/src/lib.rs"),
+        _ => format!("{name}.rs"),
+    };
+    format!(
+        "// {file}\n\
+         // Execute `rustlings hint {name}` or use the `hint` watch subcommand for a hint.\n\n\
+         // I AM NOT DONE\n\n\
+         {body}"
+    )
+}
@@ -0,0 +1,199 @@
+use crate::exercise::Exercise;
+use console::style;
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+// How much a match counts towards the rank of an exercise or topic,
+// with matches in names counting most
+const NAME_SCORE: usize = 10;
+const HINT_SCORE: usize = 3;
+const LINE_SCORE: usize = 1;
+
+// How many characters of a matching line are shown
+const SNIPPET_LENGTH: usize = 72;
+
+// An exercise or topic README that matches the search
+#[derive(Debug)]
+struct Match {
+    name: String,
+    path: PathBuf,
+    score: usize,
+    // Where the query was found, like `name` or `2 comments`
+    found_in: Vec<String>,
+    // The first matching line of a hint, comment or README
+    snippet: Option<String>,
+}
+
+impl Match {
+    fn new(name: &str, path: PathBuf) -> Match {
+        Match {
+            name: name.to_string(),
+            path,
+            score: 0,
+            found_in: Vec::new(),
+            snippet: None,
+        }
+    }
+
+    // Count the lines the query is in, under the given name
+    fn add_lines<'a>(&mut self, query: &str, lines: impl Iterator<Item = &'a str>, what: &str) {
+        let matching: Vec<&str> = lines.filter(|line| contains(line, query)).collect();
+        let Some(first) = matching.first() else {
+            return;
+        };
+        self.score += matching.len() * LINE_SCORE;
+        self.found_in.push(match matching.len() {
+            1 => format!("1 {what}"),
+            n => format!("{n} {what}s"),
+        });
+        self.snippet.get_or_insert_with(|| snippet(first));
+    }
+}
+
+// Search the names, hints and comments of the exercises and the READMEs of
+// their topics for the query, and print the matches ranked
+// by how well they match. Returns whether there were any.
+pub fn run(query: &str, exercises: &[Exercise]) -> bool {
+    let mut matches: Vec<Match> = exercises
+        .iter()
+        .filter_map(|exercise| {
+            let source = fs::read_to_string(&exercise.path).unwrap_or_default();
+            match_exercise(query, exercise, &source)
+        })
+        .collect();
+
+    let mut topics: Vec<String> = Vec::new();
+    for exercise in exercises {
+        let topic = exercise.topic();
+        if topics.contains(&topic) {
+            continue;
+        }
+        let readme = exercise.topic_dir().join("README.md");
+        if let Ok(contents) = fs::read_to_string(&readme) {
+            matches.extend(match_readme(query, &topic, readme, &contents));
+        }
+        topics.push(topic);
+    }
+
+    if matches.is_empty() {
+        println!("Nothing matches `{query}`");
+        return false;
+    }
+    // Sorting is stable, so equal matches stay in the order of info.toml
+    matches.sort_by_key(|found| Reverse(found.score));
+    let width = matches.iter().map(|m| m.name.len()).max().unwrap_or(0);
+    for found in matches {
+        println!(
+            "{}  {}  {}",
+            style(format!("{:<width$}", found.name)).bold(),
+            found.path.display(),
+            style(format!("({})", found.found_in.join(", "))).dim()
+        );
+        if let Some(snippet) = found.snippet {
+            println!("{:width$}  {}", "", style(snippet).dim());
+        }
+    }
+    true
+}
+
+// How well the exercise with the given source matches the query, if at all
+fn match_exercise(query: &str, exercise: &Exercise, source: &str) -> Option<Match> {
+    let mut found = Match::new(&exercise.name, exercise.path.clone());
+    if contains(&exercise.name, query) {
+        found.score += NAME_SCORE;
+        found.found_in.push("name".to_string());
+    }
+    let levels = exercise.hint.levels();
+    if let Some(level) = levels.iter().find(|level| contains(level, query)) {
+        found.score += HINT_SCORE;
+        found.found_in.push("hint".to_string());
+        let line = level.lines().find(|line| contains(line, query));
+        found.snippet = line.map(snippet);
+    }
+    let comments = source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("//"));
+    found.add_lines(query, comments, "comment");
+    (found.score > 0).then_some(found)
+}
+
+// How well the README of the topic matches the query, if at all
+fn match_readme(query: &str, topic: &str, path: PathBuf, readme: &str) -> Option<Match> {
+    let mut found = Match::new(&format!("{topic} (topic)"), path);
+    if contains(topic, query) {
+        found.score += NAME_SCORE;
+        found.found_in.push("name".to_string());
+    }
+    found.add_lines(query, readme.lines(), "README line");
+    (found.score > 0).then_some(found)
+}
+
+// Whether the query is in the text at the start of a word, ignoring case,
+// so that `rc` finds `Rc<T>` and `rc1` but not `source`
+fn contains(text: &str, query: &str) -> bool {
+    let (text, query) = (text.to_lowercase(), query.to_lowercase());
+    text.match_indices(&query).any(|(i, _)| {
+        !text[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+// The line without surrounding whitespace and comment slashes, cut short
+fn snippet(line: &str) -> String {
+    let line = line.trim().trim_start_matches('/').trim();
+    match line.char_indices().nth(SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    #[test]
+    fn test_match_exercise() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+[[exercises]]
+name = "rc1"
+path = "exercises/smart_pointers/rc1.rs"
+mode = "test"
+hint = ["Think about ownership", "Use Rc::clone to share the sun"]
+"#,
+        )
+        .unwrap();
+        let exercise = &list.exercises[0];
+        let source = "// In this exercise, we want to express the concept of multiple owners\n\
+            // via the Rc<T> type.\nuse std::rc::Rc;\n// Rc counts references\n";
+
+        let found = match_exercise("rc", exercise, source).unwrap();
+        assert_eq!(found.found_in, ["name", "hint", "2 comments"]);
+        assert_eq!(found.score, NAME_SCORE + HINT_SCORE + 2 * LINE_SCORE);
+        assert_eq!(
+            found.snippet.as_deref(),
+            Some("Use Rc::clone to share the sun")
+        );
+
+        let found = match_exercise("MULTIPLE", exercise, source).unwrap();
+        assert_eq!(found.found_in, ["1 comment"]);
+        assert_eq!(
+            found.snippet.as_deref(),
+            Some("In this exercise, we want to express the concept of multiple owners")
+        );
+
+        assert!(match_exercise("lifetime", exercise, source).is_none());
+        assert!(match_exercise("ource", exercise, source).is_none());
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("    // A comment  "), "A comment");
+        let long = "x".repeat(SNIPPET_LENGTH + 10);
+        assert_eq!(snippet(&long), format!("{}...", "x".repeat(SNIPPET_LENGTH)));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::exam;
+use crate::exercise::Exercise;
+use crate::progress::{record_completion, Progress};
+use crate::report::{Report, Status};
+use crate::state;
+use crate::theme;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// The page of the dashboard, which gets everything else from the API below
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+// The connections of dashboards waiting for server-sent events
+type Clients = Arc<Mutex<Vec<TcpStream>>>;
+
+// What the dashboard shows about the selected exercise
+#[derive(Serialize)]
+struct Details<'a> {
+    #[serde(flatten)]
+    report: Report<'a>,
+    // The hints that have been revealed so far
+    hints: &'a [String],
+    hints_total: usize,
+    // The README of the exercise's topic, as markdown
+    readme: Option<String>,
+}
+
+// Serve the dashboard on the given address until rustlings is stopped.
+// Exercises are verified again whenever they change, and the results are
+// pushed to the dashboards as server-sent events.
+pub fn serve(
+    exercises: Vec<Exercise>,
+    address: &str,
+    exercises_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let exercises = Arc::new(exercises);
+    let clients = Clients::default();
+    watch(Arc::clone(&exercises), Arc::clone(&clients), exercises_dir)?;
+
+    let listener = TcpListener::bind(address)?;
+    let local = listener.local_addr()?;
+    println!("Serving the dashboard on http://{local}");
+    println!("Press Ctrl+C to stop.");
+    // Flush right away, so that the address can be read from a pipe
+    io::stdout().flush()?;
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let exercises = Arc::clone(&exercises);
+        let clients = Arc::clone(&clients);
+        thread::spawn(move || {
+            let _ignored = handle(stream, local, &exercises, &clients);
+        });
+    }
+    Ok(())
+}
+
+// Verify exercises whenever their files change, and tell the dashboards
+fn watch(
+    exercises: Arc<Vec<Exercise>>,
+    clients: Clients,
+    exercises_dir: &Path,
+) -> notify::Result<()> {
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
+    watcher.watch(exercises_dir, RecursiveMode::Recursive)?;
+    thread::spawn(move || {
+        // The watcher stops watching once it's dropped
+        let _watcher = watcher;
+        for event in rx {
+            let (DebouncedEvent::Create(path) | DebouncedEvent::Write(path)) = event else {
+                continue;
+            };
+            state::invalidate(&path);
+            if let Some(exercise) = exercises.iter().find(|e| e.contains(&path)) {
+                broadcast(&clients, &verify(exercise));
+            }
+        }
+    });
+    Ok(())
+}
+
+fn verify(exercise: &Exercise) -> Report<'_> {
+    let report = Report::verify(exercise);
+    if report.status == Status::Done {
+        record_completion(exercise);
+    }
+    report
+}
+
+// Send the report to all dashboards, forgetting the ones that went away
+fn broadcast(clients: &Clients, report: &Report) {
+    let event = format!(
+        "data: {}\n\n",
+        serde_json::to_string(report).expect("Failed to serialize the report to JSON")
+    );
+    clients
+        .lock()
+        .unwrap()
+        .retain_mut(|client| client.write_all(event.as_bytes()).is_ok());
+}
+
+// Answer a single HTTP request
+fn handle(
+    mut stream: TcpStream,
+    local: SocketAddr,
+    exercises: &[Exercise],
+    clients: &Clients,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the headers that tell where the request comes from are needed,
+    // and requests don't have a body
+    let (mut host, mut origin) = (None, None);
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            let value = Some(value.trim().to_string());
+            if name.eq_ignore_ascii_case("host") {
+                host = value;
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = value;
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let find = |name: &str| exercises.iter().find(|e| e.name == name);
+    // Other websites open in the browser could otherwise make it verify
+    // exercises or reveal hints, by themselves or through a DNS rebinding
+    if method == "POST" && !from_dashboard(host.as_deref(), origin.as_deref(), local) {
+        return respond(&mut stream, "403 Forbidden", "text/plain", "Forbidden");
+    }
+    match (method, segments.as_slice()) {
+        ("GET", [""]) => {
+            let dashboard = DASHBOARD.replacen("{theme}", theme::name(), 1);
+            respond(&mut stream, "200 OK", "text/html", &dashboard)
+        }
+        ("GET", ["events"]) => {
+            stream.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+            )?;
+            clients.lock().unwrap().push(stream);
+            Ok(())
+        }
+        ("GET", ["api", "exercises"]) => {
+            let reports: Vec<Report> = exercises.iter().map(Report::state).collect();
+            respond_json(&mut stream, &reports)
+        }
+        ("GET", ["api", "exercises", name]) => match find(name) {
+            Some(exercise) => respond_json(&mut stream, &details(exercise, &Progress::load())),
+            None => not_found(&mut stream),
+        },
+        ("POST", ["api", "exercises", name, "verify"]) => match find(name) {
+            Some(exercise) => {
+                let report = verify(exercise);
+                broadcast(clients, &report);
+                respond_json(&mut stream, &report)
+            }
+            None => not_found(&mut stream),
+        },
+        ("POST", ["api", "exercises", name, "hint"]) => match find(name) {
+            Some(exercise) => {
+                let mut progress = Progress::load();
+                let level = progress.hint_level(name) + 1;
+                if level <= exercise.hint.levels().len() && !exam::in_progress() {
+                    progress.see_hint_level(name, level);
+                    let _ignored = progress.save();
+                }
+                respond_json(&mut stream, &details(exercise, &progress))
+            }
+            None => not_found(&mut stream),
+        },
+        _ => not_found(&mut stream),
+    }
+}
+
+// Whether the request was made by the dashboard itself, which is on the
+// server's own host and, if the browser says where it comes from, origin.
+// When the server can be reached from other devices, like a tablet, it's
+// also on any of their addresses for it, but never on a domain name, which
+// a DNS rebinding could point at the server.
+fn from_dashboard(host: Option<&str>, origin: Option<&str>, local: SocketAddr) -> bool {
+    let port = local.port();
+    let hosts = [
+        local.to_string(),
+        format!("127.0.0.1:{port}"),
+        format!("localhost:{port}"),
+        format!("[::1]:{port}"),
+    ];
+    let on_address = |host: &str| {
+        host.parse::<SocketAddr>()
+            .is_ok_and(|address| address.port() == port)
+    };
+    let Some(host) = host.filter(|host| {
+        hosts.iter().any(|h| h == host) || (!local.ip().is_loopback() && on_address(host))
+    }) else {
+        return false;
+    };
+    origin.is_none_or(|origin| origin == format!("http://{host}"))
+}
+
+fn details<'a>(exercise: &'a Exercise, progress: &Progress) -> Details<'a> {
+    let levels = exercise.hint.levels();
+    let readme: PathBuf = exercise.topic_dir().join("README.md");
+    Details {
+        report: Report::state(exercise),
+        hints: &levels[..progress.hint_level(&exercise.name).min(levels.len())],
+        hints_total: levels.len(),
+        readme: fs::read_to_string(readme).ok(),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn respond_json<T: Serialize>(stream: &mut TcpStream, value: &T) -> io::Result<()> {
+    let body = serde_json::to_string(value).expect("Failed to serialize the response to JSON");
+    respond(stream, "200 OK", "application/json", &body)
+}
+
+fn not_found(stream: &mut TcpStream) -> io::Result<()> {
+    respond(stream, "404 Not Found", "text/plain", "Not found")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_dashboard() {
+        let local: SocketAddr = "127.0.0.1:7878".parse().unwrap();
+        assert!(from_dashboard(Some("localhost:7878"), None, local));
+        assert!(from_dashboard(
+            Some("127.0.0.1:7878"),
+            Some("http://127.0.0.1:7878"),
+            local
+        ));
+        assert!(!from_dashboard(None, None, local));
+        assert!(!from_dashboard(Some("evil.example:7878"), None, local));
+        assert!(!from_dashboard(Some("localhost:8080"), None, local));
+        assert!(!from_dashboard(
+            Some("localhost:7878"),
+            Some("http://evil.example"),
+            local
+        ));
+        assert!(!from_dashboard(
+            Some("localhost:7878"),
+            Some("http://127.0.0.1:7878"),
+            local
+        ));
+        assert!(!from_dashboard(Some("192.168.1.5:7878"), None, local));
+    }
+
+    #[test]
+    fn test_from_dashboard_on_the_network() {
+        let local: SocketAddr = "0.0.0.0:7878".parse().unwrap();
+        assert!(from_dashboard(
+            Some("192.168.1.5:7878"),
+            Some("http://192.168.1.5:7878"),
+            local
+        ));
+        assert!(from_dashboard(Some("[fd00::5]:7878"), None, local));
+        assert!(from_dashboard(Some("localhost:7878"), None, local));
+        assert!(!from_dashboard(
+            Some("192.168.1.5:7878"),
+            Some("http://evil.example"),
+            local
+        ));
+        assert!(!from_dashboard(Some("192.168.1.5:8080"), None, local));
+        assert!(!from_dashboard(
+            Some("evil.example:7878"),
+            Some("http://evil.example:7878"),
+            local
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+// The directory a run of rustlings compiles exercises into, like
+// `.rustlings-cache/build/1234` for the process 1234. Every run has its own,
+// so that two of them working on the same exercises, like watch mode and
+// `rustlings run` in another terminal, never clobber each other's binaries.
+// Each run holds a lock on `.rustlings-cache/build/1234.lock` while it's
+// running, which tells the directories of runs that ended apart: those are
+// removed, even if the run was killed before it could clean up after itself.
+//
+// Runs also take turns with each exercise: one compiles and runs it while the
+// others wait, and only one watch mode runs on the same exercises.
+use crate::exercise::COMPILE_CACHE_DIR;
+use crate::output;
+use std::fs::{self, File, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::OnceLock;
+
+struct Session {
+    dir: PathBuf,
+    // Held until rustlings exits
+    _lock: File,
+}
+
+static SESSION: OnceLock<Option<Session>> = OnceLock::new();
+
+// The build directory of this run, created when it's first needed. If it
+// can't be created, exercises are compiled into the current directory.
+pub fn dir() -> &'static Path {
+    let session = SESSION.get_or_init(|| {
+        let builds = Path::new(COMPILE_CACHE_DIR).join("build");
+        let session = start(&builds).ok();
+        remove_ended(&builds);
+        session
+    });
+    match session {
+        Some(session) => &session.dir,
+        None => Path::new("."),
+    }
+}
+
+// Remove the build directory of this run, if it has one
+pub fn end() {
+    if let Some(Some(session)) = SESSION.get() {
+        let _ignored = fs::remove_dir_all(&session.dir);
+        let _ignored = fs::remove_file(session.dir.with_extension("lock"));
+    }
+}
+
+// A lock on the exercise with the given name, held while it's compiled and
+// run, waiting for another run that holds it. Returns `None` if the lock
+// can't be taken, like in a read-only directory, where rustlings does
+// without.
+pub fn lock_exercise(name: &str) -> Option<File> {
+    let file = lock_file(&format!("{name}.lock"))?;
+    match file.try_lock() {
+        Ok(()) => return Some(file),
+        Err(TryLockError::WouldBlock) => {}
+        Err(TryLockError::Error(_)) => return None,
+    }
+    let spinner = output::spinner(format!(
+        "Waiting for another run of rustlings to finish with {name}..."
+    ));
+    let locked = file.lock();
+    spinner.finish_and_clear();
+    locked.ok().map(|()| file)
+}
+
+// A lock held while watch mode runs, so that a second one on the same
+// exercises isn't started by accident. Returns the process holding it
+// if it's already held, which is `None` if that can't be told.
+pub fn lock_watch() -> Result<Option<File>, Option<u32>> {
+    let Some(mut file) = lock_file("watch") else {
+        return Ok(None);
+    };
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut pid = String::new();
+            let _ignored = file.read_to_string(&mut pid);
+            return Err(pid.trim().parse().ok());
+        }
+        Err(TryLockError::Error(_)) => return Ok(None),
+    }
+    // For the message of the next one
+    let _ignored = file
+        .set_len(0)
+        .and_then(|()| file.rewind())
+        .and_then(|()| write!(file, "{}", process::id()));
+    Ok(Some(file))
+}
+
+// A file in `.rustlings-cache/locks` to lock, without truncating it
+fn lock_file(name: &str) -> Option<File> {
+    let dir = Path::new(COMPILE_CACHE_DIR).join("locks");
+    fs::create_dir_all(&dir).ok()?;
+    File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(name))
+        .ok()
+}
+
+fn start(builds: &Path) -> std::io::Result<Session> {
+    fs::create_dir_all(builds)?;
+    // Locked before the directory exists, so that it's never taken for one
+    // of a run that ended
+    let lock = File::create(builds.join(format!("{}.lock", process::id())))?;
+    lock.lock()?;
+    let dir = builds.join(process::id().to_string());
+    fs::create_dir_all(&dir)?;
+    output::log(format!("Compiling exercises into {}", dir.display()));
+    Ok(Session { dir, _lock: lock })
+}
+
+// Remove the build directories whose runs don't hold their locks anymore
+fn remove_ended(builds: &Path) {
+    let Ok(entries) = fs::read_dir(builds) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(lock) = lock_of(&path) else {
+            continue;
+        };
+        if let Ok(file) = File::open(&lock) {
+            if file.try_lock().is_err() {
+                continue;
+            }
+        }
+        if fs::remove_dir_all(&path).is_ok() {
+            output::log(format!("Removed {}", path.display()));
+        }
+        let _ignored = fs::remove_file(lock);
+    }
+}
+
+// The lock of a build directory, or `None` for the locks themselves
+fn lock_of(path: &Path) -> Option<PathBuf> {
+    match path.extension() {
+        None if path.is_dir() => Some(path.with_extension("lock")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_remove_ended() {
+        let builds = env::temp_dir().join(format!("rustlings-builds-{}", process::id()));
+        let _ignored = fs::remove_dir_all(&builds);
+        let running = builds.join("1");
+        let ended = builds.join("2");
+        let killed = builds.join("3");
+        for dir in [&running, &ended, &killed] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let lock = File::create(builds.join("1.lock")).unwrap();
+        lock.lock().unwrap();
+        File::create(builds.join("2.lock")).unwrap();
+
+        remove_ended(&builds);
+        assert!(running.exists());
+        assert!(builds.join("1.lock").exists());
+        assert!(!ended.exists());
+        assert!(!builds.join("2.lock").exists());
+        assert!(!killed.exists());
+        fs::remove_dir_all(&builds).unwrap();
+    }
+
+    #[test]
+    fn test_lock_watch() {
+        let watch = lock_watch().unwrap();
+        assert!(watch.is_some());
+        assert_eq!(lock_watch().unwrap_err(), Some(process::id()));
+        drop(watch);
+        assert!(lock_watch().unwrap().is_some());
+    }
+}
@@ -0,0 +1,127 @@
+// What happens when rustlings is interrupted, like with Ctrl-C in watch mode
+// or by `kill`: the compilers and exercises it started are killed, the
+// terminal is restored, progress that's being saved is saved in full, and the
+// build directory is removed, before it exits like a shell expects it to
+// after the signal. On Windows, Ctrl-C already stops all processes of the
+// console, so there's nothing to handle.
+use crate::limits;
+use crate::progress;
+use crate::sandbox;
+use crate::session;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+// The processes started with limits that are still running
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// A running process, which is killed along with everything it started if
+// rustlings is interrupted, until it's dropped. Once it's killed, the thread
+// waiting for it is stopped when dropping it, so that it doesn't go on to
+// report the process as failed while rustlings exits.
+pub struct Tracked(u32);
+
+pub fn track(pid: u32) -> Tracked {
+    RUNNING.lock().unwrap().push(pid);
+    Tracked(pid)
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        RUNNING.lock().unwrap().retain(|pid| *pid != self.0);
+        while INTERRUPTED.load(Ordering::SeqCst) {
+            thread::park();
+        }
+    }
+}
+
+// Clean up after the signal with the given number, and exit
+fn interrupted(signal: i32) -> ! {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    for pid in RUNNING.lock().unwrap().drain(..) {
+        limits::kill_tree(pid);
+    }
+    // Waits for progress that's being saved
+    let _saving = progress::lock();
+    let term = console::Term::stdout();
+    if term.is_term() {
+        let _ignored = term.show_cursor();
+    }
+    sandbox::remove_all();
+    session::end();
+    std::process::exit(128 + signal);
+}
+
+#[cfg(unix)]
+pub use unix::install;
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+#[cfg(unix)]
+mod unix {
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::thread;
+
+    // The end of the pipe the signal handler writes the signals to
+    static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+    // Only writing to a pipe is safe in a signal handler, so the signal is
+    // handled by a thread reading from it
+    extern "C" fn handle(signal: libc::c_int) {
+        let byte = signal as u8;
+        unsafe {
+            libc::write(PIPE.load(Ordering::Relaxed), (&byte as *const u8).cast(), 1);
+        }
+    }
+
+    // Handle SIGINT, SIGTERM and SIGHUP from now on
+    pub fn install() {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return;
+        }
+        PIPE.store(fds[1], Ordering::Relaxed);
+        // Reading a key leaves the terminal in raw mode if it's interrupted
+        let terminal = unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            (libc::tcgetattr(libc::STDIN_FILENO, &mut termios) == 0).then_some(termios)
+        };
+        thread::spawn(move || {
+            let mut byte = 0u8;
+            loop {
+                let read = unsafe { libc::read(fds[0], (&mut byte as *mut u8).cast(), 1) };
+                if read == 1 {
+                    break;
+                }
+                if read == 0
+                    || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted
+                {
+                    return;
+                }
+            }
+            if let Some(termios) = terminal {
+                unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) };
+            }
+            super::interrupted(byte.into());
+        });
+        let handler = handle as extern "C" fn(libc::c_int);
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+            unsafe { libc::signal(signal, handler as libc::sighandler_t) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_track() {
+        let tracked = track(u32::MAX);
+        assert!(RUNNING.lock().unwrap().contains(&u32::MAX));
+        drop(tracked);
+        assert!(!RUNNING.lock().unwrap().contains(&u32::MAX));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::diff::{diff_lines, Line};
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use crate::theme;
+use std::fs;
+
+// How many failed attempts unlock the solution of an exercise by default
+const DEFAULT_FAILURES: u32 = 3;
+
+// Print the reference solution of the exercise, or how it differs from the
+// user's attempt. Solutions are only shown once the exercise was attempted
+// unsuccessfully often enough, unless the user gives up.
+pub fn show(exercise: &Exercise, diff: bool, give_up: bool) -> Result<(), ()> {
+    let Some(path) = &exercise.solution else {
+        println!("There is no solution for {}", exercise.name);
+        return Err(());
+    };
+    let Ok(solution) = fs::read_to_string(path) else {
+        println!(
+            "There is no solution for {}, it would be in {}",
+            exercise.name,
+            path.display()
+        );
+        return Err(());
+    };
+
+    let required = exercise.solution_after.unwrap_or(DEFAULT_FAILURES);
+    let progress = Progress::load();
+    let failures = progress
+        .stats
+        .get(&exercise.name)
+        .map_or(0, |stats| stats.failures);
+    if !give_up && !progress.is_completed(exercise) && failures < required {
+        println!(
+            "The solution of {} is shown after {required} failed attempts, you've had {failures} so far.",
+            exercise.name
+        );
+        println!("Keep going, or pass `--i-give-up` to see it anyway.");
+        return Err(());
+    }
+
+    if !diff {
+        print!("{solution}");
+        return Ok(());
+    }
+    let Ok(attempt) = fs::read_to_string(&exercise.path) else {
+        println!("Failed to read {}", exercise.path.display());
+        return Err(());
+    };
+    println!(
+        "{}",
+        theme::failure().apply_to(format!("--- {} (your attempt)", exercise.path.display()))
+    );
+    println!(
+        "{}",
+        theme::success().apply_to(format!("+++ {} (solution)", path.display()))
+    );
+    for line in diff_lines(&attempt, &solution) {
+        match line {
+            Line::Same(line) => println!("  {line}"),
+            Line::Removed(line) => println!("{}", theme::failure().apply_to(format!("- {line}"))),
+            Line::Added(line) => println!("{}", theme::success().apply_to(format!("+ {line}"))),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use crate::config::CONFIG;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+
+// The regex matching the `I AM NOT DONE` marker, compiled only once
+pub static MARKER: LazyLock<Regex> = LazyLock::new(|| Regex::new(I_AM_DONE_REGEX).unwrap());
+
+// How rustlings tells that an exercise is done, set with `done` in the configuration
+#[derive(Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DoneStrategy {
+    // Once it passes and its `I AM NOT DONE` marker was removed
+    #[default]
+    Marker,
+    // Once it passes and was marked as done with `rustlings done`, or by
+    // typing `done` in watch mode, without editing the exercise
+    State,
+    // As soon as it passes
+    Passing,
+}
+
+pub fn strategy() -> DoneStrategy {
+    CONFIG.done.unwrap_or_default()
+}
+
+// What is known about a source file of an exercise
+struct Entry {
+    // When the file was last modified and how long it was when it was scanned.
+    // The entry is outdated once either of them changes.
+    modified: SystemTime,
+    len: u64,
+    // Whether the file contains the `I AM NOT DONE` marker
+    pending: bool,
+}
+
+// Whether source files contain the marker, so that checking which exercises
+// are done only reads the files that changed since the last check.
+// `list`, `verify` and watch mode check all exercises again and again.
+static FILES: LazyLock<Mutex<HashMap<PathBuf, Entry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Whether the file contains the `I AM NOT DONE` marker
+pub fn is_pending(path: &Path) -> bool {
+    let metadata = fs::metadata(path).expect("We were unable to open the exercise file!");
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let len = metadata.len();
+
+    let mut files = FILES.lock().unwrap();
+    if let Some(entry) = files.get(path) {
+        if entry.modified == modified && entry.len == len {
+            return entry.pending;
+        }
+    }
+    let source = fs::read_to_string(path).expect("We were unable to read the exercise file!");
+    // Most files don't mention being done at all, which is quicker to rule out
+    let pending = source.contains("DONE") && MARKER.is_match(&source);
+    files.insert(
+        path.to_path_buf(),
+        Entry {
+            modified,
+            len,
+            pending,
+        },
+    );
+    pending
+}
+
+// Forget what is known about the changed file, for changes that happen
+// too quickly after each other to change its modification time
+pub fn invalidate(changed: &Path) {
+    FILES
+        .lock()
+        .unwrap()
+        .retain(|path, _| !changed.ends_with(path));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_pending_after_changes() {
+        let path = std::env::temp_dir().join("rustlings-state-test.rs");
+        fs::write(&path, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+        assert!(is_pending(&path));
+        fs::write(&path, "fn main() {}\n").unwrap();
+        assert!(!is_pending(&path));
+        fs::remove_file(&path).unwrap();
+    }
+}
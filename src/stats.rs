@@ -0,0 +1,214 @@
+use crate::exercise::{last_compile_time, Exercise};
+use crate::output::{ascii, symbol};
+use crate::progress::{today, Progress, SECONDS_PER_DAY};
+use crate::streak::completed_on;
+use crate::summary::format_date;
+use console::style;
+
+// How many days `stats --chart` shows the completed exercises of
+const CHART_DAYS: u64 = 30;
+// How many characters the longest bar of `stats --chart` takes
+const BAR_WIDTH: usize = 40;
+
+// Totals of the statistics of the exercises in a topic
+#[derive(Default)]
+struct TopicStats {
+    name: String,
+    exercises: u32,
+    seconds: u64,
+    attempts: u32,
+    failures: u32,
+    // The hint levels revealed to complete the exercises
+    hints: usize,
+}
+
+// The statistics of the exercises added up per topic,
+// in the order the topics first appear in info.toml
+fn topic_stats(exercises: &[Exercise], progress: &Progress) -> Vec<TopicStats> {
+    let mut topics: Vec<TopicStats> = Vec::new();
+    for exercise in exercises {
+        let Some(stats) = progress.stats.get(&exercise.name) else {
+            continue;
+        };
+        let name = exercise.topic();
+        let topic = match topics.iter().position(|t| t.name == name) {
+            Some(i) => &mut topics[i],
+            None => {
+                topics.push(TopicStats {
+                    name,
+                    ..TopicStats::default()
+                });
+                topics.last_mut().unwrap()
+            }
+        };
+        topic.exercises += 1;
+        topic.seconds += stats.time_spent();
+        topic.attempts += stats.attempts;
+        topic.failures += stats.failures;
+        topic.hints += progress.hints_used(&exercise.name);
+    }
+    topics
+}
+
+// Print how much time was spent on each topic, how many attempts were
+// needed, how often those failed on average per exercise, and how many hints
+// were used
+pub fn show(exercises: &[Exercise]) {
+    let progress = Progress::load();
+    let topics = topic_stats(exercises, &progress);
+    if topics.is_empty() {
+        println!("No statistics yet, run `rustlings watch` to start working on the exercises!");
+        return;
+    }
+
+    println!(
+        "{:<17}\t{:>9}\t{:>8}\t{:>12}\t{:>5}",
+        "Topic", "Time", "Attempts", "Avg failures", "Hints"
+    );
+    for topic in &topics {
+        println!(
+            "{:<17}\t{:>9}\t{:>8}\t{:>12.1}\t{:>5}",
+            topic.name,
+            format_duration(topic.seconds),
+            topic.attempts,
+            topic.failures as f32 / topic.exercises as f32,
+            topic.hints
+        );
+    }
+
+    let seconds: u64 = topics.iter().map(|t| t.seconds).sum();
+    let attempts: u32 = topics.iter().map(|t| t.attempts).sum();
+    println!();
+    println!(
+        "Total: {} spent over {} attempts.",
+        format_duration(seconds),
+        attempts
+    );
+    let without_hints = exercises
+        .iter()
+        .filter(|e| progress.completed_without_hints(e))
+        .count();
+    println!(
+        "Completed without hints: {without_hints}/{}.",
+        exercises.len()
+    );
+}
+
+// Chart how many exercises were completed on each of the last days,
+// and how many attempts every topic took
+pub fn chart(exercises: &[Exercise]) {
+    let progress = Progress::load();
+    let topics = topic_stats(exercises, &progress);
+    if topics.is_empty() {
+        println!("No statistics yet, run `rustlings watch` to start working on the exercises!");
+        return;
+    }
+
+    let completed = completed_on(&progress);
+    let first_day = today() + 1 - CHART_DAYS;
+    let per_day: Vec<usize> = (first_day..=today())
+        .map(|day| completed.get(&day).copied().unwrap_or(0))
+        .collect();
+    println!("Exercises completed per day, the last {CHART_DAYS} days:");
+    println!("  {}", style(sparkline(&per_day)).green());
+    let from = format_date(first_day * SECONDS_PER_DAY);
+    println!(
+        "  {from}{:>width$}",
+        "today",
+        width = CHART_DAYS as usize - from.len()
+    );
+    println!(
+        "  {} completed, {} on the best day",
+        per_day.iter().sum::<usize>(),
+        per_day.iter().max().copied().unwrap_or(0)
+    );
+
+    println!();
+    println!("Attempts per topic:");
+    let most = topics.iter().map(|t| t.attempts).max().unwrap_or(0);
+    let name_width = topics.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for topic in &topics {
+        println!(
+            "  {:<name_width$}  {} {}",
+            topic.name,
+            style(bar(topic.attempts, most, BAR_WIDTH)).cyan(),
+            topic.attempts
+        );
+    }
+}
+
+// A line of blocks as high as the values are, relative to the largest one
+fn sparkline(values: &[usize]) -> String {
+    let blocks: &[char] = if ascii() {
+        &['_', '.', ':', '-', '=', '+', '*', '#']
+    } else {
+        &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█']
+    };
+    let max = values.iter().max().copied().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|value| blocks[(value * (blocks.len() - 1)).div_ceil(max)])
+        .collect()
+}
+
+// A bar as long as the value is, relative to the largest value taking the
+// whole width. Values above zero always get some of it.
+fn bar(value: u32, max: u32, width: usize) -> String {
+    let length = (value as usize * width).div_ceil(max.max(1) as usize);
+    symbol("█", "#").repeat(length)
+}
+
+// How working on the exercise went so far, for the footer of watch mode:
+// how long compiling it took last, how often it was checked, and for how long
+pub fn footer(exercise: &Exercise) -> String {
+    let mut parts = Vec::new();
+    if let Some(compile_time) = last_compile_time() {
+        let seconds = format!("{:.1}", compile_time.as_secs_f64());
+        parts.push(t!("footer-compiled", seconds = seconds));
+    }
+    if let Some(stats) = Progress::load().stats.get(&exercise.name) {
+        parts.push(t!(
+            "footer-attempts",
+            attempts = stats.attempts,
+            failures = stats.failures
+        ));
+        parts.push(t!(
+            "footer-time",
+            time = format_duration(stats.time_spent())
+        ));
+    }
+    parts.join(symbol(" · ", " | "))
+}
+
+// Format seconds like `1h 05m` or `3m 20s`
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m {seconds:02}s")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        if ascii() {
+            return;
+        }
+        assert_eq!(sparkline(&[0, 1, 2, 4]), "▁▃▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+    }
+
+    #[test]
+    fn test_bar() {
+        let block = symbol("█", "#");
+        assert_eq!(bar(10, 10, 4), block.repeat(4));
+        assert_eq!(bar(1, 10, 4), block);
+        assert_eq!(bar(0, 10, 4), "");
+        assert_eq!(bar(0, 0, 4), "");
+    }
+}
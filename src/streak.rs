@@ -0,0 +1,233 @@
+use crate::exercise::Exercise;
+use crate::output::symbol;
+use crate::progress::{today, Progress, SECONDS_PER_DAY};
+use crate::topics::by_topic;
+use console::style;
+use std::collections::{BTreeMap, BTreeSet};
+
+// How many exercises have to be completed in a single day for a badge
+const EXERCISES_IN_A_DAY: usize = 10;
+
+// How many days in a row exercises have to be worked on for a badge
+const STREAK_DAYS: usize = 7;
+
+// Something the user achieved while working on the exercises
+struct Badge {
+    // Identifies the badge in the progress file
+    id: String,
+    title: String,
+    description: String,
+}
+
+impl Badge {
+    fn new(id: &str, title: &str, description: &str) -> Badge {
+        Badge {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
+
+// All badges there are, along with whether the progress earns them
+fn badges(exercises: &[Exercise], progress: &Progress) -> Vec<(Badge, bool)> {
+    let completed = exercises
+        .iter()
+        .filter(|e| progress.is_completed(e))
+        .count();
+    let mut badges = vec![
+        (
+            Badge::new(
+                "first-exercise",
+                "First steps",
+                "Completed your first exercise",
+            ),
+            completed > 0,
+        ),
+        (
+            Badge::new(
+                "exercises-in-a-day",
+                "On a roll",
+                &format!("Completed {EXERCISES_IN_A_DAY} exercises in one day"),
+            ),
+            most_completed_in_a_day(progress) >= EXERCISES_IN_A_DAY,
+        ),
+        (
+            Badge::new(
+                "streak",
+                "Week streak",
+                &format!("Worked on exercises {STREAK_DAYS} days in a row"),
+            ),
+            longest_streak(&progress.active_days) >= STREAK_DAYS,
+        ),
+    ];
+
+    for (topic, in_topic) in by_topic(exercises) {
+        let done = in_topic.iter().all(|e| progress.is_completed(e));
+        badges.push((
+            Badge::new(
+                &format!("finished-{topic}"),
+                &format!("Finished {topic}"),
+                &format!("Completed every exercise about {topic}"),
+            ),
+            done,
+        ));
+    }
+    badges.push((
+        Badge::new("finished", "Rustacean", "Completed all exercises"),
+        completed == exercises.len(),
+    ));
+    badges
+}
+
+// Award the badges the progress earns but that haven't been awarded yet,
+// returning them
+fn award(exercises: &[Exercise], progress: &mut Progress) -> Vec<Badge> {
+    let mut awarded = Vec::new();
+    for (badge, earned) in badges(exercises, progress) {
+        if earned && !progress.badges.contains(&badge.id) {
+            progress.badges.insert(badge.id.clone());
+            awarded.push(badge);
+        }
+    }
+    awarded
+}
+
+// Print a banner for every badge earned since the last time,
+// which watch mode does after verifying exercises
+pub fn celebrate(exercises: &[Exercise]) {
+    let mut progress = Progress::load();
+    let awarded = award(exercises, &mut progress);
+    if awarded.is_empty() {
+        return;
+    }
+    if progress.save().is_err() {
+        warn!("{}", "Failed to save your badges");
+    }
+    for badge in awarded {
+        println!();
+        println!(
+            "{}",
+            style(format!(
+                "{star} New badge: {} {star}",
+                badge.title,
+                star = symbol("★", "*")
+            ))
+            .bold()
+            .yellow()
+        );
+        println!("  {}", badge.description);
+    }
+}
+
+// Print the current and longest streak, and all badges
+pub fn show(exercises: &[Exercise]) {
+    let mut progress = Progress::load();
+    if !award(exercises, &mut progress).is_empty() && progress.save().is_err() {
+        warn!("{}", "Failed to save your badges");
+    }
+
+    let days = |n: usize| if n == 1 { "day" } else { "days" };
+    let current = current_streak(&progress.active_days, today());
+    let longest = longest_streak(&progress.active_days);
+    println!("Current streak: {current} {}", days(current));
+    println!("Longest streak: {longest} {}", days(longest));
+    println!(
+        "Completed today: {}",
+        completed_on(&progress).get(&today()).copied().unwrap_or(0)
+    );
+
+    let badges = badges(exercises, &progress);
+    let earned = badges
+        .iter()
+        .filter(|(badge, _)| progress.badges.contains(&badge.id))
+        .count();
+    println!();
+    println!("Badges: {earned}/{}", badges.len());
+    for (badge, _) in &badges {
+        if progress.badges.contains(&badge.id) {
+            println!(
+                "  {} {} - {}",
+                style(symbol("★", "*")).yellow(),
+                style(&badge.title).bold(),
+                badge.description
+            );
+        } else {
+            println!(
+                "{}",
+                style(format!(
+                    "  {} {} - {}",
+                    symbol("☆", "-"),
+                    badge.title,
+                    badge.description
+                ))
+                .dim()
+            );
+        }
+    }
+}
+
+// How many exercises were completed on each day
+pub fn completed_on(progress: &Progress) -> BTreeMap<u64, usize> {
+    let mut days = BTreeMap::new();
+    for stats in progress.stats.values() {
+        if let Some(finished_at) = stats.finished_at {
+            *days.entry(finished_at / SECONDS_PER_DAY).or_insert(0) += 1;
+        }
+    }
+    days
+}
+
+fn most_completed_in_a_day(progress: &Progress) -> usize {
+    completed_on(progress).into_values().max().unwrap_or(0)
+}
+
+// The number of days in a row up to the given day that exercises were worked on.
+// The streak isn't broken before the day is over, so it may also end the day before.
+fn current_streak(active_days: &BTreeSet<u64>, today: u64) -> usize {
+    let mut day = if active_days.contains(&today) {
+        today
+    } else {
+        today.saturating_sub(1)
+    };
+    let mut streak = 0;
+    while active_days.contains(&day) {
+        streak += 1;
+        if day == 0 {
+            break;
+        }
+        day -= 1;
+    }
+    streak
+}
+
+// The most days in a row that exercises were worked on
+fn longest_streak(active_days: &BTreeSet<u64>) -> usize {
+    let mut longest = 0;
+    let mut streak = 0;
+    let mut previous: Option<u64> = None;
+    for &day in active_days {
+        streak = match previous {
+            Some(previous) if previous + 1 == day => streak + 1,
+            _ => 1,
+        };
+        longest = longest.max(streak);
+        previous = Some(day);
+    }
+    longest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_streaks() {
+        let active_days = BTreeSet::from([3, 4, 5, 6, 10, 11, 12]);
+        assert_eq!(longest_streak(&active_days), 4);
+        assert_eq!(current_streak(&active_days, 12), 3);
+        // Not working on exercises yet today doesn't break the streak
+        assert_eq!(current_streak(&active_days, 13), 3);
+        assert_eq!(current_streak(&active_days, 14), 0);
+    }
+}
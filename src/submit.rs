@@ -0,0 +1,226 @@
+// `rustlings submit` posts the results of all exercises to an endpoint set
+// up by the instructor, like a GitHub Classroom or Gitea webhook, so that
+// results can be collected without students pushing their code. With
+// `--attest`, the submission also contains the hashes of the rustlings
+// executable and of the exercises, so that the instructor can tell which
+// ones the results came from, and it's signed with the token. The signature
+// only authenticates the submission: it shows that whoever holds the token
+// sent it unchanged, but since the student holds the token, it doesn't keep
+// them from changing the tests, the results or rustlings itself.
+use crate::config::CONFIG;
+use crate::exercise::Exercise;
+use crate::progress::now;
+use crate::report::{Report, Status};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+// Takes precedence over the token in the configuration
+const TOKEN_VAR: &str = "RUSTLINGS_SUBMIT_TOKEN";
+// The header the signature is sent in, like the one of GitHub webhooks
+const SIGNATURE_HEADER: &str = "X-Rustlings-Signature-256";
+
+#[derive(Serialize)]
+pub struct Submission<'a> {
+    rustlings_version: &'a str,
+    // In seconds since the Unix epoch
+    submitted_at: u64,
+    done: usize,
+    total: usize,
+    exercises: Vec<Report<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attestation: Option<Attestation>,
+}
+
+// What the results were produced from
+#[derive(Serialize)]
+struct Attestation {
+    // The SHA-256 of the rustlings executable
+    rustlings: String,
+    // The SHA-256 of info.toml
+    info: String,
+    exercises: BTreeMap<String, ExerciseHashes>,
+}
+
+#[derive(Serialize)]
+struct ExerciseHashes {
+    // The SHA-256 of every file of the exercise, by its path relative to
+    // info.toml, which is more than one for directory and project exercises
+    sources: BTreeMap<String, String>,
+    // The SHA-256 of its tests, which students aren't supposed to change.
+    // These are its hidden tests, or else its `#[cfg(test)]` module.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tests: Option<String>,
+}
+
+// Verify all exercises, and put together what is submitted
+pub fn collect<'a>(
+    exercises: &'a [Exercise],
+    info_file: &Path,
+    attest: bool,
+) -> Result<Submission<'a>, Box<dyn Error>> {
+    let reports: Vec<Report> = exercises.iter().map(Report::verify).collect();
+    let attestation = if attest {
+        Some(attestation(exercises, info_file)?)
+    } else {
+        None
+    };
+    Ok(Submission {
+        rustlings_version: crate::VERSION,
+        submitted_at: now(),
+        done: reports.iter().filter(|r| r.status == Status::Done).count(),
+        total: reports.len(),
+        exercises: reports,
+        attestation,
+    })
+}
+
+fn attestation(exercises: &[Exercise], info_file: &Path) -> Result<Attestation, Box<dyn Error>> {
+    let mut hashes = BTreeMap::new();
+    for exercise in exercises {
+        let source = fs::read_to_string(&exercise.path)
+            .map_err(|e| format!("Failed to read {}: {e}", exercise.path.display()))?;
+        let tests = match &exercise.hidden_tests {
+            Some(hidden_tests) => Some(fs::read_to_string(hidden_tests)?),
+            None => test_module(&source).map(str::to_string),
+        };
+        let mut sources = BTreeMap::new();
+        for path in exercise.sources() {
+            let contents =
+                fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            let relative = path.strip_prefix(exercise.info_dir()).unwrap_or(path);
+            sources.insert(
+                relative.display().to_string(),
+                hex::encode(Sha256::digest(&contents)),
+            );
+        }
+        hashes.insert(
+            exercise.name.clone(),
+            ExerciseHashes {
+                sources,
+                tests: tests.map(|tests| hex::encode(Sha256::digest(tests.as_bytes()))),
+            },
+        );
+    }
+    let executable = env::current_exe()?;
+    let rustlings = fs::read(&executable)
+        .map_err(|e| format!("Failed to read {}: {e}", executable.display()))?;
+    Ok(Attestation {
+        rustlings: hex::encode(Sha256::digest(&rustlings)),
+        info: hex::encode(Sha256::digest(&fs::read(info_file)?)),
+        exercises: hashes,
+    })
+}
+
+// The `#[cfg(test)]` module at the end of an exercise, if it has one
+fn test_module(source: &str) -> Option<&str> {
+    let start = source.find("#[cfg(test)]")?;
+    Some(source[start..].trim_end())
+}
+
+// The token to authenticate with and to sign the submission with
+pub fn token() -> Option<String> {
+    env::var(TOKEN_VAR)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .or_else(|| CONFIG.submit.token.clone())
+}
+
+impl Submission<'_> {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("submissions can always be serialized")
+    }
+
+    // Post the submission to the endpoint with curl, which deals with
+    // TLS and proxies
+    pub fn post(&self, endpoint: &str, token: Option<&str>) -> Result<(), Box<dyn Error>> {
+        // A line break would start another header
+        if token.is_some_and(|token| token.chars().any(char::is_control)) {
+            return Err("the token can't contain control characters, like line breaks".into());
+        }
+        let body = self.to_json();
+        // Headers go through stdin, so that the token doesn't show up
+        // in the list of processes
+        let mut headers = String::from("Content-Type: application/json\n");
+        if let Some(token) = token {
+            headers += &format!("Authorization: Bearer {token}\n");
+            if self.attestation.is_some() {
+                let mut mac = Hmac::<Sha256>::new_from_slice(token.as_bytes())
+                    .expect("HMAC takes keys of any length");
+                mac.update(body.as_bytes());
+                let signature = hex::encode(mac.finalize().into_bytes());
+                headers += &format!("{SIGNATURE_HEADER}: sha256={signature}\n");
+            }
+        }
+        // Created only for us and removed when it's dropped
+        let mut body_file = NamedTempFile::new()?;
+        body_file.write_all(body.as_bytes())?;
+        body_file.flush()?;
+
+        let child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .args(["--header", "@-", "--data-binary"])
+            .arg(format!("@{}", body_file.path().display()))
+            .arg(endpoint)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+        match child {
+            Ok(mut child) => {
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(headers.as_bytes())?;
+                }
+                let output = child.wait_with_output()?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(String::from_utf8_lossy(&output.stderr)
+                        .trim()
+                        .to_string()
+                        .into())
+                }
+            }
+            Err(e) => Err(format!("Failed to run curl, which is needed to submit: {e}").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_test_module() {
+        let source = "fn main() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n";
+        assert_eq!(
+            test_module(source),
+            Some("#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}")
+        );
+        assert_eq!(test_module("fn main() {}\n"), None);
+    }
+
+    #[test]
+    fn test_token_with_line_breaks() {
+        let submission = Submission {
+            rustlings_version: crate::VERSION,
+            submitted_at: 0,
+            done: 0,
+            total: 0,
+            exercises: Vec::new(),
+            attestation: None,
+        };
+        let error = submission
+            .post("http://127.0.0.1:1", Some("token\r\nX-Injected: 1"))
+            .unwrap_err();
+        assert!(error.to_string().contains("control characters"));
+    }
+}
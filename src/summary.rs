@@ -0,0 +1,230 @@
+use crate::exercise::Exercise;
+use crate::progress::{Progress, SECONDS_PER_DAY};
+use crate::stats::format_duration;
+use crate::topics::by_topic;
+
+// The formats `rustlings report` can write the summary in
+#[derive(PartialEq, Debug)]
+pub enum SummaryFormat {
+    // A table to post in forums or issues
+    Markdown,
+    // Comma separated values, for instructors collecting the reports of a class
+    Csv,
+}
+
+pub fn parse_summary_format(value: &str) -> Result<SummaryFormat, String> {
+    match value {
+        "md" | "markdown" => Ok(SummaryFormat::Markdown),
+        "csv" => Ok(SummaryFormat::Csv),
+        _ => Err(format!("unknown format `{value}`, expected `md` or `csv`")),
+    }
+}
+
+// The progress in a single topic
+#[derive(Default, PartialEq, Debug)]
+struct TopicSummary {
+    name: String,
+    completed: usize,
+    // How many exercises were completed without revealing any hints
+    without_hints: usize,
+    // The hint levels revealed to complete the exercises
+    hints: usize,
+    total: usize,
+    // When the first exercise of the topic was first checked
+    started_at: Option<u64>,
+    // When the last exercise of the topic was completed, once all are
+    finished_at: Option<u64>,
+    seconds: u64,
+}
+
+// Print a summary of the progress per topic, in the given format
+pub fn print(exercises: &[Exercise], format: &SummaryFormat) {
+    let topics = summarize(exercises, &Progress::load());
+    match format {
+        SummaryFormat::Markdown => print!("{}", markdown(&topics)),
+        SummaryFormat::Csv => print!("{}", csv(&topics)),
+    }
+}
+
+// Topics in the order they first appear in info.toml
+fn summarize(exercises: &[Exercise], progress: &Progress) -> Vec<TopicSummary> {
+    let mut topics: Vec<TopicSummary> = Vec::new();
+    for (name, in_topic) in by_topic(exercises) {
+        let mut topic = TopicSummary {
+            name,
+            total: in_topic.len(),
+            ..TopicSummary::default()
+        };
+        for exercise in in_topic {
+            if progress.is_completed(exercise) {
+                topic.completed += 1;
+            }
+            if progress.completed_without_hints(exercise) {
+                topic.without_hints += 1;
+            }
+            topic.hints += progress.hints_used(&exercise.name);
+            if let Some(stats) = progress.stats.get(&exercise.name) {
+                topic.seconds += stats.time_spent();
+                topic.started_at = Some(
+                    topic
+                        .started_at
+                        .map_or(stats.started_at, |t| t.min(stats.started_at)),
+                );
+                topic.finished_at = topic.finished_at.max(stats.finished_at);
+            }
+        }
+        if topic.completed < topic.total {
+            topic.finished_at = None;
+        }
+        topics.push(topic);
+    }
+    topics
+}
+
+fn markdown(topics: &[TopicSummary]) -> String {
+    let mut out = String::from(
+        "| Topic | Completed | Without hints | Hints | Started | Finished | Time spent |\n",
+    );
+    out.push_str("| --- | ---: | ---: | ---: | --- | --- | ---: |\n");
+    for topic in topics {
+        out.push_str(&format!(
+            "| {} | {}/{} | {}/{} | {} | {} | {} | {} |\n",
+            topic.name,
+            topic.completed,
+            topic.total,
+            topic.without_hints,
+            topic.total,
+            topic.hints,
+            topic.started_at.map(format_date).unwrap_or_default(),
+            topic.finished_at.map(format_date).unwrap_or_default(),
+            format_duration(topic.seconds),
+        ));
+    }
+    let completed: usize = topics.iter().map(|t| t.completed).sum();
+    let total: usize = topics.iter().map(|t| t.total).sum();
+    let without_hints: usize = topics.iter().map(|t| t.without_hints).sum();
+    let hints: usize = topics.iter().map(|t| t.hints).sum();
+    let seconds: u64 = topics.iter().map(|t| t.seconds).sum();
+    out.push_str(&format!(
+        "| **Total** | **{completed}/{total}** | **{without_hints}/{total}** | **{hints}** | | | **{}** |\n",
+        format_duration(seconds)
+    ));
+    out
+}
+
+// Times are in seconds since the Unix epoch, so that they are easy to process
+fn csv(topics: &[TopicSummary]) -> String {
+    let mut out = String::from(
+        "topic,completed,total,started_at,finished_at,seconds_spent,completed_without_hints,hints_used\n",
+    );
+    for topic in topics {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            topic.name,
+            topic.completed,
+            topic.total,
+            topic.started_at.map(|t| t.to_string()).unwrap_or_default(),
+            topic.finished_at.map(|t| t.to_string()).unwrap_or_default(),
+            topic.seconds,
+            topic.without_hints,
+            topic.hints,
+        ));
+    }
+    out
+}
+
+// Format seconds since the Unix epoch as a date like `2023-04-01`, in UTC.
+// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub fn format_date(seconds: u64) -> String {
+    let days = (seconds / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_680_350_000), "2023-04-01");
+    }
+
+    #[test]
+    fn test_hints_used_to_complete() {
+        let exercises: Vec<Exercise> = ["intro1", "intro2", "intro3"]
+            .iter()
+            .map(|name| {
+                toml::from_str(&format!(
+                    "name = \"{name}\"\npath = \"exercises/intro/{name}.rs\"\nmode = \"compile\"\nhint = \"\""
+                ))
+                .unwrap()
+            })
+            .collect();
+        let mut progress = Progress::default();
+        for exercise in &exercises {
+            progress.attempt(exercise, false);
+        }
+        progress.see_hint_level("intro2", 2);
+        progress.complete(&exercises[0]);
+        progress.complete(&exercises[1]);
+        // Revealed after completing it, which doesn't count
+        progress.see_hint_level("intro1", 1);
+        progress.see_hint_level("intro3", 1);
+
+        let topics = summarize(&exercises, &progress);
+        assert_eq!(topics[0].completed, 2);
+        assert_eq!(topics[0].without_hints, 1);
+        assert_eq!(topics[0].hints, 3);
+    }
+
+    #[test]
+    fn test_formats() {
+        let topics = [
+            TopicSummary {
+                name: "intro".to_string(),
+                completed: 2,
+                without_hints: 1,
+                hints: 3,
+                total: 2,
+                started_at: Some(1_680_350_000),
+                finished_at: Some(1_680_353_600),
+                seconds: 200,
+            },
+            TopicSummary {
+                name: "variables".to_string(),
+                completed: 0,
+                total: 6,
+                ..TopicSummary::default()
+            },
+        ];
+        assert_eq!(
+            markdown(&topics),
+            "| Topic | Completed | Without hints | Hints | Started | Finished | Time spent |\n\
+             | --- | ---: | ---: | ---: | --- | --- | ---: |\n\
+             | intro | 2/2 | 1/2 | 3 | 2023-04-01 | 2023-04-01 | 3m 20s |\n\
+             | variables | 0/6 | 0/6 | 0 |  |  | 0m 00s |\n\
+             | **Total** | **2/8** | **1/8** | **3** | | | **3m 20s** |\n"
+        );
+        assert_eq!(
+            csv(&topics),
+            "topic,completed,total,started_at,finished_at,seconds_spent,completed_without_hints,hints_used\n\
+             intro,2,2,1680350000,1680353600,200,1,3\n\
+             variables,0,6,,,0,0,0\n"
+        );
+    }
+}
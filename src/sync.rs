@@ -0,0 +1,427 @@
+// `rustlings sync` keeps the progress in sync across machines through a
+// backend the user sets up in the `[sync]` table of rustlings.toml: a GitHub
+// gist, an object in an S3-compatible bucket, a file on a WebDAV server, or
+// a file in a folder that is synced some other way. The progress never leaves
+// the machine unencrypted: it's encrypted with XChaCha20-Poly1305 and a key
+// derived from a passphrase, so the backend only ever sees noise. Syncing
+// pulls the progress from the backend, merges it with the local one, and
+// pushes the result back.
+use crate::config::CONFIG;
+use crate::progress::Progress;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use pbkdf2::pbkdf2_hmac_array;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+// Take precedence over the token and passphrase in the configuration
+const TOKEN_VAR: &str = "RUSTLINGS_SYNC_TOKEN";
+const PASSPHRASE_VAR: &str = "RUSTLINGS_SYNC_PASSPHRASE";
+
+// The name of the file in the gist
+const GIST_FILE: &str = "rustlings-progress";
+const GITHUB_API: &str = "https://api.github.com";
+
+// What encrypted progress starts with, along with the version of its format
+const MAGIC: &str = "rustlings-sync";
+const FORMAT_VERSION: u32 = 2;
+// How often the passphrase is hashed to derive the key. It's part of the
+// format, so that what's downloaded can't make deriving the key take forever.
+const ITERATIONS: u32 = 600_000;
+// In bytes
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+// Where the progress is synced to
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncBackend {
+    // A file in a GitHub gist, given by its ID
+    Gist,
+    // An object in an S3-compatible bucket, given by its URL
+    S3,
+    // A file on a WebDAV server, given by its URL
+    Webdav,
+    // A file in a folder that is synced by something else, given by its path
+    File,
+}
+
+// Which way to sync
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Direction {
+    // Merge the progress of the backend into the local one
+    Pull,
+    // Replace the progress of the backend with the local one
+    Push,
+    // Pull, then push the merged progress
+    Both,
+}
+
+// Sync the progress with the configured backend
+pub fn run(direction: Direction) -> Result<(), Box<dyn Error>> {
+    let backend = CONFIG.sync.backend.ok_or(
+        "Syncing isn't set up, choose a `backend` in the `[sync]` table of rustlings.toml",
+    )?;
+    let passphrase = env::var(PASSPHRASE_VAR)
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+        .or_else(|| CONFIG.sync.passphrase.clone())
+        .ok_or(format!(
+            "Syncing needs a passphrase to encrypt your progress with, set `passphrase` in `[sync]` or {PASSPHRASE_VAR}"
+        ))?;
+
+    let mut progress = Progress::load();
+    if direction != Direction::Push {
+        match download(backend)? {
+            Some(remote) => {
+                let remote: Progress = toml::from_str(&decrypt(&remote, &passphrase)?)?;
+                let completed = progress.completed.len();
+                progress.merge(remote);
+                progress.save()?;
+                success!(
+                    "{}",
+                    t!("sync-pulled", count = progress.completed.len() - completed)
+                );
+            }
+            None if direction == Direction::Pull => {
+                println!("{}", t!("sync-nothing-to-pull"));
+            }
+            None => {}
+        }
+    }
+    if direction != Direction::Pull {
+        let contents = toml::to_string(&progress)?;
+        upload(backend, &encrypt(&contents, &passphrase)?)?;
+        success!("{}", t!("sync-pushed", count = progress.completed.len()));
+    }
+    Ok(())
+}
+
+// Sync both ways if the configuration asks to do that automatically,
+// which watch mode does when it starts and when it ends
+pub fn auto() {
+    if !CONFIG.sync.auto.unwrap_or(false) {
+        return;
+    }
+    if let Err(e) = run(Direction::Both) {
+        warn!("{}", t!("sync-failed", error = e));
+    }
+}
+
+// The encrypted progress stored in the backend, or `None` if there is none yet
+fn download(backend: SyncBackend) -> Result<Option<String>, Box<dyn Error>> {
+    match backend {
+        SyncBackend::File => {
+            let path = file_path()?;
+            if !path.exists() {
+                return Ok(None);
+            }
+            Ok(Some(fs::read_to_string(path)?))
+        }
+        SyncBackend::Gist => {
+            let url = format!("{}/gists/{}", github_api(), gist_id()?);
+            let (status, body) = curl("GET", &url, &gist_config()?, None)?;
+            check_status(status, &body)?;
+            let gist: serde_json::Value = serde_json::from_slice(&body)?;
+            let file = &gist["files"][GIST_FILE];
+            if file["truncated"].as_bool() == Some(true) {
+                return Err("the progress in the gist is too large to be read".into());
+            }
+            Ok(file["content"].as_str().map(str::to_string))
+        }
+        SyncBackend::S3 | SyncBackend::Webdav => {
+            let (status, body) = curl("GET", url()?, &credentials(backend)?, None)?;
+            if status == 404 {
+                return Ok(None);
+            }
+            check_status(status, &body)?;
+            Ok(Some(String::from_utf8(body)?))
+        }
+    }
+}
+
+// Store the encrypted progress in the backend
+fn upload(backend: SyncBackend, contents: &str) -> Result<(), Box<dyn Error>> {
+    match backend {
+        SyncBackend::File => {
+            let path = file_path()?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let temporary = path.with_extension("tmp");
+            fs::write(&temporary, contents)?;
+            fs::rename(temporary, path)?;
+        }
+        SyncBackend::Gist => {
+            let url = format!("{}/gists/{}", github_api(), gist_id()?);
+            let body = serde_json::json!({ "files": { GIST_FILE: { "content": contents } } });
+            let (status, response) = curl(
+                "PATCH",
+                &url,
+                &gist_config()?,
+                Some(body.to_string().as_bytes()),
+            )?;
+            check_status(status, &response)?;
+        }
+        SyncBackend::S3 | SyncBackend::Webdav => {
+            let (status, response) = curl(
+                "PUT",
+                url()?,
+                &credentials(backend)?,
+                Some(contents.as_bytes()),
+            )?;
+            check_status(status, &response)?;
+        }
+    }
+    Ok(())
+}
+
+fn file_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(PathBuf::from(CONFIG.sync.path.as_ref().ok_or(
+        "The `file` backend needs the `path` of the file to sync to",
+    )?))
+}
+
+fn url() -> Result<&'static str, Box<dyn Error>> {
+    Ok(CONFIG
+        .sync
+        .url
+        .as_deref()
+        .ok_or("The `s3` and `webdav` backends need the `url` to sync to")?)
+}
+
+fn gist_id() -> Result<&'static str, Box<dyn Error>> {
+    Ok(CONFIG
+        .sync
+        .gist
+        .as_deref()
+        .ok_or("The `gist` backend needs the ID of the `gist` to sync to")?)
+}
+
+// The API of GitHub, or of a GitHub Enterprise server given as the `url`
+fn github_api() -> &'static str {
+    CONFIG
+        .sync
+        .url
+        .as_deref()
+        .unwrap_or(GITHUB_API)
+        .trim_end_matches('/')
+}
+
+// The token to authenticate with, or the password or secret key
+fn token() -> Option<String> {
+    env::var(TOKEN_VAR)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .or_else(|| CONFIG.sync.token.clone())
+}
+
+// The curl configuration that authenticates with GitHub
+fn gist_config() -> Result<String, Box<dyn Error>> {
+    let token = token().ok_or(format!(
+        "The `gist` backend needs a GitHub token that may write gists, set `token` in `[sync]` or {TOKEN_VAR}"
+    ))?;
+    Ok(format!(
+        "header = \"Authorization: Bearer {}\"\nheader = \"Accept: application/vnd.github+json\"\n",
+        escape(&token)
+    ))
+}
+
+// The curl configuration that authenticates with the S3 or WebDAV server.
+// Requests to S3 are signed with the access key as the `user` and the
+// secret key as the token.
+fn credentials(backend: SyncBackend) -> Result<String, Box<dyn Error>> {
+    let mut config = String::new();
+    if let Some(user) = &CONFIG.sync.user {
+        config += &format!(
+            "user = \"{}:{}\"\n",
+            escape(user),
+            escape(&token().unwrap_or_default())
+        );
+    }
+    if backend == SyncBackend::S3 {
+        if CONFIG.sync.user.is_none() {
+            return Err("The `s3` backend needs the access key as the `user`".into());
+        }
+        let region = CONFIG.sync.region.as_deref().unwrap_or("us-east-1");
+        config += &format!("aws-sigv4 = \"aws:amz:{}:s3\"\n", escape(region));
+    }
+    Ok(config)
+}
+
+// Escape a value for a curl configuration file
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Long responses are likely error pages, which aren't worth showing
+fn check_status(status: u16, body: &[u8]) -> Result<(), Box<dyn Error>> {
+    if (200..300).contains(&status) {
+        return Ok(());
+    }
+    let body = String::from_utf8_lossy(body);
+    match body.trim() {
+        body if !body.is_empty() && body.len() <= 200 => {
+            Err(format!("the server responded with {status}: {body}").into())
+        }
+        _ => Err(format!("the server responded with {status}").into()),
+    }
+}
+
+// Make a request with curl, which deals with TLS, proxies and signing
+// requests to S3, returning the status and the body of the response. Its
+// configuration goes through stdin, so that credentials don't show up in the
+// list of processes.
+fn curl(
+    method: &str,
+    url: &str,
+    config: &str,
+    body: Option<&[u8]>,
+) -> Result<(u16, Vec<u8>), Box<dyn Error>> {
+    // Created only for us and removed when they're dropped
+    let response_file = NamedTempFile::new()?;
+    let mut body_file = NamedTempFile::new()?;
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--request", method])
+        .args(["--config", "-", "--write-out", "%{http_code}", "--output"])
+        .arg(response_file.path());
+    if let Some(body) = body {
+        body_file.write_all(body)?;
+        body_file.flush()?;
+        command
+            .arg("--data-binary")
+            .arg(format!("@{}", body_file.path().display()));
+    }
+    let child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(config.as_bytes())?;
+            }
+            let output = child.wait_with_output()?;
+            if output.status.success() {
+                let status = String::from_utf8_lossy(&output.stdout).trim().parse()?;
+                Ok((status, fs::read(response_file.path()).unwrap_or_default()))
+            } else {
+                Err(String::from_utf8_lossy(&output.stderr)
+                    .trim()
+                    .to_string()
+                    .into())
+            }
+        }
+        Err(e) => Err(format!("Failed to run curl, which is needed to sync: {e}").into()),
+    }
+}
+
+// Encrypt the text with XChaCha20-Poly1305, with a key derived from the
+// passphrase with PBKDF2-HMAC-SHA256, so that a wrong passphrase or
+// tampering is noticed. Fails if the system has no randomness to offer.
+pub fn encrypt(text: &str, passphrase: &str) -> Result<String, Box<dyn Error>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::fill(&mut salt)
+        .and_then(|()| getrandom::fill(&mut nonce))
+        .map_err(|e| format!("Failed to get random bytes to encrypt with: {e}"))?;
+    Ok(encrypt_with(text, passphrase, &salt, &nonce))
+}
+
+fn encrypt_with(text: &str, passphrase: &str, salt: &[u8], nonce: &[u8]) -> String {
+    let cipher = cipher(passphrase, salt);
+    let ciphertext = cipher
+        .encrypt(
+            &XNonce::try_from(nonce).expect("nonces have a fixed length"),
+            Payload {
+                msg: text.as_bytes(),
+                aad: header().as_bytes(),
+            },
+        )
+        .expect("any text can be encrypted");
+    format!(
+        "{}:{}:{}:{}\n",
+        header(),
+        hex::encode(salt),
+        hex::encode(nonce),
+        hex::encode(&ciphertext)
+    )
+}
+
+// The text encrypted by `encrypt` with the same passphrase
+pub fn decrypt(encrypted: &str, passphrase: &str) -> Result<String, Box<dyn Error>> {
+    let not_encrypted = || "the synced progress wasn't encrypted by rustlings".to_string();
+    let parts: Vec<&str> = encrypted.trim().split(':').collect();
+    let [MAGIC, version, salt, nonce, ciphertext] = parts[..] else {
+        return Err(not_encrypted().into());
+    };
+    if version.parse::<u32>()? > FORMAT_VERSION {
+        return Err("the synced progress was encrypted by a newer version of rustlings".into());
+    }
+    let salt = hex::decode(salt).ok().filter(|salt| salt.len() == SALT_LEN);
+    let nonce = hex::decode(nonce)
+        .ok()
+        .and_then(|nonce| XNonce::try_from(&nonce[..]).ok());
+    let ciphertext = hex::decode(ciphertext).ok();
+    let (Some(salt), Some(nonce), Some(ciphertext)) = (salt, nonce, ciphertext) else {
+        return Err(not_encrypted().into());
+    };
+    let text = cipher(passphrase, &salt)
+        .decrypt(
+            &nonce,
+            Payload {
+                msg: &ciphertext,
+                aad: header().as_bytes(),
+            },
+        )
+        .map_err(|_| {
+            "the synced progress can't be decrypted, the passphrase is wrong or it was changed"
+        })?;
+    Ok(String::from_utf8(text)?)
+}
+
+// What encrypted progress starts with, which is authenticated along with it
+fn header() -> String {
+    format!("{MAGIC}:{FORMAT_VERSION}")
+}
+
+// The cipher with the key derived from the passphrase
+fn cipher(passphrase: &str, salt: &[u8]) -> XChaCha20Poly1305 {
+    let key = pbkdf2_hmac_array::<Sha256, 32>(passphrase.as_bytes(), salt, ITERATIONS);
+    XChaCha20Poly1305::new_from_slice(&key).expect("the key has the length of a key")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encryption() {
+        let text = "completed = [\"intro1\"]\n";
+        let encrypted = encrypt_with(text, "secret", &[1; SALT_LEN], &[2; NONCE_LEN]);
+        assert!(encrypted.starts_with(&format!(
+            "rustlings-sync:2:{}:{}:",
+            "01".repeat(SALT_LEN),
+            "02".repeat(NONCE_LEN)
+        )));
+        assert!(!encrypted.contains("intro1"));
+        assert_eq!(decrypt(&encrypted, "secret").unwrap(), text);
+        assert_ne!(encrypt(text, "secret").unwrap(), encrypted);
+
+        let error = decrypt(&encrypted, "wrong").unwrap_err();
+        assert!(error.to_string().contains("the passphrase is wrong"));
+        let tampered = encrypted.replacen(":0101", ":0001", 1);
+        assert!(decrypt(&tampered, "secret").is_err());
+        assert!(decrypt("completed = []", "secret").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+// The colors telling what passed, what failed and what needs attention.
+// Green and red can't be told apart by many colorblind people, so there's a
+// palette they can, and one without colors at all, set with `--theme` or
+// `theme` in rustlings.toml. Colors that don't tell how something went, like
+// those of line numbers, are the same in every theme.
+use console::Style;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    // Green, red and yellow
+    Default,
+    // Blue, vermillion and orange from the Okabe-Ito palette, which stay
+    // apart with the common kinds of color blindness
+    Colorblind,
+    // No colors: failures are bold, and what needs attention is underlined
+    Monochrome,
+}
+
+pub fn parse_theme(value: &str) -> Result<Theme, String> {
+    match value {
+        "default" => Ok(Theme::Default),
+        "colorblind" => Ok(Theme::Colorblind),
+        "monochrome" => Ok(Theme::Monochrome),
+        _ => Err(format!(
+            "unknown theme `{value}`, expected `default`, `colorblind` or `monochrome`"
+        )),
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+// Use the theme from now on
+pub fn set(theme: Theme) {
+    let _ignored = THEME.set(theme);
+}
+
+pub fn current() -> Theme {
+    THEME.get().copied().unwrap_or(Theme::Default)
+}
+
+// What passed or is done
+pub fn success() -> Style {
+    match current() {
+        Theme::Default => Style::new().green(),
+        Theme::Colorblind => Style::new().color256(25),
+        Theme::Monochrome => Style::new(),
+    }
+}
+
+// What failed
+pub fn failure() -> Style {
+    match current() {
+        Theme::Default => Style::new().red(),
+        Theme::Colorblind => Style::new().color256(166),
+        Theme::Monochrome => Style::new().bold(),
+    }
+}
+
+// What needs attention, like warnings and exercises that are pending
+pub fn warning() -> Style {
+    match current() {
+        Theme::Default => Style::new().yellow(),
+        Theme::Colorblind => Style::new().color256(214),
+        Theme::Monochrome => Style::new().underlined(),
+    }
+}
+
+// The name of the theme, which the dashboard of `rustlings serve` is styled by
+pub fn name() -> &'static str {
+    match current() {
+        Theme::Default => "default",
+        Theme::Colorblind => "colorblind",
+        Theme::Monochrome => "monochrome",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme() {
+        assert_eq!(parse_theme("colorblind"), Ok(Theme::Colorblind));
+        assert_eq!(parse_theme("monochrome"), Ok(Theme::Monochrome));
+        assert!(parse_theme("dark").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::exercise::Exercise;
+use crate::output::symbol;
+use crate::progress::Progress;
+use crate::stats::format_duration;
+use console::style;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+// What a topic is about, which watch mode shows once its last exercise passes.
+// Topics are described in the `topics` array of info.toml, by the name of
+// the directory their exercises live in.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Topic {
+    pub name: String,
+    // What working through the exercises of the topic taught
+    #[serde(default)]
+    pub summary: Option<String>,
+    // Links to read more about the topic
+    #[serde(default)]
+    pub further_reading: Vec<String>,
+}
+
+// The exercises and topics watch mode works on. Only watch mode celebrates
+// finished topics, so that `verify` stays quiet about them.
+static WATCHED: Mutex<Option<(Vec<Exercise>, Vec<Topic>)>> = Mutex::new(None);
+
+// Show a summary whenever a topic of these exercises is finished,
+// replacing the ones from before info.toml was reloaded
+pub fn watch(exercises: &[Exercise], topics: &[Topic]) {
+    *WATCHED.lock().unwrap() = Some((exercises.to_vec(), topics.to_vec()));
+}
+
+// The exercises grouped by their topic, with the topics in the order
+// they first appear in info.toml
+pub fn by_topic<'a>(
+    exercises: impl IntoIterator<Item = &'a Exercise>,
+) -> Vec<(String, Vec<&'a Exercise>)> {
+    let mut topics: Vec<(String, Vec<&Exercise>)> = Vec::new();
+    for exercise in exercises {
+        let topic = exercise.topic();
+        match topics.iter_mut().find(|(name, _)| *name == topic) {
+            Some((_, in_topic)) => in_topic.push(exercise),
+            None => topics.push((topic, vec![exercise])),
+        }
+    }
+    topics
+}
+
+// Print the summary of the topic of the exercise that was just completed,
+// if that was the last one of it
+pub fn completed(exercise: &Exercise) {
+    let watched = WATCHED.lock().unwrap();
+    let Some((exercises, topics)) = watched.as_ref() else {
+        return;
+    };
+    let name = exercise.topic();
+    let in_topic: Vec<&Exercise> = exercises.iter().filter(|e| e.topic() == name).collect();
+    let progress = Progress::load();
+    if !in_topic.iter().all(|e| progress.is_completed(e)) {
+        return;
+    }
+    let topic = topics.iter().find(|t| t.name == name);
+    print!("{}", summary(&name, topic, &in_topic, &progress));
+}
+
+// The summary of a finished topic: how working through it went,
+// what it covered, and where to read further
+fn summary(
+    name: &str,
+    topic: Option<&Topic>,
+    exercises: &[&Exercise],
+    progress: &Progress,
+) -> String {
+    let star = symbol("★", "*");
+    let mut text = format!(
+        "\n{}\n",
+        style(format!("{star} You finished {name}! {star}"))
+            .bold()
+            .green()
+    );
+
+    let without_hints = exercises
+        .iter()
+        .filter(|e| progress.completed_without_hints(e))
+        .count();
+    let seconds: u64 = exercises
+        .iter()
+        .filter_map(|e| progress.stats.get(&e.name))
+        .map(|stats| stats.time_spent())
+        .sum();
+    let count = |n: usize| if n == 1 { "exercise" } else { "exercises" };
+    text.push_str(&format!(
+        "  {} {}, {without_hints} without hints, in {}\n",
+        exercises.len(),
+        count(exercises.len()),
+        format_duration(seconds)
+    ));
+
+    match topic.and_then(|t| t.summary.as_deref()) {
+        Some(summary) => text.push_str(&format!("\n  What you learned: {summary}\n")),
+        None => {
+            // Without a summary, the tags of the exercises tell what they were about
+            let mut tags: Vec<&str> = Vec::new();
+            for tag in exercises.iter().flat_map(|e| &e.tags) {
+                if !tags.contains(&tag.as_str()) {
+                    tags.push(tag);
+                }
+            }
+            if !tags.is_empty() {
+                text.push_str(&format!("\n  Covered: {}\n", tags.join(", ")));
+            }
+        }
+    }
+
+    let further_reading = topic
+        .map(|t| t.further_reading.as_slice())
+        .unwrap_or_default();
+    if !further_reading.is_empty() {
+        text.push_str("\n  Further reading:\n");
+        for link in further_reading {
+            text.push_str(&format!("    {} {link}\n", symbol("•", "-")));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    const INFO: &str = r#"
+[[exercises]]
+name = "structs1"
+path = "exercises/structs/structs1.rs"
+mode = "compile"
+hint = ""
+tags = ["structs", "fields"]
+
+[[exercises]]
+name = "structs2"
+path = "exercises/structs/structs2.rs"
+mode = "compile"
+hint = ""
+tags = ["structs", "update syntax"]
+
+[[topics]]
+name = "structs"
+summary = "Grouping data into structs"
+further_reading = ["https://doc.rust-lang.org/book/ch05-00-structs.html"]
+"#;
+
+    #[test]
+    fn test_by_topic() {
+        let list: ExerciseList = toml::from_str(&format!(
+            "{INFO}{}",
+            r#"
+[[exercises]]
+name = "quiz1"
+path = "exercises/quiz1.rs"
+mode = "test"
+hint = ""
+
+[[exercises]]
+name = "structs3"
+path = "exercises/structs/structs3.rs"
+mode = "compile"
+hint = ""
+"#
+        ))
+        .unwrap();
+        let names: Vec<(String, Vec<&str>)> = by_topic(&list.exercises)
+            .into_iter()
+            .map(|(topic, in_topic)| (topic, in_topic.iter().map(|e| e.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("structs".into(), vec!["structs1", "structs2", "structs3"]),
+                ("quiz1".into(), vec!["quiz1"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        console::set_colors_enabled(false);
+        let list: ExerciseList = toml::from_str(INFO).unwrap();
+        let exercises: Vec<&Exercise> = list.exercises.iter().collect();
+        let mut progress = Progress::default();
+        progress.complete(exercises[0]);
+        progress.see_hint_level("structs2", 1);
+        progress.complete(exercises[1]);
+
+        let untold = summary("structs", None, &exercises, &progress);
+        assert!(untold.contains("You finished structs!"));
+        assert!(untold.contains("2 exercises, 1 without hints, in 0m 00s"));
+        assert!(untold.contains("Covered: structs, fields, update syntax"));
+        assert!(!untold.contains("Further reading"));
+
+        let told = summary("structs", list.topics.first(), &exercises, &progress);
+        assert!(told.contains("What you learned: Grouping data into structs"));
+        assert!(!told.contains("Covered"));
+        assert!(told.contains("Further reading:"));
+        assert!(told.contains("https://doc.rust-lang.org/book/ch05-00-structs.html"));
+    }
+}
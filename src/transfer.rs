@@ -0,0 +1,200 @@
+// `rustlings export-progress` and `import-progress` move the progress to
+// another machine, like from a work laptop to a desktop at home. The export
+// holds the state file along with the hashes of the exercises as they were,
+// so that importing it can tell when the exercises on this machine differ
+// from the exported ones, or when this machine has progress the export
+// doesn't, and won't overwrite either without `--force`.
+use crate::exercise::Exercise;
+use crate::progress::{now, Progress};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// Exports of later versions might not be understood
+const VERSION: u32 = 1;
+
+// What is exported. Tables have to come after plain values to be
+// serialized to TOML, so maps are kept at the end.
+#[derive(Serialize, Deserialize, Debug)]
+struct Export {
+    version: u32,
+    // In seconds since the Unix epoch
+    exported_at: u64,
+    // The SHA-256 of the sources of every exercise, by its name
+    hashes: BTreeMap<String, String>,
+    progress: Progress,
+}
+
+// Write the progress and the hashes of the exercises to the file
+pub fn export(exercises: &[Exercise], file: &Path) -> Result<(), Box<dyn Error>> {
+    let export = Export {
+        version: VERSION,
+        exported_at: now(),
+        hashes: exercises
+            .iter()
+            .filter_map(|e| Some((e.name.clone(), hash(e)?)))
+            .collect(),
+        progress: Progress::load(),
+    };
+    fs::write(file, toml::to_string(&export)?)?;
+    success!(
+        "{}",
+        format!(
+            "Exported the progress of {} completed exercises to {}",
+            export.progress.completed.len(),
+            file.display()
+        )
+    );
+    Ok(())
+}
+
+// Replace the progress with the one exported to the file, unless that
+// conflicts with the exercises or the progress on this machine and
+// `force` isn't given. Returns whether it was imported.
+pub fn import(exercises: &[Exercise], file: &Path, force: bool) -> Result<bool, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+    let export: Export = toml::from_str(&contents).map_err(|e| {
+        format!(
+            "{} isn't an export of rustlings progress: {e}",
+            file.display()
+        )
+    })?;
+    if export.version > VERSION {
+        return Err(format!(
+            "{} was exported by a newer version of rustlings, update to import it",
+            file.display()
+        )
+        .into());
+    }
+
+    let conflicts = conflicts(&export, exercises, &Progress::load(), hash);
+    if !conflicts.is_empty() {
+        let heading = if force {
+            "Importing anyway, even though"
+        } else {
+            "Not importing, since"
+        };
+        warn!("{}", format!("{heading}:"));
+        for conflict in &conflicts {
+            println!("  {conflict}");
+        }
+        if !force {
+            println!(
+                "Copy your exercises over first, or pass `--force` to replace the progress on this machine."
+            );
+            return Ok(false);
+        }
+    }
+    export.progress.save()?;
+    success!(
+        "{}",
+        format!(
+            "Imported the progress of {} completed exercises from {}",
+            export.progress.completed.len(),
+            file.display()
+        )
+    );
+    Ok(true)
+}
+
+// The SHA-256 of all sources of the exercise, if they can be read
+fn hash(exercise: &Exercise) -> Option<String> {
+    let mut contents = Vec::new();
+    for source in exercise.sources() {
+        contents.extend(fs::read(source).ok()?);
+    }
+    Some(hex::encode(Sha256::digest(&contents)))
+}
+
+// Why importing the export would lose something: exercises that differ from
+// the exported ones, and exercises completed here but not in the export
+fn conflicts(
+    export: &Export,
+    exercises: &[Exercise],
+    local: &Progress,
+    hash: impl Fn(&Exercise) -> Option<String>,
+) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    for exercise in exercises {
+        let Some(exported) = export.hashes.get(&exercise.name) else {
+            continue;
+        };
+        if hash(exercise).as_ref() != Some(exported) {
+            conflicts.push(format!(
+                "{} differs from the exported one",
+                exercise.path.display()
+            ));
+        }
+    }
+    for name in local.completed.difference(&export.progress.completed) {
+        conflicts.push(format!("{name} is completed here, but not in the export"));
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    #[test]
+    fn test_conflicts() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+[[exercises]]
+name = "intro1"
+path = "exercises/intro/intro1.rs"
+mode = "compile"
+hint = ""
+
+[[exercises]]
+name = "intro2"
+path = "exercises/intro/intro2.rs"
+mode = "compile"
+hint = ""
+"#,
+        )
+        .unwrap();
+        let mut export = Export {
+            version: VERSION,
+            exported_at: 0,
+            hashes: BTreeMap::from([
+                ("intro1".to_string(), "same".to_string()),
+                ("intro2".to_string(), "exported".to_string()),
+            ]),
+            progress: Progress::default(),
+        };
+        export.progress.completed.insert("intro1".to_string());
+        let mut local = Progress::default();
+        local.completed.insert("intro1".to_string());
+        local.completed.insert("intro2".to_string());
+
+        let hash = |e: &Exercise| {
+            Some(
+                if e.name == "intro1" {
+                    "same"
+                } else {
+                    "changed"
+                }
+                .to_string(),
+            )
+        };
+        assert_eq!(
+            conflicts(&export, &list.exercises, &local, hash),
+            [
+                "exercises/intro/intro2.rs differs from the exported one",
+                "intro2 is completed here, but not in the export",
+            ]
+        );
+
+        export
+            .hashes
+            .insert("intro2".to_string(), "changed".to_string());
+        export.progress.completed.insert("intro2".to_string());
+        assert!(conflicts(&export, &list.exercises, &local, hash).is_empty());
+    }
+}
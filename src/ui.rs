@@ -1,33 +1,43 @@
 macro_rules! warn {
     ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
         let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("!").red(), style(formatstr).red());
-        } else {
-            println!(
-                "{} {}",
-                style(Emoji("⚠️ ", "!")).red(),
-                style(formatstr).red()
-            );
-        }
+        println!(
+            "{} {}",
+            crate::theme::failure().apply_to(crate::output::symbol("⚠️ ", "!")),
+            crate::theme::failure().apply_to(formatstr)
+        );
     }};
 }
 
 macro_rules! success {
     ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
         let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("✓").green(), style(formatstr).green());
-        } else {
+        if !crate::output::quiet() {
             println!(
                 "{} {}",
-                style(Emoji("✅", "✓")).green(),
-                style(formatstr).green()
+                crate::theme::success().apply_to(crate::output::symbol("✅", "+")),
+                crate::theme::success().apply_to(formatstr)
             );
         }
     }};
 }
+
+// Like `println!`, but only if the output isn't quiet
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !crate::output::quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+// The message with the given ID in the language of the user,
+// with the given arguments filled in. See i18n.rs.
+macro_rules! t {
+    ($id:literal) => {
+        crate::i18n::message($id, &[])
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        crate::i18n::message($id, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
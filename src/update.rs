@@ -0,0 +1,155 @@
+use crate::embedded::EMBEDDED_FILES;
+use crate::run::original_path;
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+// What updating did to a single file
+#[derive(PartialEq, Debug)]
+enum Change {
+    // The file is new in this version of rustlings
+    Added,
+    // The file was replaced, as it hadn't been changed since it was set up
+    Updated,
+    // The file was left alone, as it has been worked on
+    Skipped,
+    Unchanged,
+}
+
+// Check for a newer release of rustlings, and update the exercises to the
+// ones embedded into this binary. Exercises that have been worked on are
+// skipped, but their new version is what `rustlings reset` restores.
+pub fn update(check_release: bool) -> io::Result<()> {
+    if check_release {
+        match latest_release() {
+            Some(latest) if is_newer(&latest, crate::VERSION) => {
+                println!(
+                    "rustlings {latest} is available, you have {}.",
+                    crate::VERSION
+                );
+                println!("Run `cargo install --force rustlings` and then `rustlings update` again to get its exercises.");
+                println!();
+            }
+            Some(_) => println!("You have the latest release of rustlings."),
+            None => println!(
+                "Couldn't check for a newer release of rustlings, updating the exercises anyway."
+            ),
+        }
+    }
+
+    let mut changes: Vec<(&str, Change)> = Vec::new();
+    for (name, contents) in EMBEDDED_FILES {
+        let path = Path::new(name);
+        let original = original_path(Path::new(""), path);
+        let change = plan(
+            fs::read(path).ok().as_deref(),
+            fs::read(&original).ok().as_deref(),
+            contents,
+        );
+        match change {
+            Change::Added | Change::Updated => write(path, contents)?,
+            Change::Skipped | Change::Unchanged => {}
+        }
+        // `reset` restores the new version from now on
+        write(&original, contents)?;
+        changes.push((name, change));
+    }
+
+    let mut up_to_date = true;
+    for (kind, heading) in [
+        (Change::Added, "Added:"),
+        (Change::Updated, "Updated:"),
+        (
+            Change::Skipped,
+            "Skipped, as you've worked on them (`rustlings reset` gets the new version):",
+        ),
+    ] {
+        let files: Vec<&str> = changes
+            .iter()
+            .filter(|(_, change)| *change == kind)
+            .map(|(name, _)| *name)
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        up_to_date = false;
+        println!("{heading}");
+        for file in files {
+            println!("  {file}");
+        }
+    }
+    if up_to_date {
+        println!("The exercises are up to date.");
+    }
+    // `reset` doesn't restore it, like when exercises were skipped in it
+    if changes.contains(&("info.toml", Change::Skipped)) {
+        println!(
+            "info.toml keeps your changes, the new version with the new exercises is in {}.",
+            original_path(Path::new(""), Path::new("info.toml")).display()
+        );
+    }
+    Ok(())
+}
+
+// What to do with a file, given its current contents, the contents it was
+// set up with, and its contents in this version of rustlings. Like the
+// exercises, info.toml can be changed, by skipping exercises in it or with
+// `rustlings new-exercise`.
+fn plan(current: Option<&[u8]>, original: Option<&[u8]>, new: &[u8]) -> Change {
+    match current {
+        None => Change::Added,
+        Some(current) if current == new => Change::Unchanged,
+        Some(current) if Some(current) == original => Change::Updated,
+        Some(_) => Change::Skipped,
+    }
+}
+
+fn write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+// The version of the latest release of rustlings on crates.io
+fn latest_release() -> Option<String> {
+    let output = Command::new("cargo")
+        .args(["search", "rustlings", "--limit", "1"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = Regex::new(r#"(?m)^rustlings = "([^"]+)""#).unwrap();
+    Some(version.captures(&stdout)?[1].to_string())
+}
+
+// Whether the version is newer than the other one, comparing their numbers
+fn is_newer(version: &str, other: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version.split('.').map(|n| n.parse().unwrap_or(0)).collect()
+    };
+    numbers(version) > numbers(other)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("5.6.0", "5.5.1"));
+        assert!(is_newer("5.10.0", "5.9.0"));
+        assert!(!is_newer("5.5.1", "5.5.1"));
+        assert!(!is_newer("4.8.0", "5.5.1"));
+    }
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(plan(None, None, b"new"), Change::Added);
+        assert_eq!(plan(Some(b"new"), Some(b"old"), b"new"), Change::Unchanged);
+        assert_eq!(plan(Some(b"old"), Some(b"old"), b"new"), Change::Updated);
+        assert_eq!(plan(Some(b"mine"), Some(b"old"), b"new"), Change::Skipped);
+        assert_eq!(plan(Some(b"old"), None, b"new"), Change::Skipped);
+    }
+}
@@ -1,43 +1,322 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::exercise::{CompiledExercise, Exercise, ExerciseOutput, Mode, State};
+use crate::hooks::{self, Event};
+use crate::output;
+use crate::progress::{record_attempt, record_completion, Progress};
+use crate::state::{self, DoneStrategy};
+use crate::theme;
+use crate::topics;
 use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::env;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+// The exercise at which verification stopped
+pub struct Stopped<'a> {
+    pub exercise: &'a Exercise,
+    // Whether the exercise passes, and only still has its `I AM NOT DONE` marker
+    pub passed: bool,
+}
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// With more than one job, exercises are first checked concurrently, and only
+// the first one that doesn't pass is compiled again to report its output.
+// To keep going, all exercises are verified, and the failures with their
+// output and a summary per topic are printed at the end instead.
+// The first exercise that isn't done is still returned.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
     verbose: bool,
     success_hints: bool,
-) -> Result<(), &'a Exercise> {
+    jobs: usize,
+    keep_going: bool,
+) -> Result<(), Stopped<'a>> {
     let (num_done, total) = progress;
+    let exercises: Vec<&Exercise> = exercises.into_iter().collect();
+    let stats = Progress::load().stats;
+    let mut failing: BTreeSet<&str> = exercises
+        .iter()
+        .filter(|e| stats.get(&e.name).is_some_and(|s| s.failing))
+        .map(|e| e.name.as_str())
+        .collect();
+    let mut tally = Tally {
+        done: num_done,
+        failing: failing.len(),
+        total,
+    };
+
     let bar = ProgressBar::new(total as u64);
-    let mut percentage = num_done as f32 / total as f32 * 100.0;
-    bar.set_style(ProgressStyle::default_bar()
-        .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-        .progress_chars("#>-")
-    );
-    bar.set_position(num_done as u64);
-    bar.set_message(format!("({:.1} %)", percentage));
-
-    for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
+    bar.set_style(ProgressStyle::default_bar().template("Progress: {msg}"));
+    if output::quiet() || output::a11y() {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    bar.set_message(tally.render());
+
+    let prechecked = if jobs > 1 {
+        precheck(&exercises, jobs, verbose, keep_going)
+    } else {
+        vec![false; exercises.len()]
+    };
+
+    let mut stopped = None;
+    let mut outcomes: Vec<(&Exercise, Outcome)> = Vec::new();
+    let mut failures: Vec<(&Exercise, ExerciseOutput)> = Vec::new();
+    for (exercise, prechecked) in exercises.into_iter().zip(prechecked) {
+        let compile_result = if prechecked {
+            Ok(true)
+        } else if keep_going {
+            check(exercise).map_err(|output| failures.push((exercise, output)))
+        } else {
+            match exercise.mode {
+                Mode::Test | Mode::Property | Mode::Doctest | Mode::Project => {
+                    compile_and_test(exercise, RunMode::Interactive, verbose, success_hints)
+                }
+                // Interactive exercises would wait for input, so they are only compiled
+                Mode::Compile | Mode::Fmt if exercise.interactive => {
+                    compile_only(exercise, success_hints)
+                }
+                Mode::Compile | Mode::Fmt => compile_and_run_interactively(exercise, success_hints),
+                Mode::Clippy => compile_only(exercise, success_hints),
+                Mode::Bench | Mode::CompileFail | Mode::Plugin => {
+                    compile_and_run_interactively(exercise, success_hints)
+                }
+            }
         };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+        if !prechecked {
+            record_attempt(exercise, compile_result.is_err());
+        }
+        if compile_result.is_err() {
+            hooks::run(Event::ExerciseFailed(exercise));
+            failing.insert(&exercise.name);
+        } else {
+            failing.remove(exercise.name.as_str());
+        }
+        let outcome = match compile_result {
+            Ok(true) => Outcome::Done,
+            Ok(false) => Outcome::Pending,
+            Err(()) => Outcome::Failing,
+        };
+        if outcome == Outcome::Done {
+            if record_completion(exercise) {
+                hooks::run(Event::ExercisePassed(exercise));
+                topics::completed(exercise);
+            }
+            tally.done += 1;
+        }
+        tally.failing = failing.len();
+        bar.set_message(tally.render());
+        if output::a11y() {
+            let status = match outcome {
+                Outcome::Done => t!("a11y-passed"),
+                Outcome::Pending => t!("a11y-pending"),
+                Outcome::Failing => t!("a11y-failed"),
+            };
+            info!(
+                "{}",
+                t!("a11y-status", name = exercise.name, status = status)
+            );
+            info!("{}", t!("a11y-progress", counts = tally.counts()));
+        }
+        outcomes.push((exercise, outcome));
+
+        if outcome != Outcome::Done {
+            let passed = outcome == Outcome::Pending;
+            stopped.get_or_insert(Stopped { exercise, passed });
+            if !keep_going {
+                break;
+            }
+        }
+    }
+    if keep_going {
+        print_failures(&failures);
+        print_summary(&outcomes);
+    }
+    match stopped {
+        Some(stopped) => Err(stopped),
+        None => Ok(()),
+    }
+}
+
+// How verifying a single exercise went
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Outcome {
+    Done,
+    // The exercise passes, but still has its `I AM NOT DONE` marker
+    Pending,
+    Failing,
+}
+
+// The width of the progress bar, in characters
+const BAR_WIDTH: usize = 60;
+
+// The exercises that are done and failing, out of all exercises
+struct Tally {
+    done: usize,
+    failing: usize,
+    total: usize,
+}
+
+impl Tally {
+    // The progress bar, with a segment for the exercises that are done,
+    // one for those that are failing, and one for those that weren't
+    // attempted yet, followed by the counts
+    fn render(&self) -> String {
+        let total = self.total.max(1);
+        let done_width = self.done * BAR_WIDTH / total;
+        let failing_width = (self.done + self.failing) * BAR_WIDTH / total - done_width;
+        let rest_width = BAR_WIDTH - done_width - failing_width;
+        format!(
+            "[{}{}{}] {}",
+            theme::success().apply_to("#".repeat(done_width)),
+            theme::failure().apply_to("x".repeat(failing_width)),
+            style("-".repeat(rest_width)).dim(),
+            self.counts()
+        )
+    }
+
+    // The counts of the progress bar, which is all screen readers get
+    fn counts(&self) -> String {
+        let mut counts = format!("{}/{}", self.done, self.total);
+        if self.failing > 0 {
+            counts.push_str(&format!(", {}", t!("failing", count = self.failing)));
+        }
+        counts.push_str(&format!(
+            " ({:.1} %)",
+            self.done as f32 / self.total.max(1) as f32 * 100.0
+        ));
+        counts
+    }
+}
+
+// Compile and run the exercise, only showing a spinner meanwhile.
+// Returns whether the exercise is done, or the output of the compiler or of
+// the exercise if it fails. Like when they are verified one at a time,
+// interactive and Clippy exercises are only compiled.
+pub fn check(exercise: &Exercise) -> Result<bool, ExerciseOutput> {
+    let progress_bar = output::spinner(t!("checking", exercise = exercise));
+    let result = exercise.compile().and_then(|compilation| {
+        if exercise.interactive || matches!(exercise.mode, Mode::Clippy) {
+            Ok(())
+        } else {
+            compilation.run().map(|_| ())
+        }
+    });
+    progress_bar.finish_and_clear();
+    result.map(|()| exercise.done_if_passing())
+}
+
+// Print every exercise that failed, along with its output
+fn print_failures(failures: &[(&Exercise, ExerciseOutput)]) {
+    if failures.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", t!("failures", count = failures.len()));
+    for (exercise, output) in failures {
+        println!();
+        warn!("{}", t!("failed", exercise = exercise));
+        for text in [&output.stdout, &output.stderr] {
+            if !text.trim().is_empty() {
+                println!("{}", text.trim_end());
+            }
+        }
+    }
+}
+
+// Print how verifying went per topic, in the order the topics came up
+fn print_summary(outcomes: &[(&Exercise, Outcome)]) {
+    println!();
+    println!("{}", t!("summary"));
+    for (topic, in_topic) in topics::by_topic(outcomes.iter().map(|(e, _)| *e)) {
+        let outcome_of = |exercise: &Exercise| {
+            outcomes
+                .iter()
+                .find(|(e, _)| e.name == exercise.name)
+                .map(|(_, o)| *o)
+        };
+        let names = |outcome: Outcome| -> Vec<&str> {
+            in_topic
+                .iter()
+                .filter(|e| outcome_of(e) == Some(outcome))
+                .map(|e| e.name.as_str())
+                .collect()
+        };
+        let (failing, pending) = (names(Outcome::Failing), names(Outcome::Pending));
+        let done = in_topic.len() - failing.len() - pending.len();
+        let line = t!(
+            "summary-topic",
+            topic = topic,
+            done = done,
+            total = in_topic.len()
+        );
+        if !failing.is_empty() {
+            println!("  {}", theme::failure().apply_to(line));
+        } else if !pending.is_empty() {
+            println!("  {}", theme::warning().apply_to(line));
+        } else {
+            println!("  {}", theme::success().apply_to(line));
+        }
+        if !failing.is_empty() {
+            println!("    {}", t!("summary-failing", names = failing.join(", ")));
+        }
+        if !pending.is_empty() {
+            println!("    {}", t!("summary-pending", names = pending.join(", ")));
         }
-        percentage += 100.0 / total as f32;
-        bar.inc(1);
-        bar.set_message(format!("({:.1} %)", percentage));
     }
-    Ok(())
+}
+
+// Silently compile and run the given exercises on `jobs` threads,
+// returning for each of them whether it is known to pass.
+// Exercises after the first failing one are not checked, since
+// verification is going to stop there anyway, unless it keeps going.
+fn precheck(exercises: &[&Exercise], jobs: usize, verbose: bool, keep_going: bool) -> Vec<bool> {
+    let next = AtomicUsize::new(0);
+    let first_failure = AtomicUsize::new(usize::MAX);
+    let passed: Vec<AtomicBool> = exercises.iter().map(|_| AtomicBool::new(false)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let stopped = !keep_going && i > first_failure.load(Ordering::SeqCst);
+                if i >= exercises.len() || stopped {
+                    break;
+                }
+                if passes_silently(exercises[i], verbose) {
+                    passed[i].store(true, Ordering::SeqCst);
+                } else {
+                    first_failure.fetch_min(i, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+
+    passed.into_iter().map(AtomicBool::into_inner).collect()
+}
+
+// Whether the exercise is done and passes without having to show anything.
+// Clippy exercises share a single Cargo.toml, and bench exercises would be
+// slowed down by the others, and plugins might not expect to be run several
+// times at once, so they are never checked concurrently.
+// Tests are left to the regular check when their output should be shown.
+fn passes_silently(exercise: &Exercise, verbose: bool) -> bool {
+    match exercise.mode {
+        Mode::Clippy | Mode::Bench | Mode::Plugin => return false,
+        Mode::Test | Mode::Property | Mode::Doctest | Mode::Project if verbose => return false,
+        _ => {}
+    }
+    if !exercise.done_if_passing() {
+        return false;
+    }
+    match exercise.compile() {
+        Ok(compilation) => exercise.interactive || compilation.run().is_ok(),
+        Err(_) => false,
+    }
 }
 
 enum RunMode {
@@ -53,9 +332,7 @@ pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
 
 // Invoke the rust compiler without running the resulting binary
 fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = output::spinner(t!("compiling", exercise = exercise));
 
     let _ = compile(exercise, &progress_bar)?;
     progress_bar.finish_and_clear();
@@ -65,35 +342,40 @@ fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
 fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = output::spinner(t!("compiling", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(t!("running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     let output = match result {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            warn!("{}", t!("ran-with-errors", exercise = exercise));
             println!("{}", output.stdout);
             println!("{}", output.stderr);
             return Err(());
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    Ok(prompt_for_completion(
+        exercise,
+        Some(output.stdout),
+        success_hints,
+    ))
 }
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_test(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    verbose: bool,
+    success_hints: bool,
+) -> Result<bool, ()> {
+    let progress_bar = output::spinner(t!("testing", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
     let result = compilation.run();
@@ -111,11 +393,12 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
             }
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            warn!("{}", t!("testing-failed", exercise = exercise));
             println!("{}", output.stdout);
+            // Like the diagnostic of a test binary that timed out
+            if !output.stderr.is_empty() {
+                println!("{}", output.stderr);
+            }
             Err(())
         }
     }
@@ -123,9 +406,9 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
+    progress_bar: &ProgressBar,
 ) -> Result<CompiledExercise<'a>, ()> {
     let compilation_result = exercise.compile();
 
@@ -133,39 +416,67 @@ fn compile<'a, 'b>(
         Ok(compilation) => Ok(compilation),
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            warn!("{}", t!("compiling-failed", exercise = exercise));
             println!("{}", output.stderr);
             Err(())
         }
     }
 }
 
-fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
+fn prompt_for_completion(
+    exercise: &Exercise,
+    prompt_output: Option<String>,
+    success_hints: bool,
+) -> bool {
     let context = match exercise.state() {
         State::Done => return true,
         State::Pending(context) => context,
     };
     match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
+        Mode::Compile | Mode::Fmt => success!("{}", t!("ran", exercise = exercise)),
+        Mode::Test | Mode::Property | Mode::Doctest | Mode::Project => {
+            success!("{}", t!("tested", exercise = exercise))
+        }
+        Mode::Clippy => success!("{}", t!("compiled", exercise = exercise)),
+        Mode::Bench => success!("{}", t!("benchmarked", exercise = exercise)),
+        Mode::CompileFail => success!("{}", t!("rejected", exercise = exercise)),
+        Mode::Plugin => success!("{}", t!("verified", exercise = exercise)),
+    }
+    let marked = state::strategy() == DoneStrategy::State;
+    if output::quiet() {
+        if marked {
+            warn!("{}", t!("still-unmarked", exercise = exercise));
+        } else {
+            warn!("{}", t!("still-marked", exercise = exercise));
+        }
+        return false;
     }
 
-    let no_emoji = env::var("NO_EMOJI").is_ok();
+    let no_emoji = output::ascii();
 
     let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
+        t!("clippy-happy")
     } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
+        t!("clippy-happy-emoji")
     };
 
     let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
+        Mode::Compile => t!("compiles"),
+        Mode::Test => t!("tests-pass"),
+        Mode::Property => t!("properties-hold"),
         Mode::Clippy => clippy_success_msg,
+        Mode::Bench => t!("fast-enough"),
+        Mode::Fmt => t!("well-formatted"),
+        Mode::Doctest => t!("doc-tests-pass"),
+        Mode::Project => t!("project-tests-pass"),
+        Mode::Plugin => t!(
+            "plugin-passes",
+            plugin = exercise.plugin.as_deref().unwrap_or_default()
+        ),
+        Mode::CompileFail => t!(
+            "fails-as-expected",
+            code = exercise.expected_error.as_deref().unwrap_or_default()
+        ),
     };
     println!();
     if no_emoji {
@@ -176,24 +487,35 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
     println!();
 
     if let Some(output) = prompt_output {
-        println!("Output:");
+        println!("{}", t!("output"));
         println!("{}", separator());
         println!("{output}");
         println!("{}", separator());
         println!();
     }
     if success_hints {
-        println!("Hints:");
+        println!("{}", t!("hints"));
         println!("{}", separator());
         println!("{}", exercise.hint);
         println!("{}", separator());
         println!();
     }
 
-    println!("You can keep working on this exercise,");
+    println!("{}", t!("keep-working"));
+    if marked {
+        println!(
+            "{}",
+            t!(
+                "mark-done",
+                command = style("done").bold(),
+                name = exercise.name
+            )
+        );
+        return false;
+    }
     println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
+        "{}",
+        t!("remove-marker", marker = style("`I AM NOT DONE`").bold())
     );
     println!();
     for context_line in context {
@@ -217,3 +539,55 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
 fn separator() -> console::StyledObject<&'static str> {
     style("====================").bold()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_tally() {
+        console::set_colors_enabled(false);
+        let tally = Tally {
+            done: 30,
+            failing: 15,
+            total: 60,
+        };
+        assert_eq!(
+            tally.render(),
+            format!(
+                "[{}{}{}] 30/60, 15 failing (50.0 %)",
+                "#".repeat(30),
+                "x".repeat(15),
+                "-".repeat(15)
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_full_tally() {
+        console::set_colors_enabled(false);
+        let done = Tally {
+            done: 2,
+            failing: 0,
+            total: 2,
+        };
+        assert_eq!(
+            done.render(),
+            format!("[{}] 2/2 (100.0 %)", "#".repeat(BAR_WIDTH))
+        );
+        let failing = Tally {
+            done: 1,
+            failing: 1,
+            total: 2,
+        };
+        assert_eq!(failing.counts(), "1/2, 1 failing (50.0 %)");
+        assert_eq!(
+            failing.render(),
+            format!(
+                "[{}{}] 1/2, 1 failing (50.0 %)",
+                "#".repeat(BAR_WIDTH / 2),
+                "x".repeat(BAR_WIDTH / 2)
+            )
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// Exercises compiled to WebAssembly, with `target` in info.toml, for a
+// chapter about it. Exercises for WASI targets, like `wasm32-wasip1`, are
+// run with a WebAssembly runtime, wasmtime by default, which keeps them from
+// reaching anything they aren't given. The runtime itself runs within the
+// memory limit, and in the sandbox with `--untrusted`. Ones for
+// `wasm32-unknown-unknown` have nothing to run them with, so they only
+// have to compile.
+use crate::config::CONFIG;
+use crate::sandbox::{self, Sandbox};
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// How exercises for WASI targets are run, followed by the module and the
+// arguments for it
+pub const DEFAULT_RUNTIME: &str = "wasmtime run";
+
+// Whether exercises can be compiled for the target
+pub fn is_supported(target: &str) -> bool {
+    target.starts_with("wasm32-")
+}
+
+// Whether exercises compiled for the target can be run
+pub fn is_runnable(target: &str) -> bool {
+    target.starts_with("wasm32-wasi")
+}
+
+// The command line of the runtime, as configured. The rustlings.toml of
+// untrusted exercises mustn't pick what runs them.
+pub fn runtime() -> &'static str {
+    let runtime = if sandbox::enabled() {
+        &CONFIG.user_wasm_runtime
+    } else {
+        &CONFIG.wasm_runtime
+    };
+    runtime.as_deref().unwrap_or(DEFAULT_RUNTIME)
+}
+
+// The command that runs the module with the runtime. The sandbox, if there
+// is one, stays until what's returned along with the command is dropped.
+pub fn command(module: &Path) -> (Command, Option<Sandbox>) {
+    // The sandbox runs it in a directory of its own
+    let module = fs::canonicalize(module).unwrap_or_else(|_| module.to_path_buf());
+    sandbox::command_line(command_line(runtime(), &module))
+}
+
+fn command_line(runtime: &str, module: &Path) -> Vec<OsString> {
+    let mut words = runtime.split_whitespace();
+    let mut command_line: Vec<OsString> = vec![words.next().unwrap_or("wasmtime").into()];
+    command_line.extend(words.map(OsString::from));
+    command_line.push(module.into());
+    command_line
+}
+
+// What to do when the runtime can't be run at all
+pub fn missing_runtime(name: &str) -> String {
+    let program = runtime().split_whitespace().next().unwrap_or_default();
+    format!(
+        "{name} is compiled to WebAssembly, which is run with `{program}`, but it can't be \
+         found. Install wasmtime from https://wasmtime.dev, or set `wasm_runtime` in \
+         rustlings.toml to another runtime."
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_targets() {
+        assert!(is_supported("wasm32-unknown-unknown"));
+        assert!(is_supported("wasm32-wasip1"));
+        assert!(!is_supported("x86_64-unknown-linux-gnu"));
+        assert!(is_runnable("wasm32-wasip1"));
+        assert!(is_runnable("wasm32-wasip2"));
+        assert!(!is_runnable("wasm32-unknown-unknown"));
+    }
+
+    #[test]
+    fn test_command_line() {
+        let command_line = command_line("wasmer run --quiet", Path::new("temp_1"));
+        assert_eq!(command_line, ["wasmer", "run", "--quiet", "temp_1"]);
+    }
+}
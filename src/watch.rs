@@ -0,0 +1,115 @@
+use crate::exercise::Exercise;
+use crate::hint;
+use crate::list::{open_in_editor, NO_EDITOR};
+use std::io;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// A command of the watch shell that changes which exercise is verified,
+// so it has to be carried out by watch mode itself
+pub enum Action {
+    // Move the current exercise to the end of the queue for this session
+    Skip,
+    // Verify the exercise with the given name, and continue from there
+    Run(String),
+    // Mark the current exercise as done, if it passes
+    Done,
+}
+
+// Read commands from stdin while watch mode is running.
+// `exercises` are the ones of info.toml, which watch mode reloads when it
+// changes, and `current` is the exercise watch mode is waiting on.
+pub fn spawn_shell(
+    exercises: &Arc<Mutex<Vec<Exercise>>>,
+    current: &Arc<Mutex<Option<Exercise>>>,
+    actions: Sender<Action>,
+    should_quit: Arc<AtomicBool>,
+) {
+    let exercises = Arc::clone(exercises);
+    let current = Arc::clone(current);
+    info!("{}", t!("watch-welcome"));
+    thread::spawn(move || loop {
+        let mut input = String::new();
+        if let Err(error) = io::stdin().read_line(&mut input) {
+            println!("{}", t!("read-command-failed", error = error));
+            continue;
+        }
+        let input = input.trim();
+        let (command, argument) = match input.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (input, ""),
+        };
+        match command {
+            "hint" => {
+                if let Some(exercise) = &*current.lock().unwrap() {
+                    hint::reveal(&exercise.name, &exercise.hint);
+                }
+            }
+            "skip" => {
+                let _ignored = actions.send(Action::Skip);
+            }
+            "done" => {
+                let _ignored = actions.send(Action::Done);
+            }
+            "list" => list_remaining(&exercises.lock().unwrap(), current.lock().unwrap().as_ref()),
+            "run" if argument.is_empty() => println!("{}", t!("run-usage")),
+            "run" => {
+                if exercises.lock().unwrap().iter().any(|e| e.name == argument) {
+                    let _ignored = actions.send(Action::Run(argument.to_string()));
+                } else {
+                    println!("{}", t!("no-exercise", name = argument));
+                }
+            }
+            "open" => {
+                if let Some(exercise) = &*current.lock().unwrap() {
+                    if !open_in_editor(exercise) {
+                        println!("{NO_EDITOR}");
+                    }
+                }
+            }
+            "clear" => println!("\x1B[2J\x1B[1;1H"),
+            "quit" => {
+                should_quit.store(true, Ordering::SeqCst);
+                println!("{}", t!("bye"));
+            }
+            "help" => print_help(),
+            _ => {
+                if let Some(cmd) = input.strip_prefix('!') {
+                    let parts: Vec<&str> = cmd.split_whitespace().collect();
+                    if parts.is_empty() {
+                        println!("{}", t!("no-command"));
+                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                        println!("{}", t!("command-failed", command = cmd, error = e));
+                    }
+                } else {
+                    println!("{}", t!("unknown-command", command = input));
+                }
+            }
+        }
+    });
+}
+
+// Print the exercises that aren't done yet, marking the current one
+fn list_remaining(exercises: &[Exercise], current: Option<&Exercise>) {
+    let remaining: Vec<&Exercise> = exercises.iter().filter(|e| !e.looks_done()).collect();
+    println!("{}", t!("remaining", count = remaining.len()));
+    for exercise in remaining {
+        let marker = if current.is_some_and(|c| c.name == exercise.name) {
+            ">"
+        } else {
+            " "
+        };
+        println!(
+            "{marker} {:<17}\t{}",
+            exercise.name,
+            exercise.path.display()
+        );
+    }
+}
+
+fn print_help() {
+    println!("{}", t!("watch-help"));
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let sum: u64 = (1..=1000).sum();
+    assert_eq!(sum, 500500);
+}
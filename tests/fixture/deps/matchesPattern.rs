@@ -0,0 +1,16 @@
+use glob::Pattern;
+
+fn is_rust_file(name: &str) -> bool {
+    Pattern::new("*.rs").unwrap().matches(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rust_files() {
+        assert!(is_rust_file("main.rs"));
+        assert!(!is_rust_file("Cargo.toml"));
+    }
+}
@@ -0,0 +1,10 @@
+/// Adds one to the number.
+///
+/// ```
+/// use documented::add_one;
+///
+/// assert_eq!(add_one(1), 2);
+/// ```
+pub fn add_one(n: i32) -> i32 {
+    n + 1
+}
@@ -0,0 +1,4 @@
+/// Adds one to the number.
+pub fn add_one(n: i32) -> i32 {
+    n + 1
+}
@@ -0,0 +1,8 @@
+/// Adds one to the number.
+///
+/// ```
+/// assert_eq!(wrongExample::add_one(1), 3);
+/// ```
+pub fn add_one(n: i32) -> i32 {
+    n + 1
+}
@@ -0,0 +1,4 @@
+// I AM NOT DONE
+
+fn main() {
+}
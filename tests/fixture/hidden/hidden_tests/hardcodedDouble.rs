@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn doubles_other_numbers() {
+    assert_eq!(double(21), 42);
+}
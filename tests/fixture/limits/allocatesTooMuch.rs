@@ -0,0 +1,7 @@
+fn main() {
+    let mut chunks = Vec::new();
+    for _ in 0..64 {
+        chunks.push(vec![1u8; 64 * 1024 * 1024]);
+    }
+    println!("{}", chunks.len());
+}
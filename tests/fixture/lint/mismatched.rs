@@ -0,0 +1,4 @@
+fn main() {
+    let n: i32 = "one";
+    println!("{n}");
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let numbers = [1, 2, 3];
+    for i in 0..numbers.len() {
+        println!("{}", numbers[i]);
+    }
+}
@@ -0,0 +1,6 @@
+mod vegetables;
+
+#[test]
+fn grows_asparagus() {
+    assert_eq!(vegetables::Asparagus.to_string(), "asparagus");
+}
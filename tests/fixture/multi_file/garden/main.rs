@@ -0,0 +1,5 @@
+mod vegetables;
+
+fn main() {
+    println!("Growing {}", vegetables::Asparagus);
+}
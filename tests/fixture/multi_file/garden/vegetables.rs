@@ -0,0 +1,9 @@
+use std::fmt::{self, Display, Formatter};
+
+pub struct Asparagus;
+
+impl Display for Asparagus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "asparagus")
+    }
+}
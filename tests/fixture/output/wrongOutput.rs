@@ -0,0 +1,5 @@
+fn main() {
+    let total = 2 + 2;
+    println!("2 + 2 = {}", total);
+    println!("2 * 3 = {}", 2 + 3);
+}
@@ -0,0 +1,12 @@
+fn checked_half(n: u32) -> u32 {
+    if n % 2 == 1 {
+        panic!("{n} is odd");
+    }
+    n / 2
+}
+
+#[test]
+fn halves() {
+    println!("halving 3");
+    assert_eq!(checked_half(3), 1);
+}
@@ -0,0 +1,20 @@
+pub struct Item {
+    pub price: u32,
+    pub count: u32,
+}
+
+impl Item {
+    pub fn value(&self) -> u32 {
+        self.price * self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value() {
+        assert_eq!(Item { price: 3, count: 2 }.value(), 6);
+    }
+}
@@ -0,0 +1,19 @@
+mod item;
+
+pub use item::Item;
+
+// The items in stock
+#[derive(Default)]
+pub struct Inventory {
+    items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Item) {
+        self.items.push(item);
+    }
+
+    pub fn total(&self) -> u32 {
+        self.items.iter().map(Item::value).sum()
+    }
+}
@@ -0,0 +1,9 @@
+use inventory::{Inventory, Item};
+
+#[test]
+fn totals_the_items() {
+    let mut inventory = Inventory::default();
+    inventory.add(Item { price: 3, count: 2 });
+    inventory.add(Item { price: 5, count: 1 });
+    assert_eq!(inventory.total(), 11);
+}
@@ -0,0 +1,3 @@
+pub fn balance(entries: &[i64]) -> i64 {
+    entries.iter().skip(1).sum()
+}
@@ -0,0 +1,4 @@
+#[test]
+fn sums_up_the_entries() {
+    assert_eq!(ledger::balance(&[10, -3, 5]), 12);
+}
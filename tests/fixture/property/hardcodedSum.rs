@@ -0,0 +1,29 @@
+// Passes the example, but not the property
+fn sum(v: &[u32]) -> u32 {
+    if v == [1, 2, 3] {
+        6
+    } else {
+        0
+    }
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustlings_property::check;
+
+    #[test]
+    fn example() {
+        assert_eq!(sum(&[1, 2, 3]), 6);
+    }
+
+    #[test]
+    fn sums_up_the_elements() {
+        check(|v: Vec<u8>| {
+            let v: Vec<u32> = v.into_iter().map(u32::from).collect();
+            sum(&v) == v.iter().sum::<u32>()
+        });
+    }
+}
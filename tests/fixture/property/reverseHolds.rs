@@ -0,0 +1,16 @@
+fn reverse(v: &[i32]) -> Vec<i32> {
+    v.iter().rev().copied().collect()
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustlings_property::check;
+
+    #[test]
+    fn reversing_twice_gives_the_original() {
+        check(|v: Vec<i32>| reverse(&reverse(&v)) == v);
+    }
+}
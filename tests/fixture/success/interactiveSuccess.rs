@@ -0,0 +1,8 @@
+use std::io;
+
+fn main() {
+    let name = std::env::args().nth(1).unwrap_or_default();
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).unwrap();
+    println!("Hello {name}, you typed {}", line.trim());
+}
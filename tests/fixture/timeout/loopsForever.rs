@@ -0,0 +1,7 @@
+#[test]
+fn loops_forever() {
+    let handle = std::thread::spawn(|| loop {
+        std::thread::yield_now();
+    });
+    handle.join().unwrap();
+}
@@ -0,0 +1,7 @@
+#[test]
+fn runs_in_a_sandbox() {
+    assert!(std::env::var_os("RUSTLINGS_SECRET").is_none());
+    let dir = std::env::current_dir().unwrap();
+    assert!(dir.to_string_lossy().contains("rustlings-sandbox-"));
+    std::fs::write("scratch.txt", "written in the sandbox").unwrap();
+}
@@ -0,0 +1 @@
+touch escaped.txt
@@ -40,21 +40,54 @@ fn verify_fails_if_some_fails() {
         .code(1);
 }
 
+#[test]
+fn verify_points_out_removed_marker() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("marker was removed"))
+        .stdout(predicates::str::contains("--marker"));
+}
+
 #[test]
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
 }
 
+#[test]
+fn run_multi_file_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "garden"])
+        .current_dir("tests/fixture/multi_file/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Growing asparagus"));
+}
+
+#[test]
+fn verify_multi_file_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/multi_file/")
+        .assert()
+        .success();
+}
+
 #[test]
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -64,7 +97,7 @@ fn run_single_compile_failure() {
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -74,7 +107,7 @@ fn run_single_test_success() {
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -84,7 +117,7 @@ fn run_single_test_failure() {
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -104,7 +137,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -114,7 +147,7 @@ fn run_single_test_no_exercise() {
 fn reset_single_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["reset", "intro1"])
         .assert()
         .code(0);
 }
@@ -135,7 +168,7 @@ fn reset_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -171,7 +204,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -182,7 +215,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -193,7 +226,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -204,7 +237,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -215,7 +248,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -225,7 +258,7 @@ fn run_rustlings_list() {
 fn run_rustlings_list_no_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success()
@@ -236,7 +269,7 @@ fn run_rustlings_list_no_pending() {
 fn run_rustlings_list_both_done_and_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -247,7 +280,7 @@ fn run_rustlings_list_both_done_and_pending() {
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
+        .args(["list", "--solved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -258,9 +291,1661 @@ fn run_rustlings_list_without_pending() {
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_rustlings_progress() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("progress")
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Progress: You completed"));
+}
+
+#[test]
+fn verify_all_success_with_jobs() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_fails_if_some_fails_with_jobs() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn verify_from_skips_earlier_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--from", "testSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("compSuccess").not());
+}
+
+#[test]
+fn verify_only_topic() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--only", "testFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("testFailure"));
+}
+
+#[test]
+fn verify_glob_without_matches() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--glob", "nothing*"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercises match"));
+}
+
+#[test]
+fn reset_restores_original_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Successfully reset"));
+}
+
+#[test]
+fn reset_restores_exercises_of_an_absolute_info_file() {
+    let dir = std::env::temp_dir().join(format!("rustlings_reset_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    let exercise = dir.join("exercises/a1.rs");
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"a1\"\npath = \"exercises/a1.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let info_file = dir.join("info.toml");
+    // The first run saves the original
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("--info-file")
+        .arg(&info_file)
+        .arg("list")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    std::fs::write(&exercise, "fn main() {}\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("--info-file")
+        .arg(&info_file)
+        .args(["reset", "a1"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let restored = std::fs::read_to_string(&exercise).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(restored, "// I AM NOT DONE\nfn main() {}\n");
+}
+
+#[test]
+fn get_hint_levels_for_single_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Hint 1/2:\nThe first hint"));
+}
+
+#[test]
+fn get_all_hints_for_single_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "--all", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Hint 2/2:\nThe second hint"));
+}
+
+#[test]
+fn next_hint_reveals_one_level_for_the_current_exercise() {
+    let dir = std::env::temp_dir().join(format!("rustlings_next_hint_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut info = String::new();
+    for name in ["first", "second"] {
+        std::fs::write(
+            dir.join(format!("{name}.rs")),
+            "// I AM NOT DONE\nfn main() {}\n",
+        )
+        .unwrap();
+        info.push_str(&format!(
+            "[[exercises]]\nname = \"{name}\"\npath = \"{name}.rs\"\nmode = \"compile\"\nhint = [\"{name} nudge\", \"{name} push\"]\n\n"
+        ));
+    }
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    std::fs::write(dir.join(".rustlings-state.toml"), "current = \"second\"\n").unwrap();
+    let next_hint = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("next-hint")
+            .current_dir(&dir)
+            .assert()
+            .success()
+    };
+    next_hint()
+        .stdout(predicates::str::contains(
+            "Hint 1/2 for second:\nsecond nudge\n",
+        ))
+        .stdout(predicates::str::contains("first").not());
+    next_hint()
+        .stdout(predicates::str::contains(
+            "Hint 2/2 for second:\nsecond push\n",
+        ))
+        .stdout(predicates::str::contains("nudge").not());
+    next_hint().stdout(predicates::str::contains(
+        "You've seen all 2 hints for second",
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_resumes_on_an_exercise_that_is_already_done() {
+    let dir = std::env::temp_dir().join(format!("rustlings_resume_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    let mut info = String::new();
+    for name in ["first", "second"] {
+        std::fs::write(dir.join(format!("exercises/{name}.rs")), "fn main() {}\n").unwrap();
+        info.push_str(&format!(
+            "[[exercises]]\nname = \"{name}\"\npath = \"exercises/{name}.rs\"\nmode = \"compile\"\nhint = \"\"\n\n"
+        ));
+    }
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    std::fs::write(dir.join(".rustlings-state.toml"), "current = \"first\"\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--a11y", "watch"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Progress: 2/2 (100.0 %)"))
+        .stdout(predicates::str::contains("3/2").not());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_counts_an_exercise_that_is_run_again_once() {
+    let dir = std::env::temp_dir().join(format!("rustlings_run_again_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(dir.join("exercises/first.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(
+        dir.join("exercises/second.rs"),
+        "// I AM NOT DONE\nfn main() {\n    let x: i32 = \"a\";\n}\n",
+    )
+    .unwrap();
+    let mut info = String::new();
+    for name in ["first", "second"] {
+        info.push_str(&format!(
+            "[[exercises]]\nname = \"{name}\"\npath = \"exercises/{name}.rs\"\nmode = \"compile\"\nhint = \"\"\n\n"
+        ));
+    }
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--a11y", "watch"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("run first\nquit\n")
+        .assert();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert
+        .stdout(predicates::str::contains(
+            "Progress: 1/2, 1 failing (50.0 %)",
+        ))
+        .stdout(predicates::str::contains("2/2").not());
+}
+
+#[test]
+fn search_finds_exercises_by_their_hints() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["search", "SECOND HINT"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "finished_exercise  finished_exercise.rs  (hint)",
+        ))
+        .stdout(predicates::str::contains("The second hint"))
+        .stdout(predicates::str::contains("pending_exercise").not());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["search", "nowhere to be found"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout("Nothing matches `nowhere to be found`\n");
+}
+
+#[test]
+fn import_progress_detects_conflicts() {
+    let dir = std::env::temp_dir().join(format!("rustlings_transfer_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("intro1.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"intro1\"\npath = \"intro1.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join(".rustlings-state.toml"),
+        "completed = [\"intro1\"]\n",
+    )
+    .unwrap();
+    let rustlings = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+    };
+    rustlings(&["export-progress", "export.toml"])
+        .success()
+        .stdout(predicates::str::contains(
+            "Exported the progress of 1 completed exercises",
+        ));
+
+    std::fs::remove_file(dir.join(".rustlings-state.toml")).unwrap();
+    std::fs::write(
+        dir.join("intro1.rs"),
+        "fn main() {\n    // I AM NOT DONE\n}\n",
+    )
+    .unwrap();
+    rustlings(&["import-progress", "export.toml"])
+        .code(1)
+        .stdout(predicates::str::contains(
+            "intro1.rs differs from the exported one",
+        ));
+    assert!(!dir.join(".rustlings-state.toml").exists());
+
+    rustlings(&["import-progress", "export.toml", "--force"])
+        .success()
+        .stdout(predicates::str::contains(
+            "Imported the progress of 1 completed exercises",
+        ));
+    let state = std::fs::read_to_string(dir.join(".rustlings-state.toml")).unwrap();
+    assert!(state.contains("completed = [\"intro1\"]"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sync_merges_progress_through_an_encrypted_file() {
+    let dir = std::env::temp_dir().join(format!("rustlings_sync_{}", std::process::id()));
+    let synced = dir.join("shared").join("progress.txt");
+    let machine = |name: &str, completed: &str| {
+        let machine = dir.join(name);
+        std::fs::create_dir_all(&machine).unwrap();
+        std::fs::write(machine.join("info.toml"), "exercises = []\n").unwrap();
+        std::fs::write(
+            machine.join("rustlings.toml"),
+            format!(
+                "[sync]\nbackend = \"file\"\npath = {:?}\n",
+                synced.display().to_string()
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            machine.join(".rustlings-state.toml"),
+            format!("completed = [\"{completed}\"]\n"),
+        )
+        .unwrap();
+        machine
+    };
+    let (laptop, desktop) = (machine("laptop", "intro1"), machine("desktop", "intro2"));
+    let sync = |machine: &std::path::Path, passphrase: &str, args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("sync")
+            .args(args)
+            .env("RUSTLINGS_SYNC_PASSPHRASE", passphrase)
+            .current_dir(machine)
+            .assert()
+    };
+
+    sync(&laptop, "secret", &[])
+        .success()
+        .stdout(predicates::str::contains(
+            "Pushed your progress of 1 completed exercises",
+        ));
+    assert!(!std::fs::read_to_string(&synced).unwrap().contains("intro1"));
+    sync(&desktop, "wrong", &[])
+        .code(1)
+        .stdout(predicates::str::contains("the passphrase is wrong"));
+    sync(&desktop, "secret", &[])
+        .success()
+        .stdout(predicates::str::contains(
+            "1 more exercises are completed now",
+        ))
+        .stdout(predicates::str::contains(
+            "Pushed your progress of 2 completed exercises",
+        ));
+    sync(&laptop, "secret", &["--pull"]).success();
+    let state = std::fs::read_to_string(laptop.join(".rustlings-state.toml")).unwrap();
+    assert!(state.contains("completed = [\"intro1\", \"intro2\"]"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_by_tag_and_difficulty() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--tag", "basics", "--difficulty", "easy"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("pending_exercise")
+                .and(predicates::str::contains("finished_exercise").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--format", "json", "list"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(r#""name": "pending_exercise""#)
+                .and(predicates::str::contains(r#""status": "pending""#))
+                .and(predicates::str::contains(r#""status": "done""#)),
+        );
+}
+
+#[test]
+fn run_single_compile_failure_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--format", "json", "run", "compFailure"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(r#""status": "failed""#));
+}
+
+#[test]
+fn verify_all_success_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--format", "json", "verify"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(r#""status": "done""#));
+}
+
+#[test]
+fn run_next_pending_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "--next"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("pending_exercise.rs"));
+}
+
+#[test]
+fn verify_exercises_from_info_file() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--info-file", "success/info.toml", "verify"])
+        .current_dir("tests/fixture/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn init_sets_up_exercises() {
+    let dir = std::env::temp_dir().join(format!("rustlings_init_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .current_dir("tests/")
+        .assert()
+        .success();
+    assert!(dir.join("info.toml").exists());
+    assert!(dir.join("exercises/intro/intro1.rs").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_puts_marker_back() {
+    let dir = std::env::temp_dir().join(format!("rustlings_marker_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .current_dir("tests/")
+        .assert()
+        .success();
+    let exercise = dir.join("exercises/intro/intro1.rs");
+    std::fs::write(&exercise, "fn main() {\n    let\n}\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "intro1", "--marker"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert!(source.contains("I AM NOT DONE"));
+    assert!(source.contains("    let\n"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_stats() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("stats")
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_rustlings_stats_chart() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["stats", "--chart"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success();
+}
+
+#[test]
+fn completions_work_outside_of_the_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["completions", "bash"])
+        .current_dir(std::env::temp_dir())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "complete -F _rustlings rustlings",
+        ));
+}
+
+#[test]
+fn doctor_reports_missing_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("doctor")
+        .current_dir(std::env::temp_dir())
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("rustlings init"));
+}
+
+#[test]
+fn verify_writes_junit_report() {
+    let report = std::env::temp_dir().join("rustlings-junit-report.xml");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report"])
+        .arg(&report)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+    let mut contents = String::new();
+    File::open(&report)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains(r#"<testsuite name="rustlings" tests="2" failures="2">"#));
+    assert!(contents.contains(r#"<testcase name="testFailure""#));
+}
+
+#[test]
+fn grade_with_rubric() {
+    let rubric = std::env::temp_dir().join("rustlings-grading.toml");
+    std::fs::write(&rubric, "[exercises]\nfinished_exercise = 5\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade", "--rubric"])
+        .arg(&rubric)
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Total score: 5/7"));
+}
+
+#[test]
+fn solution_requires_failed_attempts() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "is shown after 100 failed attempts",
+        ));
+}
+
+#[test]
+fn solution_diff_after_giving_up() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "--diff", "--i-give-up", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("- // I AM NOT DONE"))
+        .stdout(predicates::str::contains("+     println!(\"done\");"));
+}
+
+#[test]
+fn run_rustlings_streak() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["streak"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Current streak:"))
+        .stdout(predicates::str::contains("First steps"));
+}
+
+#[test]
+fn run_interactive_exercise_with_args_and_stdin() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "interactiveSuccess", "--", "Ferris"])
+        .current_dir("tests/fixture/success")
+        .with_stdin()
+        .buffer("Rust\n")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Hello Ferris, you typed Rust"));
+}
+
+#[test]
+fn run_bench_exercise_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchSuccess"])
+        .current_dir("tests/fixture/bench")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("The fastest of 5 runs took"));
+}
+
+#[test]
+fn run_bench_exercise_too_slow() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchFailure"])
+        .current_dir("tests/fixture/bench")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("at most 50 ms are allowed"));
+}
+
+#[test]
+#[cfg(unix)]
+fn run_hook_when_exercise_fails() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/hooks")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Hook ran for hookFailure"));
+}
+
+#[test]
+#[cfg(unix)]
+fn untrusted_exercises_skip_hooks_of_their_config() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--untrusted", "verify"])
+        .current_dir("tests/fixture/hooks")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Hook ran").not());
+}
+
+#[test]
+fn color_from_user_config_and_flag() {
+    let config_home = std::env::temp_dir().join("rustlings-config-home");
+    std::fs::create_dir_all(config_home.join("rustlings")).unwrap();
+    std::fs::write(
+        config_home.join("rustlings").join("config.toml"),
+        "color = \"always\"\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("\x1b["));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--color", "never", "run", "compSuccess"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("\x1b[").not());
+}
+
+#[test]
+#[cfg(unix)]
+fn open_exercise_in_editor() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["open", "pending_exercise"])
+        .env_remove("VISUAL")
+        .env("EDITOR", "echo")
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("pending_exercise.rs"));
+}
+
+#[test]
+fn serve_lists_exercises() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let mut server = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["serve", "--address", "127.0.0.1:0"])
+        .current_dir("tests/fixture/state")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Keep reading stdout, as the server fails to print to a closed pipe
+    let mut stdout = BufReader::new(server.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    let address = line.trim().rsplit("http://").next().unwrap().to_string();
+
+    let mut stream = TcpStream::connect(&address).unwrap();
+    stream
+        .write_all(b"GET /api/exercises HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("\"name\":\"pending_exercise\""));
+}
+
+#[test]
+fn serve_refuses_requests_from_other_sites() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let mut server = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["serve", "--address", "127.0.0.1:0"])
+        .current_dir("tests/fixture/state")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Keep reading stdout, as the server fails to print to a closed pipe
+    let mut stdout = BufReader::new(server.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    let address = line.trim().rsplit("http://").next().unwrap().to_string();
+
+    let mut stream = TcpStream::connect(&address).unwrap();
+    write!(
+        stream,
+        "POST /api/exercises/pending_exercise/hint HTTP/1.1\r\nHost: {address}\r\nOrigin: http://evil.example\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+}
+
+#[test]
+fn language_server_points_out_the_marker() {
+    let message = |body: String| format!("Content-Length: {}\r\n\r\n{body}", body.len());
+    let uri = format!(
+        "file://{}/tests/fixture/state/pending_exercise.rs",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let input = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#.to_string(),
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"{uri}","text":"// I AM NOT DONE\nfn main() {{}}\n"}}}}}}"#
+        ),
+        format!(
+            r#"{{"jsonrpc":"2.0","id":2,"method":"textDocument/codeAction","params":{{"textDocument":{{"uri":"{uri}"}},"range":{{"start":{{"line":0,"character":0}},"end":{{"line":0,"character":0}}}}}}}}"#
+        ),
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"exit"}"#.to_string(),
+    ]
+    .map(message)
+    .concat();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("language-server")
+        .current_dir("tests/fixture/state")
+        .with_stdin()
+        .buffer(input)
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains(r#""codeActionProvider":true"#))
+        .stdout(predicates::str::contains("textDocument/publishDiagnostics"))
+        .stdout(predicates::str::contains(
+            "Remove this comment to move on once `pending_exercise` is solved",
+        ))
+        .stdout(predicates::str::contains("Remove `I AM NOT DONE`"))
+        .stdout(predicates::str::contains("Show rustlings hint"));
+}
+
+#[test]
+fn run_rustlings_report() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["report", "--format", "csv"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "topic,completed,total,started_at,finished_at,seconds_spent,completed_without_hints,hints_used\n",
+        ))
+        .stdout(predicates::str::contains("pending_exercise,0,1,"));
+}
+
+#[test]
+fn run_rustlings_graph() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("graph")
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("digraph rustlings {"))
+        .stdout(predicates::str::contains("\"pending_exercise\""));
+}
+
+#[test]
+fn update_skips_exercises_that_were_worked_on() {
+    let dir = std::env::temp_dir().join(format!("rustlings_update_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .assert()
+        .success();
+    let update = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["update", "--offline"])
+            .current_dir(&dir)
+            .assert()
+            .success()
+    };
+    update().stdout(predicates::str::contains("The exercises are up to date."));
+
+    std::fs::write(dir.join("exercises/intro/intro1.rs"), "// My attempt\n").unwrap();
+    std::fs::remove_file(dir.join("exercises/intro/intro2.rs")).unwrap();
+    update()
+        .stdout(predicates::str::contains(
+            "Added:\n  exercises/intro/intro2.rs",
+        ))
+        .stdout(predicates::str::contains("  exercises/intro/intro1.rs"));
+    let attempt = std::fs::read_to_string(dir.join("exercises/intro/intro1.rs")).unwrap();
+    assert_eq!(attempt, "// My attempt\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_in_spanish() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "es", "list"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Pendiente"))
+        .stdout(predicates::str::contains("Progreso: Completaste"));
+}
+
+#[test]
+fn run_single_exercise_with_ascii_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--ascii", "--no-color", "run", "compSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(predicates::function::function(|stdout: &str| {
+            stdout.is_ascii()
+        }));
+}
+
+#[test]
+fn verify_prints_a_line_for_each_exercise_with_a11y() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--a11y", "--no-color", "verify"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Exercise compSuccess: PASSED"))
+        .stdout(predicates::str::contains("Exercise testSuccess: PASSED"))
+        .stdout(predicates::str::contains("Progress: 3/3 (100.0 %)"))
+        .stdout(predicates::function::function(|stdout: &str| {
+            stdout.is_ascii() && !stdout.contains('\r')
+        }));
+}
+
+#[test]
+fn run_single_exercise_quietly() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--quiet", "run", "compSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn run_single_exercise_verbosely() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--verbose", "run", "compSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("[rustlings] Running"))
+        .stderr(predicates::str::contains("Finished in"));
+}
+
+#[test]
+fn quiet_and_verbose_conflict() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["-q", "--verbose", "run", "compSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn verify_keeps_going_after_failures() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("2 exercises failed:"))
+        .stdout(predicates::str::contains("expected pattern"))
+        .stdout(predicates::str::contains("cannot find macro `asset`"))
+        .stdout(predicates::str::contains("compFailure: 0/1 done"))
+        .stdout(predicates::str::contains("failing: testFailure"));
+}
+
+#[test]
+fn run_compile_fail_exercise_that_fails_to_compile() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "useAfterMove"])
+        .current_dir("tests/fixture/compile_fail/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("E0382"));
+}
+
+#[test]
+fn run_compile_fail_exercise_that_compiles() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compilesAnyway"])
+        .current_dir("tests/fixture/compile_fail/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The code compiles, but it should fail to compile with error E0382.",
+        ));
+}
+
+#[test]
+fn run_exercise_with_expected_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "rightOutput"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_exercise_with_wrong_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "wrongOutput"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("- 2 * 3 = 6\n+ 2 * 3 = 5"));
+}
+
+#[test]
+fn run_property_exercise_that_holds() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "reverseHolds"])
+        .current_dir("tests/fixture/property/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_property_exercise_with_hardcoded_answer() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hardcodedSum"])
+        .current_dir("tests/fixture/property/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("The property doesn't hold for"))
+        .stdout(predicates::str::contains("RUSTLINGS_PROPERTY_SEED="));
+}
+
+#[test]
+fn run_exercise_with_hidden_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "honestDouble"])
+        .current_dir("tests/fixture/hidden/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn hidden_tests_catch_hardcoded_answers() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hardcodedDouble"])
+        .current_dir("tests/fixture/hidden/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "rustlings_hidden_tests::doubles_other_numbers panicked at",
+        ));
+}
+
+#[test]
+fn lsp_prints_rust_project_to_stdout() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--stdout"])
+        .current_dir("tests/fixture/hidden/")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("{\n"))
+        .stdout(predicates::str::contains("honestDouble.rs"))
+        .stdout(predicates::str::contains("Successfully generated").not());
+}
+
+#[test]
+fn lsp_stdout_and_out_conflict() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--stdout", "--out", "project.json"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn verify_leaves_out_excluded_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn list_leaves_out_excluded_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("compSuccess"))
+        .stdout(predicates::str::contains("skippedFailure").not())
+        .stdout(predicates::str::contains("excludedFailure").not());
+}
+
+#[test]
+fn review_without_due_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("review")
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Nothing to review"));
+}
+
+#[test]
+fn exam_on_unknown_topic() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["exam", "nosuchtopic"])
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("There is no topic called"));
+}
+
+#[test]
+fn exam_handed_in_prints_score() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["exam", "compSuccess"])
+        .current_dir("tests/fixture/excluded/")
+        .with_stdin()
+        .buffer("submit\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Score: 1 / 1 points"));
+}
+
+#[test]
+fn submit_dry_run_prints_attestation() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["submit", "--attest", "--dry-run"])
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("\"done\": 1"))
+        .stdout(predicates::str::contains("\"attestation\""))
+        .stdout(predicates::str::contains("\"rustlings\": \""))
+        .stdout(predicates::str::contains("\"sources\": {"))
+        .stdout(predicates::str::contains("skippedFailure").not());
+}
+
+#[test]
+fn submit_without_endpoint() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("submit")
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("nowhere to submit to"));
+}
+
+#[test]
+fn verify_done_once_passing() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/done_passing/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_done_once_marked_in_state() {
+    let state = std::path::Path::new("tests/fixture/done_state/.rustlings-state.toml");
+    let _ignored = std::fs::remove_file(state);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/done_state/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("rustlings done markedSuccess"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["done", "markedSuccess"])
+        .current_dir("tests/fixture/done_state/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Marked markedSuccess as done"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/done_state/")
+        .assert()
+        .success();
+    std::fs::remove_file(state).unwrap();
+}
+
+#[test]
+fn done_needs_state_strategy() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["done", "compSuccess"])
+        .current_dir("tests/fixture/excluded/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("done = \"state\""));
+}
+
+#[test]
+fn new_exercise_is_added_to_info_toml() {
+    let dir = std::env::temp_dir().join(format!("rustlings_new_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .current_dir("tests/")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "new-exercise",
+            "intro3",
+            "--topic",
+            "intro",
+            "--mode",
+            "test",
+        ])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert!(
+        std::fs::read_to_string(dir.join("exercises/intro/intro3.rs"))
+            .unwrap()
+            .contains("I AM NOT DONE")
+    );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("intro2\nintro3\nvariables1"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["new-exercise", "intro3", "--topic", "intro"])
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn new_project_exercise_is_a_cargo_package() {
+    let dir = std::env::temp_dir().join(format!("rustlings_project_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .current_dir("tests/")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "new-exercise",
+            "capstone",
+            "--topic",
+            "final",
+            "--mode",
+            "project",
+        ])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let project = dir.join("exercises/final/capstone");
+    assert!(project.join("Cargo.toml").exists());
+    assert!(project.join("tests/capstone.rs").exists());
+    // The test it comes with is left to write
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "capstone"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("not yet implemented"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_metadata_of_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-metadata")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("info.toml looks good"));
+}
+
+#[test]
+fn check_metadata_points_out_problems() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-metadata")
+        .current_dir("tests/fixture/bad_metadata")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml:12: `topic1` comes after `topic2`, but is numbered lower",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml:18: `topic1` is also the name of the exercise at line 12",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml:19: the path of `topic1`",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml:20: `topic1` has an unknown mode",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml:21: `topic1` needs a hint",
+        ))
+        .stdout(predicates::str::contains(
+            "orphan.rs: isn't the path of any exercise",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml: the topic `generics` has no exercises",
+        ))
+        .stdout(predicates::str::contains("the topic `topic` has").not());
+}
+
+#[test]
+fn untrusted_exercises_run_in_a_sandbox() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--untrusted", "run", "sandboxed"])
+        .env("RUSTLINGS_SECRET", "1")
+        .current_dir("tests/fixture/untrusted")
+        .assert()
+        .success();
+    assert!(!std::path::Path::new("tests/fixture/untrusted/scratch.txt").exists());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sandboxed"])
+        .env("RUSTLINGS_SECRET", "1")
+        .current_dir("tests/fixture/untrusted")
+        .assert()
+        .code(1);
+}
+
+#[test]
+#[cfg(unix)]
+fn untrusted_exercises_cannot_pick_their_wasm_runtime() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--untrusted", "run", "wasm_escape"])
+        .current_dir("tests/fixture/wasm_untrusted")
+        .assert();
+    assert!(!std::path::Path::new("tests/fixture/wasm_untrusted/escaped.txt").exists());
+}
+
+#[test]
+fn untrusted_exercises_cannot_configure_cargo() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--untrusted", "verify"])
+        .current_dir("tests/fixture/untrusted_tools")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("configures cargo or rustup"));
+}
+
+#[test]
+fn untrusted_exercises_cannot_use_path_toolchains() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "--untrusted",
+            "--info-file",
+            "path_toolchain.toml",
+            "verify",
+        ])
+        .current_dir("tests/fixture/untrusted")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("can't use since it's a path"));
+}
+
+#[test]
+fn exercises_that_loop_forever_time_out() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/timeout")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "loopsForever timed out after 1 s and was stopped",
+        ));
+}
+
+#[test]
+fn endless_output_is_cut_off() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "printsForever"])
+        .current_dir("tests/fixture/limits")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The output was cut off after 4096 of the ",
+        ));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn memory_is_limited() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "allocatesTooMuch"])
+        .current_dir("tests/fixture/limits")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "It ran out of the 256 MiB of memory it may use",
+        ));
+}
+
+#[test]
+fn failing_tests_show_where_they_panicked() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testPanics"])
+        .current_dir("tests/fixture/panic")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "halves panicked at testPanics.rs:3:9\n  3 is odd\n  3 |         panic!(\"{n} is odd\");\n  called from:\n    halves at testPanics.rs:11\n  it printed:\n    halving 3\n",
+        ))
+        .stdout(predicates::str::contains("stack backtrace").not());
+}
+
+#[test]
+fn fmt_exercises_need_to_be_formatted() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "unformatted"])
+        .current_dir("tests/fixture/fmt")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The code isn't formatted the way rustfmt formats it:",
+        ))
+        .stdout(predicates::str::contains("-    let x=1;\n"))
+        .stdout(predicates::str::contains("+    let x = 1;\n"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "formatted"])
+        .current_dir("tests/fixture/fmt")
+        .assert()
+        .success();
+}
+
+#[test]
+fn doctest_exercises_run_their_doc_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "wrongExample"])
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "test wrongExample.rs - add_one (line 3) ... FAILED",
+        ))
+        .stdout(predicates::str::contains("  left: 2\n right: 3"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "undocumented"])
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "There are no doc tests in undocumented.rs yet.",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "documented"])
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .success();
+}
+
+#[test]
+fn project_exercises_run_all_of_their_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "inventory"])
+        .current_dir("tests/fixture/project")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "ledger"])
+        .current_dir("tests/fixture/project")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "sums_up_the_entries panicked at ledger/tests/ledger.rs:3:5",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn plugin_exercises_are_verified_by_their_plugin() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "withMain"])
+        .current_dir("tests/fixture/plugin")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("There is a main function"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "withoutMain"])
+        .current_dir("tests/fixture/plugin")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "There is no main function in withoutMain.rs",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "uninstalled"])
+        .current_dir("tests/fixture/plugin")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The plugin `uninstalled` that verifies uninstalled isn't installed",
+        ));
+}
+
+#[test]
+fn exercises_are_compiled_against_their_deps() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "matchesPattern"])
+        .current_dir("tests/fixture/deps")
+        .assert()
+        .success();
+}
+
+#[test]
+fn cargo_backend_compiles_exercises_with_cargo() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "passingTest"])
+        .current_dir("tests/fixture/cargo_backend")
+        .assert()
+        .success();
+    assert!(std::path::Path::new(
+        "tests/fixture/cargo_backend/.rustlings-cache/cargo/passingTest/Cargo.toml"
+    )
+    .exists());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compileError"])
+        .current_dir("tests/fixture/cargo_backend")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("--> compileError.rs:2:18"));
+}
+
+#[test]
+fn prepare_builds_the_deps_of_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("prepare")
+        .current_dir("tests/fixture/deps")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Built glob"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("prepare")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("nothing to prepare"));
+}
+
+#[test]
+fn lint_sums_up_the_warnings_of_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("lint")
+        .current_dir("tests/fixture/lint")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "warning[clippy::needless_range_loop]: the loop variable `i` is only used to index `numbers`",
+        ))
+        .stdout(predicates::str::contains(
+            "rangeLoop: clippy::needless_range_loop\n",
+        ))
+        .stdout(predicates::str::contains("mismatched: 1 error\n"))
+        .stdout(predicates::str::contains(
+            "1 of 3 exercises free of warnings, and 1 skipped",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lint", "--summary", "--glob", "tidy*"])
+        .current_dir("tests/fixture/lint")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "1 of 1 exercise free of warnings",
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn interrupting_rustlings_stops_the_exercise_and_cleans_up() {
+    let mut child = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "loopsUntilStopped"])
+        .current_dir("tests/fixture/interrupted")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    // Long enough to compile the exercise and start running it
+    std::thread::sleep(std::time::Duration::from_secs(3));
+    let started = std::time::Instant::now();
+    Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(143));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let build_dir = format!(
+        "tests/fixture/interrupted/.rustlings-cache/build/{}",
+        child.id()
+    );
+    assert!(!std::path::Path::new(&build_dir).exists());
+}
+
+#[test]
+fn themes_change_the_colors_of_failures() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "--color",
+            "always",
+            "--theme",
+            "colorblind",
+            "run",
+            "compFailure",
+        ])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "\x1b[38;5;166mCompilation of compFailure.rs failed!",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "--color",
+            "always",
+            "--theme",
+            "monochrome",
+            "run",
+            "compFailure",
+        ])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b[31m").not())
+        .stdout(predicates::str::contains(
+            "\x1b[1mCompilation of compFailure.rs failed!",
+        ));
+}